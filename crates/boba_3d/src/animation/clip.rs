@@ -0,0 +1,180 @@
+use glam::{Quat, Vec3};
+use indexmap::IndexMap;
+
+use super::Keyframes;
+
+/// The keyframes that animate a single transform.
+#[derive(Debug, Default, Clone)]
+pub struct TransformTrack {
+    pub position: Option<Keyframes<Vec3>>,
+    pub rotation: Option<Keyframes<Quat>>,
+    pub scale: Option<Keyframes<Vec3>>,
+}
+
+/// The local transform values of a [`TransformTrack`] at a point in time.
+///
+/// Values without keyframes are `None`, and should be left untouched.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TransformSample {
+    pub position: Option<Vec3>,
+    pub rotation: Option<Quat>,
+    pub scale: Option<Vec3>,
+}
+
+impl TransformTrack {
+    /// Gets the time of the last keyframe in the track.
+    pub fn duration(&self) -> f32 {
+        let position = self.position.as_ref().map(|k| k.duration());
+        let rotation = self.rotation.as_ref().map(|k| k.duration());
+        let scale = self.scale.as_ref().map(|k| k.duration());
+        [position, rotation, scale]
+            .into_iter()
+            .flatten()
+            .fold(0., f32::max)
+    }
+
+    pub fn sample(&self, time: f32) -> TransformSample {
+        TransformSample {
+            position: self.position.as_ref().map(|k| k.sample(time)),
+            rotation: self.rotation.as_ref().map(|k| k.sample(time)),
+            scale: self.scale.as_ref().map(|k| k.sample(time)),
+        }
+    }
+}
+
+/// A named animation, made of transform tracks that each target a named node.
+///
+/// Node names are bound to actual transforms by an [`AnimationPlayer`](crate::pearls::AnimationPlayer).
+#[derive(Debug, Clone)]
+pub struct AnimationClip {
+    name: String,
+    duration: f32,
+    tracks: IndexMap<String, TransformTrack>,
+}
+
+impl AnimationClip {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.into(),
+            duration: 0.,
+            tracks: Default::default(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the time of the last keyframe in the clip.
+    pub fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    /// Sets the position keyframes for the node named `target`.
+    pub fn set_position(&mut self, target: &str, keyframes: Keyframes<Vec3>) {
+        self.duration = self.duration.max(keyframes.duration());
+        self.tracks.entry(target.into()).or_default().position = Some(keyframes);
+    }
+
+    /// Sets the rotation keyframes for the node named `target`.
+    pub fn set_rotation(&mut self, target: &str, keyframes: Keyframes<Quat>) {
+        self.duration = self.duration.max(keyframes.duration());
+        self.tracks.entry(target.into()).or_default().rotation = Some(keyframes);
+    }
+
+    /// Sets the scale keyframes for the node named `target`.
+    pub fn set_scale(&mut self, target: &str, keyframes: Keyframes<Vec3>) {
+        self.duration = self.duration.max(keyframes.duration());
+        self.tracks.entry(target.into()).or_default().scale = Some(keyframes);
+    }
+
+    /// Removes all keyframes for the node named `target`.
+    pub fn remove_track(&mut self, target: &str) -> Option<TransformTrack> {
+        let track = self.tracks.shift_remove(target);
+        self.duration = self
+            .tracks
+            .values()
+            .map(|t| t.duration())
+            .fold(0., f32::max);
+        track
+    }
+
+    /// Gets the track for the node named `target`.
+    pub fn track(&self, target: &str) -> Option<&TransformTrack> {
+        self.tracks.get(target)
+    }
+
+    /// Iterates over all tracks and the names of the nodes they target.
+    pub fn tracks(&self) -> impl Iterator<Item = (&str, &TransformTrack)> {
+        self.tracks.iter().map(|(k, v)| (k.as_str(), v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{Quat, Vec3};
+
+    use crate::animation::{Interpolation, Keyframes};
+
+    use super::AnimationClip;
+
+    #[test]
+    fn sample_keyframes() {
+        let times = vec![0., 1., 3.];
+        let values = vec![Vec3::ZERO, Vec3::X, Vec3::Y];
+        let linear = Keyframes::new(times.clone(), values.clone(), Interpolation::Linear).unwrap();
+        assert!(linear.sample(-1.) == Vec3::ZERO);
+        assert!(linear.sample(0.5) == Vec3::X * 0.5);
+        assert!(linear.sample(2.).abs_diff_eq(Vec3::new(0.5, 0.5, 0.), 1e-5));
+        assert!(linear.sample(10.) == Vec3::Y);
+
+        let step = Keyframes::new(times, values, Interpolation::Step).unwrap();
+        assert!(step.sample(0.99) == Vec3::ZERO);
+        assert!(step.sample(1.) == Vec3::X);
+
+        // flat tangents ease in and out, but still pass through the keyframes
+        let cubic = Keyframes::new(
+            vec![0., 2.],
+            vec![
+                Vec3::ZERO,
+                Vec3::ZERO,
+                Vec3::ZERO,
+                Vec3::ZERO,
+                Vec3::X,
+                Vec3::ZERO,
+            ],
+            Interpolation::CubicSpline,
+        )
+        .unwrap();
+        assert!(cubic.sample(1.).abs_diff_eq(Vec3::X * 0.5, 1e-5));
+        assert!(cubic.sample(0.5).x < 0.25);
+        assert!(cubic.sample(2.) == Vec3::X);
+
+        assert!(Keyframes::new(vec![0., 1.], vec![Vec3::ZERO], Interpolation::Linear).is_err());
+    }
+
+    #[test]
+    fn clip_tracks() {
+        let mut clip = AnimationClip::new("wave");
+        let position = Keyframes::new(
+            vec![0., 2.],
+            vec![Vec3::ZERO, Vec3::Y],
+            Interpolation::Linear,
+        );
+        let rotation = Keyframes::new(
+            vec![0., 4.],
+            vec![Quat::IDENTITY, Quat::from_rotation_y(1.)],
+            Interpolation::Linear,
+        );
+        clip.set_position("arm", position.unwrap());
+        clip.set_rotation("hand", rotation.unwrap());
+        assert!(clip.duration() == 4.);
+
+        let sample = clip.track("arm").unwrap().sample(1.);
+        assert!(sample.position == Some(Vec3::Y * 0.5));
+        assert!(sample.rotation.is_none());
+
+        clip.remove_track("hand");
+        assert!(clip.duration() == 2.);
+    }
+}
@@ -0,0 +1,147 @@
+use std::path::Path;
+
+use glam::{Quat, Vec3};
+use gltf::animation::util::ReadOutputs;
+use log::warn;
+use thiserror::Error;
+
+use super::{AnimationClip, Interpolation, KeyframeError, Keyframes};
+
+/// An error returned when loading animations from a glTF file.
+#[derive(Debug, Error)]
+pub enum GltfAnimationError {
+    #[error("Could not import glTF file. Error: {0}")]
+    Import(gltf::Error),
+    #[error("Animation channel {channel} of '{animation}' is missing its keyframe data")]
+    MissingData { animation: String, channel: usize },
+    #[error("Animation channel {channel} of '{animation}' has invalid keyframes. Error: {error}")]
+    Keyframes {
+        animation: String,
+        channel: usize,
+        error: KeyframeError,
+    },
+}
+
+/// Gets the name used to target a glTF node in an [`AnimationClip`].
+///
+/// Unnamed nodes are called `node_<index>`.
+pub fn gltf_node_name(node: &gltf::Node) -> String {
+    match node.name() {
+        Some(name) => name.into(),
+        None => format!("node_{}", node.index()),
+    }
+}
+
+impl AnimationClip {
+    /// Loads all the animations in the glTF or GLB file at `path`.
+    pub fn load_gltf(path: impl AsRef<Path>) -> Result<Vec<Self>, GltfAnimationError> {
+        let (document, buffers, _) = gltf::import(path).map_err(GltfAnimationError::Import)?;
+        Self::from_gltf(&document, &buffers)
+    }
+
+    /// Reads all the animations in an already imported glTF document.
+    ///
+    /// Tracks target the nodes by the name given by [`gltf_node_name`].
+    /// Morph target weights are not supported and are skipped.
+    pub fn from_gltf(
+        document: &gltf::Document,
+        buffers: &[gltf::buffer::Data],
+    ) -> Result<Vec<Self>, GltfAnimationError> {
+        let mut clips = Vec::new();
+        for animation in document.animations() {
+            let name = match animation.name() {
+                Some(name) => name.to_string(),
+                None => format!("animation_{}", animation.index()),
+            };
+
+            let mut clip = AnimationClip::new(&name);
+            for channel in animation.channels() {
+                let missing = || GltfAnimationError::MissingData {
+                    animation: name.clone(),
+                    channel: channel.index(),
+                };
+                let invalid = |error| GltfAnimationError::Keyframes {
+                    animation: name.clone(),
+                    channel: channel.index(),
+                    error,
+                };
+
+                let reader = channel.reader(|buffer| Some(&buffers.get(buffer.index())?.0));
+                let times: Vec<f32> = reader.read_inputs().ok_or_else(missing)?.collect();
+                let interpolation = match channel.sampler().interpolation() {
+                    gltf::animation::Interpolation::Step => Interpolation::Step,
+                    gltf::animation::Interpolation::Linear => Interpolation::Linear,
+                    gltf::animation::Interpolation::CubicSpline => Interpolation::CubicSpline,
+                };
+
+                let target = gltf_node_name(&channel.target().node());
+                match reader.read_outputs().ok_or_else(missing)? {
+                    ReadOutputs::Translations(values) => {
+                        let values = values.map(Vec3::from).collect();
+                        let keyframes = Keyframes::new(times, values, interpolation);
+                        clip.set_position(&target, keyframes.map_err(invalid)?);
+                    }
+                    ReadOutputs::Rotations(values) => {
+                        let values = values.into_f32().map(Quat::from_array).collect();
+                        let keyframes = Keyframes::new(times, values, interpolation);
+                        clip.set_rotation(&target, keyframes.map_err(invalid)?);
+                    }
+                    ReadOutputs::Scales(values) => {
+                        let values = values.map(Vec3::from).collect();
+                        let keyframes = Keyframes::new(times, values, interpolation);
+                        clip.set_scale(&target, keyframes.map_err(invalid)?);
+                    }
+                    ReadOutputs::MorphTargetWeights(_) => {
+                        warn!("Skipping morph target animation on '{target}' in '{name}'.");
+                    }
+                }
+            }
+
+            clips.push(clip);
+        }
+
+        Ok(clips)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec3;
+
+    use super::AnimationClip;
+
+    // one node moving from the origin to (1, 2, 3) over a second
+    const ANIMATED_NODE: &str = r#"{
+        "asset": { "version": "2.0" },
+        "nodes": [{ "name": "mover" }],
+        "buffers": [{
+            "byteLength": 32,
+            "uri": "data:application/octet-stream;base64,AAAAAAAAgD8AAAAAAAAAAAAAAAAAAIA/AAAAQAAAQEA="
+        }],
+        "bufferViews": [
+            { "buffer": 0, "byteOffset": 0, "byteLength": 8 },
+            { "buffer": 0, "byteOffset": 8, "byteLength": 24 }
+        ],
+        "accessors": [
+            { "bufferView": 0, "componentType": 5126, "count": 2, "type": "SCALAR", "min": [0.0], "max": [1.0] },
+            { "bufferView": 1, "componentType": 5126, "count": 2, "type": "VEC3" }
+        ],
+        "animations": [{
+            "name": "move",
+            "samplers": [{ "input": 0, "output": 1, "interpolation": "LINEAR" }],
+            "channels": [{ "sampler": 0, "target": { "node": 0, "path": "translation" } }]
+        }]
+    }"#;
+
+    #[test]
+    fn load_gltf_animation() {
+        let (document, buffers, _) = gltf::import_slice(ANIMATED_NODE).unwrap();
+        let clips = AnimationClip::from_gltf(&document, &buffers).unwrap();
+        assert!(clips.len() == 1);
+        assert!(clips[0].name() == "move");
+        assert!(clips[0].duration() == 1.);
+
+        let sample = clips[0].track("mover").unwrap().sample(0.5);
+        assert!(sample.position == Some(Vec3::new(0.5, 1., 1.5)));
+    }
+}
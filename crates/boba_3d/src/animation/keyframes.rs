@@ -0,0 +1,154 @@
+use glam::{Quat, Vec3, Vec4};
+
+use crate::tween::Tweenable;
+
+/// How values are interpolated between two keyframes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Holds the value of the previous keyframe until the next one.
+    Step,
+    #[default]
+    Linear,
+    /// A cubic hermite spline, using an in and out tangent stored with each keyframe.
+    CubicSpline,
+}
+
+/// A value that can be stored in [`Keyframes`].
+pub trait Keyframe: Tweenable {
+    /// Evaluates a cubic hermite spline between `from` and `to`.
+    ///
+    /// `span` is the time between the two keyframes, which the tangents are scaled by.
+    fn hermite(from: Self, from_out: Self, to: Self, to_in: Self, t: f32, span: f32) -> Self;
+}
+
+fn hermite_weights(t: f32, span: f32) -> (f32, f32, f32, f32) {
+    let (t2, t3) = (t * t, t * t * t);
+    (
+        2. * t3 - 3. * t2 + 1.,
+        (t3 - 2. * t2 + t) * span,
+        -2. * t3 + 3. * t2,
+        (t3 - t2) * span,
+    )
+}
+
+impl Keyframe for Vec3 {
+    fn hermite(from: Self, from_out: Self, to: Self, to_in: Self, t: f32, span: f32) -> Self {
+        let (a, b, c, d) = hermite_weights(t, span);
+        from * a + from_out * b + to * c + to_in * d
+    }
+}
+
+impl Keyframe for Quat {
+    fn hermite(from: Self, from_out: Self, to: Self, to_in: Self, t: f32, span: f32) -> Self {
+        let (a, b, c, d) = hermite_weights(t, span);
+        let value = Vec4::from(from) * a
+            + Vec4::from(from_out) * b
+            + Vec4::from(to) * c
+            + Vec4::from(to_in) * d;
+        Quat::from_vec4(value).normalize()
+    }
+}
+
+/// A list of values over time.
+///
+/// Keyframes using [`Interpolation::CubicSpline`] store three values per keyframe:
+/// the in tangent, the value, and the out tangent, in that order.
+#[derive(Debug, Clone)]
+pub struct Keyframes<T> {
+    times: Vec<f32>,
+    values: Vec<T>,
+    interpolation: Interpolation,
+}
+
+/// An error returned when creating [`Keyframes`] with mismatched times and values.
+#[derive(Debug, thiserror::Error)]
+#[error("Keyframes expected {expected} values for {times} times, but got {values}")]
+pub struct KeyframeError {
+    pub times: usize,
+    pub values: usize,
+    pub expected: usize,
+}
+
+impl<T: Keyframe> Keyframes<T> {
+    /// Creates a new list of keyframes.
+    ///
+    /// `times` must be in ascending order.
+    pub fn new(
+        times: Vec<f32>,
+        values: Vec<T>,
+        interpolation: Interpolation,
+    ) -> Result<Self, KeyframeError> {
+        let expected = match interpolation {
+            Interpolation::CubicSpline => times.len() * 3,
+            _ => times.len(),
+        };
+
+        if values.len() != expected || times.is_empty() {
+            return Err(KeyframeError {
+                times: times.len(),
+                values: values.len(),
+                expected,
+            });
+        }
+
+        Ok(Self {
+            times,
+            values,
+            interpolation,
+        })
+    }
+
+    pub fn interpolation(&self) -> Interpolation {
+        self.interpolation
+    }
+
+    pub fn times(&self) -> &[f32] {
+        &self.times
+    }
+
+    /// Gets the time of the last keyframe.
+    pub fn duration(&self) -> f32 {
+        self.times.last().copied().unwrap_or(0.)
+    }
+
+    /// Samples the keyframes at `time`.
+    ///
+    /// Times before the first or after the last keyframe are clamped.
+    pub fn sample(&self, time: f32) -> T {
+        let next = self.times.partition_point(|t| *t <= time);
+        if next == 0 {
+            return self.value(0);
+        }
+        if next == self.times.len() {
+            return self.value(next - 1);
+        }
+
+        let prev = next - 1;
+        let (start, end) = (self.times[prev], self.times[next]);
+        let span = end - start;
+        let t = match span > 0. {
+            true => (time - start) / span,
+            false => 0.,
+        };
+
+        match self.interpolation {
+            Interpolation::Step => self.value(prev),
+            Interpolation::Linear => T::interpolate(self.value(prev), self.value(next), t),
+            Interpolation::CubicSpline => T::hermite(
+                self.value(prev),
+                self.values[prev * 3 + 2],
+                self.value(next),
+                self.values[next * 3],
+                t,
+                span,
+            ),
+        }
+    }
+
+    fn value(&self, index: usize) -> T {
+        match self.interpolation {
+            Interpolation::CubicSpline => self.values[index * 3 + 1],
+            _ => self.values[index],
+        }
+    }
+}
@@ -0,0 +1,7 @@
+mod clip;
+mod gltf;
+mod keyframes;
+
+pub use self::gltf::*;
+pub use clip::*;
+pub use keyframes::*;
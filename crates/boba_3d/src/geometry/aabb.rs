@@ -0,0 +1,146 @@
+use glam::{Mat4, Vec3};
+
+use super::BoundingSphere;
+
+/// An axis aligned bounding box.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    pub fn new(min: Vec3, max: Vec3) -> Self {
+        Self {
+            min: min.min(max),
+            max: min.max(max),
+        }
+    }
+
+    pub fn from_center_half_extents(center: Vec3, half_extents: Vec3) -> Self {
+        Self::new(center - half_extents, center + half_extents)
+    }
+
+    /// Creates the smallest box that contains all of `points`.
+    ///
+    /// Returns `None` if there are no points.
+    pub fn from_points(points: impl IntoIterator<Item = Vec3>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let (min, max) = points.fold((first, first), |(min, max), p| (min.min(p), max.max(p)));
+        Some(Self { min, max })
+    }
+
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn half_extents(&self) -> Vec3 {
+        (self.max - self.min) * 0.5
+    }
+
+    pub fn size(&self) -> Vec3 {
+        self.max - self.min
+    }
+
+    pub fn surface_area(&self) -> f32 {
+        let size = self.size();
+        2. * (size.x * size.y + size.y * size.z + size.z * size.x)
+    }
+
+    /// Creates the smallest box that contains both boxes.
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    /// Grows the box by `amount` in every direction.
+    pub fn expanded(&self, amount: f32) -> Aabb {
+        Aabb::new(
+            self.min - Vec3::splat(amount),
+            self.max + Vec3::splat(amount),
+        )
+    }
+
+    /// Gets the point in or on the box that is closest to `point`.
+    pub fn closest_point(&self, point: Vec3) -> Vec3 {
+        point.clamp(self.min, self.max)
+    }
+
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        point.cmpge(self.min).all() && point.cmple(self.max).all()
+    }
+
+    /// Returns true if `other` is completely inside this box.
+    pub fn contains_aabb(&self, other: &Aabb) -> bool {
+        other.min.cmpge(self.min).all() && other.max.cmple(self.max).all()
+    }
+
+    pub fn intersects_aabb(&self, other: &Aabb) -> bool {
+        self.min.cmple(other.max).all() && self.max.cmpge(other.min).all()
+    }
+
+    pub fn intersects_sphere(&self, sphere: &BoundingSphere) -> bool {
+        let closest = self.closest_point(sphere.center);
+        closest.distance_squared(sphere.center) <= sphere.radius * sphere.radius
+    }
+
+    /// Gets the axis aligned box that contains this box after it has been transformed by `matrix`.
+    pub fn transformed(&self, matrix: &Mat4) -> Aabb {
+        let center = matrix.transform_point3(self.center());
+        let half = self.half_extents();
+        let half_extents = matrix.x_axis.truncate().abs() * half.x
+            + matrix.y_axis.truncate().abs() * half.y
+            + matrix.z_axis.truncate().abs() * half.z;
+        Aabb::from_center_half_extents(center, half_extents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{Mat4, Quat, Vec3};
+
+    use crate::geometry::BoundingSphere;
+
+    use super::Aabb;
+
+    #[test]
+    fn intersections() {
+        let unit = Aabb::new(Vec3::ONE, -Vec3::ONE);
+        assert!(unit.min == -Vec3::ONE);
+        assert!(unit.contains_point(Vec3::ZERO));
+        assert!(!unit.contains_point(Vec3::X * 1.5));
+
+        let touching = Aabb::new(Vec3::X, Vec3::new(3., 1., 1.));
+        let apart = Aabb::new(Vec3::X * 2., Vec3::X * 3.);
+        assert!(unit.intersects_aabb(&touching));
+        assert!(!unit.intersects_aabb(&apart));
+        assert!(unit.union(&apart).contains_aabb(&touching.expanded(-0.1)));
+
+        assert!(unit.intersects_sphere(&BoundingSphere::new(Vec3::splat(1.5), 1.)));
+        assert!(!unit.intersects_sphere(&BoundingSphere::new(Vec3::splat(2.), 1.)));
+    }
+
+    #[test]
+    fn transform() {
+        let unit = Aabb::from_center_half_extents(Vec3::ZERO, Vec3::ONE);
+        let matrix = Mat4::from_scale_rotation_translation(
+            Vec3::new(2., 1., 1.),
+            Quat::from_rotation_z(std::f32::consts::FRAC_PI_2),
+            Vec3::X * 10.,
+        );
+
+        let bounds = unit.transformed(&matrix);
+        assert!(bounds.center().abs_diff_eq(Vec3::X * 10., 1e-5));
+        assert!(bounds
+            .half_extents()
+            .abs_diff_eq(Vec3::new(1., 2., 1.), 1e-5));
+
+        let points = [Vec3::ZERO, Vec3::new(1., -2., 3.), Vec3::Y];
+        let bounds = Aabb::from_points(points).unwrap();
+        assert!(bounds.min == Vec3::new(0., -2., 0.) && bounds.max == Vec3::new(1., 1., 3.));
+        assert!(Aabb::from_points([]).is_none());
+    }
+}
@@ -0,0 +1,84 @@
+use glam::{Mat4, Vec3};
+
+use super::{Aabb, BoundingSphere, Plane};
+
+/// The volume visible to a camera, made of six inward facing planes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frustum {
+    /// The planes in the order left, right, bottom, top, near, far.
+    pub planes: [Plane; 6],
+}
+
+impl Frustum {
+    /// Extracts the frustum from a view projection matrix.
+    ///
+    /// The matrix is expected to map depth between `0.0` and `1.0`,
+    /// like the matrices created by [`Mat4::perspective_rh`].
+    pub fn from_matrix(view_projection: &Mat4) -> Self {
+        let m = view_projection.transpose();
+        let planes = [
+            m.w_axis + m.x_axis,
+            m.w_axis - m.x_axis,
+            m.w_axis + m.y_axis,
+            m.w_axis - m.y_axis,
+            m.z_axis,
+            m.w_axis - m.z_axis,
+        ];
+
+        Self {
+            planes: planes.map(Plane::from_coefficients),
+        }
+    }
+
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        self.planes.iter().all(|p| p.signed_distance(point) >= 0.)
+    }
+
+    pub fn intersects_sphere(&self, sphere: &BoundingSphere) -> bool {
+        let radius = -sphere.radius;
+        self.planes
+            .iter()
+            .all(|p| p.signed_distance(sphere.center) >= radius)
+    }
+
+    /// Returns true if `aabb` might be inside the frustum.
+    ///
+    /// Large boxes near the corners of the frustum can be reported as intersecting when they are not,
+    /// which is fine for culling.
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        self.planes.iter().all(|plane| {
+            // test the corner that is furthest along the normal
+            let corner = Vec3::select(plane.normal.cmpge(Vec3::ZERO), aabb.max, aabb.min);
+            plane.signed_distance(corner) >= 0.
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{Mat4, Vec3};
+
+    use crate::geometry::{Aabb, BoundingSphere};
+
+    use super::Frustum;
+
+    #[test]
+    fn culling() {
+        let view = Mat4::look_at_rh(Vec3::ZERO, Vec3::Z, Vec3::Y);
+        let projection = Mat4::perspective_rh(std::f32::consts::FRAC_PI_2, 1., 0.1, 100.);
+        let frustum = Frustum::from_matrix(&(projection * view));
+
+        assert!(frustum.contains_point(Vec3::Z * 10.));
+        assert!(!frustum.contains_point(-Vec3::Z * 10.));
+        assert!(!frustum.contains_point(Vec3::Z * 200.));
+        assert!(!frustum.contains_point(Vec3::new(20., 0., 10.)));
+
+        let visible = Aabb::from_center_half_extents(Vec3::new(11., 0., 10.), Vec3::splat(2.));
+        let hidden = Aabb::from_center_half_extents(Vec3::new(15., 0., 10.), Vec3::splat(2.));
+        assert!(frustum.intersects_aabb(&visible));
+        assert!(!frustum.intersects_aabb(&hidden));
+
+        assert!(frustum.intersects_sphere(&BoundingSphere::new(Vec3::new(0., 0., -0.5), 1.)));
+        assert!(!frustum.intersects_sphere(&BoundingSphere::new(Vec3::new(0., 0., -2.), 1.)));
+    }
+}
@@ -0,0 +1,11 @@
+mod aabb;
+mod frustum;
+mod plane;
+mod ray;
+mod sphere;
+
+pub use aabb::*;
+pub use frustum::*;
+pub use plane::*;
+pub use ray::*;
+pub use sphere::*;
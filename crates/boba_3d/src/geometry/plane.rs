@@ -0,0 +1,49 @@
+use glam::{Vec3, Vec4};
+
+/// An infinite plane, made of all points `p` where `normal.dot(p) + distance == 0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Plane {
+    pub normal: Vec3,
+    pub distance: f32,
+}
+
+impl Plane {
+    /// Creates a plane from a normal and distance, normalizing both.
+    pub fn new(normal: Vec3, distance: f32) -> Self {
+        let length = normal.length();
+        Self {
+            normal: normal / length,
+            distance: distance / length,
+        }
+    }
+
+    pub fn from_point_normal(point: Vec3, normal: Vec3) -> Self {
+        let normal = normal.normalize();
+        Self {
+            normal,
+            distance: -normal.dot(point),
+        }
+    }
+
+    /// Creates a plane through three points, facing the side they wind counter clockwise around.
+    pub fn from_points(a: Vec3, b: Vec3, c: Vec3) -> Self {
+        Self::from_point_normal(a, (b - a).cross(c - a))
+    }
+
+    /// Creates a plane from its coefficients `(a, b, c, d)` in `ax + by + cz + d = 0`.
+    pub fn from_coefficients(coefficients: Vec4) -> Self {
+        Self::new(coefficients.truncate(), coefficients.w)
+    }
+
+    /// Gets the distance from `point` to the plane.
+    ///
+    /// Points on the side the normal faces have a positive distance.
+    pub fn signed_distance(&self, point: Vec3) -> f32 {
+        self.normal.dot(point) + self.distance
+    }
+
+    /// Gets the point on the plane that is closest to `point`.
+    pub fn project_point(&self, point: Vec3) -> Vec3 {
+        point - self.normal * self.signed_distance(point)
+    }
+}
@@ -0,0 +1,156 @@
+use glam::Vec3;
+
+use super::{Aabb, BoundingSphere, Plane};
+
+/// A half line starting at `origin` and going on forever in `direction`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray {
+    pub origin: Vec3,
+    pub direction: Vec3,
+}
+
+impl Ray {
+    /// Creates a new ray, normalizing `direction`.
+    pub fn new(origin: Vec3, direction: Vec3) -> Self {
+        Self {
+            origin,
+            direction: direction.normalize(),
+        }
+    }
+
+    /// Gets the point at `distance` along the ray.
+    pub fn at(&self, distance: f32) -> Vec3 {
+        self.origin + self.direction * distance
+    }
+
+    /// Gets the distance along the ray to where it enters `aabb`.
+    ///
+    /// Rays starting inside the box hit it at a distance of zero.
+    pub fn intersect_aabb(&self, aabb: &Aabb) -> Option<f32> {
+        let inverse = self.direction.recip();
+        let t1 = (aabb.min - self.origin) * inverse;
+        let t2 = (aabb.max - self.origin) * inverse;
+
+        // nan comes from rays parallel to a slab starting on its edge, which min/max ignore
+        let near = t1.min(t2).max_element().max(0.);
+        let far = t1.max(t2).min_element();
+        match near <= far {
+            true => Some(near),
+            false => None,
+        }
+    }
+
+    /// Gets the distance along the ray to where it enters `sphere`.
+    ///
+    /// Rays starting inside the sphere hit it at a distance of zero.
+    pub fn intersect_sphere(&self, sphere: &BoundingSphere) -> Option<f32> {
+        let offset = self.origin - sphere.center;
+        let c = offset.length_squared() - sphere.radius * sphere.radius;
+        if c <= 0. {
+            return Some(0.);
+        }
+
+        let b = offset.dot(self.direction);
+        let discriminant = b * b - c;
+        if b > 0. || discriminant < 0. {
+            return None;
+        }
+
+        Some(-b - discriminant.sqrt())
+    }
+
+    /// Gets the distance along the ray to where it crosses `plane`.
+    pub fn intersect_plane(&self, plane: &Plane) -> Option<f32> {
+        let facing = plane.normal.dot(self.direction);
+        if facing.abs() <= f32::EPSILON {
+            return None;
+        }
+
+        let distance = -plane.signed_distance(self.origin) / facing;
+        match distance >= 0. {
+            true => Some(distance),
+            false => None,
+        }
+    }
+
+    /// Gets the distance along the ray to where it hits the triangle `a`, `b`, `c`.
+    ///
+    /// Both sides of the triangle can be hit.
+    pub fn intersect_triangle(&self, a: Vec3, b: Vec3, c: Vec3) -> Option<f32> {
+        let (edge1, edge2) = (b - a, c - a);
+        let p = self.direction.cross(edge2);
+        let determinant = edge1.dot(p);
+        if determinant.abs() <= f32::EPSILON {
+            return None;
+        }
+
+        let inverse = 1. / determinant;
+        let offset = self.origin - a;
+        let u = offset.dot(p) * inverse;
+        if !(0. ..=1.).contains(&u) {
+            return None;
+        }
+
+        let q = offset.cross(edge1);
+        let v = self.direction.dot(q) * inverse;
+        if v < 0. || u + v > 1. {
+            return None;
+        }
+
+        let distance = edge2.dot(q) * inverse;
+        match distance >= 0. {
+            true => Some(distance),
+            false => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec3;
+
+    use crate::geometry::{Aabb, BoundingSphere, Plane};
+
+    use super::Ray;
+
+    #[test]
+    fn hits() {
+        let ray = Ray::new(Vec3::new(0., 0., -5.), Vec3::Z * 2.);
+        let unit = Aabb::from_center_half_extents(Vec3::ZERO, Vec3::ONE);
+        assert!(ray.intersect_aabb(&unit) == Some(4.));
+        assert!(ray.intersect_sphere(&BoundingSphere::new(Vec3::ZERO, 2.)) == Some(3.));
+        assert!(ray.intersect_plane(&Plane::from_point_normal(Vec3::Z, -Vec3::Z)) == Some(6.));
+
+        let hit = ray.intersect_triangle(
+            Vec3::new(-1., -1., 1.),
+            Vec3::new(1., -1., 1.),
+            Vec3::new(0., 1., 1.),
+        );
+        assert!(hit == Some(6.));
+        assert!(ray.at(6.) == Vec3::Z);
+
+        // starting inside counts as a hit at the origin
+        let inside = Ray::new(Vec3::ZERO, Vec3::X);
+        assert!(inside.intersect_aabb(&unit) == Some(0.));
+        assert!(inside.intersect_sphere(&BoundingSphere::new(Vec3::ZERO, 1.)) == Some(0.));
+    }
+
+    #[test]
+    fn misses() {
+        let ray = Ray::new(Vec3::new(0., 3., -5.), Vec3::Z);
+        let unit = Aabb::from_center_half_extents(Vec3::ZERO, Vec3::ONE);
+        assert!(ray.intersect_aabb(&unit).is_none());
+        assert!(ray
+            .intersect_sphere(&BoundingSphere::new(Vec3::ZERO, 2.))
+            .is_none());
+
+        let behind = Ray::new(Vec3::new(0., 0., 5.), Vec3::Z);
+        assert!(behind.intersect_aabb(&unit).is_none());
+        assert!(behind
+            .intersect_plane(&Plane::from_point_normal(Vec3::ZERO, Vec3::Z))
+            .is_none());
+        assert!(behind
+            .intersect_sphere(&BoundingSphere::new(Vec3::ZERO, 1.))
+            .is_none());
+    }
+}
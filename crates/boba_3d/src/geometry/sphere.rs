@@ -0,0 +1,52 @@
+use glam::{Mat4, Vec3};
+
+use super::Aabb;
+
+/// A sphere used for quick overlap tests.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingSphere {
+    pub center: Vec3,
+    pub radius: f32,
+}
+
+impl BoundingSphere {
+    pub fn new(center: Vec3, radius: f32) -> Self {
+        Self { center, radius }
+    }
+
+    /// Creates the sphere that passes through the corners of `aabb`.
+    pub fn from_aabb(aabb: &Aabb) -> Self {
+        Self::new(aabb.center(), aabb.half_extents().length())
+    }
+
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        self.center.distance_squared(point) <= self.radius * self.radius
+    }
+
+    pub fn intersects_sphere(&self, other: &BoundingSphere) -> bool {
+        let radius = self.radius + other.radius;
+        self.center.distance_squared(other.center) <= radius * radius
+    }
+
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        aabb.intersects_sphere(self)
+    }
+
+    /// Gets the smallest axis aligned box that contains the sphere.
+    pub fn aabb(&self) -> Aabb {
+        Aabb::from_center_half_extents(self.center, Vec3::splat(self.radius))
+    }
+
+    /// Gets the sphere that contains this sphere after it has been transformed by `matrix`.
+    ///
+    /// Non uniform scales use the largest axis, so the result may be larger than needed.
+    pub fn transformed(&self, matrix: &Mat4) -> BoundingSphere {
+        let scale = matrix
+            .x_axis
+            .truncate()
+            .length()
+            .max(matrix.y_axis.truncate().length())
+            .max(matrix.z_axis.truncate().length());
+        Self::new(matrix.transform_point3(self.center), self.radius * scale)
+    }
+}
@@ -1,3 +1,8 @@
+pub mod animation;
+pub mod geometry;
 pub mod pearls;
+pub mod spatial;
+pub mod stages;
+pub mod tween;
 
 pub use glam;
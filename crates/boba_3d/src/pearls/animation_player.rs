@@ -0,0 +1,356 @@
+use std::rc::Rc;
+
+use boba_core::{stages::BobaUpdate, BobaResources, BobaResult, Pearl, PearlStage};
+use glam::{Quat, Vec3, Vec4};
+use indexmap::IndexMap;
+use log::error;
+
+use crate::animation::AnimationClip;
+
+use super::BobaTransform;
+
+/// A clip being played by an [`AnimationPlayer`].
+pub struct AnimationLayer {
+    pub clip: Rc<AnimationClip>,
+    pub time: f32,
+    pub speed: f32,
+    pub weight: f32,
+    pub looping: bool,
+
+    fade: Option<Fade>,
+}
+
+struct Fade {
+    target: f32,
+    rate: f32,
+}
+
+impl AnimationLayer {
+    fn new(clip: Rc<AnimationClip>, weight: f32) -> Self {
+        Self {
+            clip,
+            time: 0.,
+            speed: 1.,
+            weight,
+            looping: true,
+            fade: None,
+        }
+    }
+
+    /// Returns true if the layer is not looping and has reached the end of its clip.
+    pub fn is_finished(&self) -> bool {
+        !self.looping && self.time >= self.clip.duration()
+    }
+
+    /// Fades the weight of the layer to `target` over `duration` seconds.
+    ///
+    /// Layers that fade out to zero are removed once the fade completes.
+    pub fn fade_to(&mut self, target: f32, duration: f32) {
+        if duration <= 0. {
+            self.weight = target;
+            self.fade = Some(Fade { target, rate: 0. });
+            return;
+        }
+
+        let rate = (target - self.weight).abs() / duration;
+        self.fade = Some(Fade { target, rate });
+    }
+
+    fn advance(&mut self, delta: f32) {
+        let duration = self.clip.duration();
+        self.time += delta * self.speed;
+        self.time = match self.looping && duration > 0. {
+            true => self.time.rem_euclid(duration),
+            false => self.time.clamp(0., duration),
+        };
+
+        if let Some(fade) = &self.fade {
+            let step = fade.rate * delta;
+            self.weight = match self.weight < fade.target {
+                true => (self.weight + step).min(fade.target),
+                false => (self.weight - step).max(fade.target),
+            };
+        }
+    }
+
+    fn faded_out(&self) -> bool {
+        matches!(&self.fade, Some(fade) if fade.target <= 0. && self.weight <= 0.)
+    }
+}
+
+/// A pearl that samples and blends animation clips onto a set of transforms during [`BobaUpdate`].
+///
+/// Clips target nodes by name, and each name must be bound to a transform with [`Self::bind`].
+/// When the weights of all layers add up to less than one, the remainder is taken from
+/// the rest pose, which is the local transform at the time it was bound.
+#[derive(Default)]
+pub struct AnimationPlayer {
+    targets: IndexMap<String, AnimationTarget>,
+    layers: Vec<AnimationLayer>,
+}
+
+struct AnimationTarget {
+    transform: Pearl<BobaTransform>,
+    position: Vec3,
+    rotation: Quat,
+    scale: Vec3,
+}
+
+boba_core::register_pearl_stages!(AnimationPlayer: BobaUpdate);
+
+impl AnimationPlayer {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Binds the node called `name` in every clip to `transform`.
+    ///
+    /// The current local transform is stored as the rest pose of the node.
+    pub fn bind(&mut self, name: &str, transform: Pearl<BobaTransform>) {
+        let (position, rotation, scale) = match transform.borrow() {
+            Ok(data) => (
+                data.local_position(),
+                data.local_rotation(),
+                data.local_scale(),
+            ),
+            Err(e) => {
+                error!("Could not read rest pose of '{name}'. Using identity. Error: {e}");
+                (Vec3::ZERO, Quat::IDENTITY, Vec3::ONE)
+            }
+        };
+
+        let target = AnimationTarget {
+            transform,
+            position,
+            rotation,
+            scale,
+        };
+        self.targets.insert(name.into(), target);
+    }
+
+    pub fn unbind(&mut self, name: &str) -> Option<Pearl<BobaTransform>> {
+        let target = self.targets.shift_remove(name)?;
+        Some(target.transform)
+    }
+
+    /// Stops all other layers and plays `clip` at full weight.
+    pub fn play(&mut self, clip: Rc<AnimationClip>) -> &mut AnimationLayer {
+        self.layers.clear();
+        self.blend(clip, 1.)
+    }
+
+    /// Plays `clip` on top of the current layers with `weight`.
+    pub fn blend(&mut self, clip: Rc<AnimationClip>, weight: f32) -> &mut AnimationLayer {
+        self.layers.push(AnimationLayer::new(clip, weight));
+        self.layers.last_mut().unwrap()
+    }
+
+    /// Fades out all current layers while fading in `clip` over `duration` seconds.
+    pub fn cross_fade(&mut self, clip: Rc<AnimationClip>, duration: f32) -> &mut AnimationLayer {
+        for layer in self.layers.iter_mut() {
+            layer.fade_to(0., duration);
+        }
+
+        let layer = self.blend(clip, 0.);
+        layer.fade_to(1., duration);
+        layer
+    }
+
+    pub fn stop_all(&mut self) {
+        self.layers.clear();
+    }
+
+    pub fn layers(&self) -> &[AnimationLayer] {
+        &self.layers
+    }
+
+    pub fn layers_mut(&mut self) -> &mut Vec<AnimationLayer> {
+        &mut self.layers
+    }
+
+    /// Advances all layers by `delta` seconds, and applies the blended result to the bound transforms.
+    pub fn advance(&mut self, delta: f32) {
+        for layer in self.layers.iter_mut() {
+            layer.advance(delta);
+        }
+        self.layers.retain(|layer| !layer.faded_out());
+
+        for (name, target) in self.targets.iter() {
+            let mut position = Blend::<Vec3>::default();
+            let mut rotation = Blend::<Vec4>::default();
+            let mut scale = Blend::<Vec3>::default();
+            for layer in self.layers.iter().filter(|l| l.weight > 0.) {
+                let Some(track) = layer.clip.track(name) else {
+                    continue;
+                };
+
+                let sample = track.sample(layer.time);
+                if let Some(value) = sample.position {
+                    position.add(value, layer.weight);
+                }
+                if let Some(value) = sample.rotation {
+                    rotation.add_rotation(value, layer.weight);
+                }
+                if let Some(value) = sample.scale {
+                    scale.add(value, layer.weight);
+                }
+            }
+
+            if position.weight == 0. && rotation.weight == 0. && scale.weight == 0. {
+                continue;
+            }
+
+            let mut transform = match target.transform.borrow_mut() {
+                Ok(transform) => transform,
+                Err(e) => {
+                    error!("Could not animate transform '{name}'. Error: {e}");
+                    continue;
+                }
+            };
+
+            if position.weight > 0. {
+                let value = position.resolve(target.position);
+                transform.set_local_position(value);
+            }
+            if rotation.weight > 0. {
+                rotation.add_rotation(target.rotation, 1. - rotation.weight);
+                let value = Quat::from_vec4(rotation.value).normalize();
+                transform.set_local_rotation(value);
+            }
+            if scale.weight > 0. {
+                let value = scale.resolve(target.scale);
+                transform.set_local_scale(value);
+            }
+        }
+    }
+}
+
+impl PearlStage<BobaUpdate> for AnimationPlayer {
+    fn update(&mut self, delta: &f32, _: &mut BobaResources) -> BobaResult {
+        self.advance(*delta);
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct Blend<T> {
+    value: T,
+    weight: f32,
+}
+
+impl Blend<Vec3> {
+    fn add(&mut self, value: Vec3, weight: f32) {
+        self.value += value * weight;
+        self.weight += weight;
+    }
+
+    fn resolve(&self, rest: Vec3) -> Vec3 {
+        match self.weight < 1. {
+            true => self.value + rest * (1. - self.weight),
+            false => self.value / self.weight,
+        }
+    }
+}
+
+impl Blend<Vec4> {
+    fn add_rotation(&mut self, value: Quat, weight: f32) {
+        if weight <= 0. {
+            return;
+        }
+
+        // keep all rotations in the same hemisphere so they do not cancel out
+        let value = Vec4::from(value);
+        let sign = match self.value.dot(value) < 0. {
+            true => -1.,
+            false => 1.,
+        };
+
+        self.value += value * weight * sign;
+        self.weight += weight;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use boba_core::Pearl;
+    use glam::Vec3;
+
+    use crate::{
+        animation::{AnimationClip, Interpolation, Keyframes},
+        pearls::BobaTransform,
+    };
+
+    use super::AnimationPlayer;
+
+    fn slide(name: &str, to: Vec3) -> Rc<AnimationClip> {
+        let mut clip = AnimationClip::new(name);
+        let keyframes = Keyframes::new(vec![0., 1.], vec![Vec3::ZERO, to], Interpolation::Linear);
+        clip.set_position("root", keyframes.unwrap());
+        Rc::new(clip)
+    }
+
+    fn position(transform: &Pearl<BobaTransform>) -> Vec3 {
+        transform.borrow().unwrap().local_position()
+    }
+
+    #[test]
+    fn play_and_blend() {
+        let transform = Pearl::wrap(BobaTransform::from_position(Vec3::Z));
+        let mut player = AnimationPlayer::new();
+        player.bind("root", transform.clone());
+
+        player.play(slide("right", Vec3::X)).looping = false;
+        player.advance(0.5);
+        assert!(position(&transform).abs_diff_eq(Vec3::X * 0.5, 1e-5));
+        player.advance(1.);
+        assert!(position(&transform).abs_diff_eq(Vec3::X, 1e-5));
+        assert!(player.layers()[0].is_finished());
+
+        player.play(slide("right", Vec3::X));
+        player.blend(slide("up", Vec3::Y), 1.);
+        player.advance(0.5);
+        assert!(position(&transform).abs_diff_eq(Vec3::new(0.25, 0.25, 0.), 1e-5));
+    }
+
+    #[test]
+    fn partial_weight() {
+        let transform = Pearl::wrap(BobaTransform::from_position(Vec3::Z));
+        let mut player = AnimationPlayer::new();
+        player.bind("root", transform.clone());
+
+        // half a clip blends with the rest pose, and stays halfway over many frames
+        player.play(slide("right", Vec3::X)).weight = 0.5;
+        player.layers_mut()[0].looping = false;
+        for _ in 0..5 {
+            player.advance(0.1);
+        }
+        assert!(position(&transform).abs_diff_eq(Vec3::new(0.25, 0., 0.5), 1e-5));
+
+        // once the clip has ended it keeps blending with the rest pose instead of the last frame
+        player.advance(1.);
+        for _ in 0..20 {
+            player.advance(0.1);
+            assert!(position(&transform).abs_diff_eq(Vec3::new(0.5, 0., 0.5), 1e-5));
+        }
+    }
+
+    #[test]
+    fn cross_fade() {
+        let transform = Pearl::wrap(BobaTransform::default());
+        let mut player = AnimationPlayer::new();
+        player.bind("root", transform.clone());
+
+        player.play(slide("right", Vec3::X)).looping = false;
+        player.advance(1.);
+        player.cross_fade(slide("up", Vec3::Y), 1.).looping = false;
+        player.advance(0.5);
+        assert!(player.layers().len() == 2);
+        assert!(position(&transform).abs_diff_eq(Vec3::new(0.5, 0.25, 0.), 1e-5));
+
+        player.advance(0.5);
+        assert!(player.layers().len() == 1);
+        assert!(position(&transform).abs_diff_eq(Vec3::Y, 1e-5));
+    }
+}
@@ -1,3 +1,7 @@
+mod animation_player;
 mod transform;
+mod tweener;
 
+pub use animation_player::*;
 pub use transform::*;
+pub use tweener::*;
@@ -1,25 +1,76 @@
+use std::{
+    cell::Cell,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
 use boba_core::{Pearl, PearlId, PearlMutError};
-use glam::{Mat4, Quat, Vec3, Vec4};
+use glam::{Mat3, Mat4, Quat, Vec3};
 use indexmap::IndexSet;
-use log::error;
 use thiserror::Error;
 
+/// A position, rotation and scale that can be arranged in a hierarchy.
+///
+/// Changing a transform never touches its children. Instead every change is stamped with a tick,
+/// and world space values are resolved lazily the first time they are read after a change.
+/// Moving a root transform many times per frame is cheap, and each descendant only recalculates
+/// its world matrix once when it is next read (usually while rendering).
+/// When no transform has changed since a value was last resolved, reading it again
+/// does not touch the parents at all.
 pub struct BobaTransform {
-    world_position: Vec3,
-    world_rotation: Quat,
-    lossy_scale: Vec3,
-
     local_position: Vec3,
     local_rotation: Quat,
     local_scale: Vec3,
-
-    parent_matrix: Mat4,
     local_matrix: Mat4,
 
+    changed: u64,
+    world: Cell<WorldCache>,
+
     parent: Option<Pearl<BobaTransform>>,
     children: IndexSet<Pearl<BobaTransform>>,
 }
 
+#[derive(Clone, Copy)]
+struct WorldCache {
+    changed: u64,
+    /// The global tick when the cache was last checked against its parents
+    verified: u64,
+    parent_matrix: Mat4,
+    matrix: Mat4,
+    position: Vec3,
+    rotation: Quat,
+    scale: Vec3,
+}
+
+impl WorldCache {
+    fn new(changed: u64, parent_matrix: Mat4, local_matrix: Mat4) -> Self {
+        let matrix = parent_matrix * local_matrix;
+        let (scale, rotation, position) = matrix.to_scale_rotation_translation();
+        Self {
+            changed,
+            verified: 0,
+            parent_matrix,
+            matrix,
+            position,
+            rotation,
+            scale,
+        }
+    }
+}
+
+static TICK: AtomicU64 = AtomicU64::new(1);
+
+/// Gets a new tick that is greater than every tick before it.
+fn next_tick() -> u64 {
+    TICK.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Gets the tick that will be handed out next.
+///
+/// This only changes when a transform somewhere is changed.
+fn current_tick() -> u64 {
+    TICK.load(Ordering::Relaxed)
+}
+
 impl Default for BobaTransform {
     fn default() -> Self {
         Self::from_position(Vec3::ZERO)
@@ -43,34 +94,32 @@ impl BobaTransform {
 
     pub fn new(position: Vec3, rotation: Quat, scale: Vec3) -> Self {
         let matrix = Mat4::from_scale_rotation_translation(scale, rotation, position);
+        let changed = next_tick();
 
         Self {
-            world_position: position,
-            world_rotation: rotation,
-            lossy_scale: scale,
-
             local_position: position,
             local_rotation: rotation,
             local_scale: scale,
-
-            parent_matrix: Mat4::IDENTITY,
             local_matrix: matrix,
 
+            changed,
+            world: Cell::new(WorldCache::new(changed, Mat4::IDENTITY, matrix)),
+
             parent: None,
             children: Default::default(),
         }
     }
 
     pub fn world_position(&self) -> Vec3 {
-        self.world_position
+        self.resolve().position
     }
 
     pub fn world_rotation(&self) -> Quat {
-        self.world_rotation
+        self.resolve().rotation
     }
 
     pub fn lossy_scale(&self) -> Vec3 {
-        self.lossy_scale
+        self.resolve().scale
     }
 
     pub fn local_position(&self) -> Vec3 {
@@ -86,78 +135,216 @@ impl BobaTransform {
     }
 
     pub fn world_matrix(&self) -> Mat4 {
-        self.parent_matrix * self.local_matrix
+        self.resolve().matrix
     }
 
     pub fn local_matrix(&self) -> Mat4 {
         self.local_matrix
     }
 
+    /// Gets the tick of the last change that affected the world matrix of this transform.
+    ///
+    /// This includes changes to any of its ancestors, so it can be compared against
+    /// a previously stored value to find out if the transform has moved.
+    pub fn changed_tick(&self) -> u64 {
+        self.resolve().changed
+    }
+
+    /// Gets the parent of the transform, if it has one.
+    ///
+    /// Use [`SetTransformParent`] to change the parent.
+    pub fn parent(&self) -> Option<&Pearl<BobaTransform>> {
+        self.parent.as_ref()
+    }
+
+    /// Iterates over the direct children of the transform in sibling order.
+    pub fn children(&self) -> impl Iterator<Item = &Pearl<BobaTransform>> {
+        self.children.iter()
+    }
+
+    /// Gets the direction the transform is facing in world space.
+    ///
+    /// This is the local `+Z` axis, matching the direction used by [`Self::look_at`].
+    pub fn forward(&self) -> Vec3 {
+        self.world_rotation() * Vec3::Z
+    }
+
+    /// Gets the right direction of the transform in world space.
+    pub fn right(&self) -> Vec3 {
+        self.world_rotation() * Vec3::NEG_X
+    }
+
+    /// Gets the up direction of the transform in world space.
+    pub fn up(&self) -> Vec3 {
+        self.world_rotation() * Vec3::Y
+    }
+
     /// Sets the local position of the transform.
     ///
-    /// Also recalculates the world position, and distributes the changes to
-    /// all available children.
+    /// The world position of this transform and its children is updated the next time it is read.
     pub fn set_local_position(&mut self, position: Vec3) {
         self.local_position = position;
-        self.local_matrix.w_axis = Vec4::from((self.local_position, 1.0));
-        self.calculate_world_transforms();
-        self.apply_matrix_to_children();
+        self.recalculate_local_matrix();
     }
 
     /// Sets the local rotation of the transform.
     ///
-    /// Also recalculates the world rotation, and distributes the changes to
-    /// all available children.
+    /// The world rotation of this transform and its children is updated the next time it is read.
     pub fn set_local_rotation(&mut self, rotation: Quat) {
         self.local_rotation = rotation;
+        self.recalculate_local_matrix();
+    }
+
+    /// Sets the local scale of the transform.
+    ///
+    /// The lossy scale of this transform and its children is updated the next time it is read.
+    pub fn set_local_scale(&mut self, scale: Vec3) {
+        self.local_scale = scale;
+        self.recalculate_local_matrix();
+    }
+
+    /// Sets the world position of the transform.
+    ///
+    /// The local position is calculated relative to the current parent.
+    pub fn set_world_position(&mut self, position: Vec3) {
+        let parent_matrix = self.resolve().parent_matrix;
+        self.set_local_position(parent_matrix.inverse().transform_point3(position));
+    }
+
+    /// Sets the world rotation of the transform.
+    ///
+    /// The local rotation is calculated relative to the current parent.
+    pub fn set_world_rotation(&mut self, rotation: Quat) {
+        let (_, parent_rotation, _) = self.resolve().parent_matrix.to_scale_rotation_translation();
+        self.set_local_rotation((parent_rotation.inverse() * rotation).normalize());
+    }
+
+    /// Moves the transform by `offset` in world space.
+    pub fn translate(&mut self, offset: Vec3) {
+        self.set_world_position(self.world_position() + offset);
+    }
+
+    /// Moves the transform by `offset` relative to its own rotation.
+    pub fn translate_local(&mut self, offset: Vec3) {
+        self.set_local_position(self.local_position + self.local_rotation * offset);
+    }
+
+    /// Applies `rotation` on top of the current world rotation.
+    pub fn rotate(&mut self, rotation: Quat) {
+        self.set_world_rotation(rotation * self.world_rotation());
+    }
+
+    /// Applies `rotation` on top of the current rotation, around the transforms own axes.
+    pub fn rotate_local(&mut self, rotation: Quat) {
+        self.set_local_rotation((self.local_rotation * rotation).normalize());
+    }
+
+    /// Rotates the transform by `rotation` around a `point` in world space.
+    ///
+    /// Both the position and the rotation of the transform are changed.
+    pub fn rotate_around(&mut self, point: Vec3, rotation: Quat) {
+        let world = self.resolve();
+        let position = point + rotation * (world.position - point);
+        let rotation = rotation * world.rotation;
+
+        let (_, parent_rotation, _) = world.parent_matrix.to_scale_rotation_translation();
+        self.local_position = world.parent_matrix.inverse().transform_point3(position);
+        self.local_rotation = (parent_rotation.inverse() * rotation).normalize();
+        self.recalculate_local_matrix();
+    }
+
+    /// Rotates the transform so that its forward direction points towards `point` in world space.
+    ///
+    /// Both `point` and the resulting direction are in world space, so a transform under a moved
+    /// or rotated parent still faces the point. For root transforms this is the same as
+    /// looking from the local position.
+    ///
+    /// The transform is kept upright using [`Vec3::Y`] unless it is looking straight up or down.
+    pub fn look_at(&mut self, point: Vec3) {
+        let Some(forward) = (point - self.world_position()).try_normalize() else {
+            return;
+        };
+
+        let rotation = match Vec3::Y.cross(forward).try_normalize() {
+            Some(x_axis) => {
+                Quat::from_mat3(&Mat3::from_cols(x_axis, forward.cross(x_axis), forward))
+            }
+            None => Quat::from_rotation_arc(Vec3::Z, forward),
+        };
+
+        self.set_world_rotation(rotation);
+    }
+
+    fn recalculate_local_matrix(&mut self) {
         self.local_matrix = Mat4::from_scale_rotation_translation(
             self.local_scale,
             self.local_rotation,
             self.local_position,
         );
-        self.calculate_world_transforms();
-        self.apply_matrix_to_children();
+        self.changed = next_tick();
     }
 
-    pub fn look_at(&mut self, point: Vec3) {
-        let Some(look_vector) = (point - self.local_position).try_normalize() else {
-            return;
-        };
-
-        let dot = Vec3::Z.dot(look_vector);
-        if (dot + 1.).abs() < 0.000001 {
-            self.local_rotation = Quat::from_axis_angle(Vec3::Y, 0.);
-            return;
-        }
-        if (dot - 1.).abs() < 0.000001 {
-            self.local_rotation = Quat::from_axis_angle(Vec3::Y, 180.);
-            return;
+    /// Moves the transform under a new parent matrix.
+    ///
+    /// `world_matrix` is the world matrix from before the parent changed.
+    fn reparent(&mut self, parent_matrix: Mat4, world_matrix: Mat4, keep_world_transform: bool) {
+        if keep_world_transform {
+            (self.local_scale, self.local_rotation, self.local_position) =
+                (parent_matrix.inverse() * world_matrix).to_scale_rotation_translation();
+            self.local_matrix = Mat4::from_scale_rotation_translation(
+                self.local_scale,
+                self.local_rotation,
+                self.local_position,
+            );
         }
 
-        let angle = dot.acos();
-        let axis = Vec3::Z.cross(look_vector).normalize();
-        self.set_local_rotation(Quat::from_axis_angle(axis, angle));
+        self.changed = next_tick();
     }
 
-    fn calculate_world_transforms(&mut self) {
-        (self.lossy_scale, self.world_rotation, self.world_position) =
-            self.world_matrix().to_scale_rotation_translation();
+    /// Gets the world space values of the transform, recalculating them if anything
+    /// in the chain of parents has changed since they were last read.
+    fn resolve(&self) -> WorldCache {
+        self.try_resolve().0
     }
 
-    fn apply_matrix_to_children(&mut self) {
-        for child in self.children.iter() {
-            match child.borrow_mut() {
-                Err(e) => {
-                    error!("Could not sync child transform due to: {e}");
-                    continue;
-                }
-                Ok(mut transform) => {
-                    transform.parent_matrix = self.world_matrix();
-                    transform.calculate_world_transforms();
-                    transform.apply_matrix_to_children();
+    /// Resolves the world space values, returning false if any ancestor was mutably borrowed.
+    ///
+    /// Ancestors that cannot be borrowed are replaced with their last known matrix.
+    /// That result is not cached, so it is resolved again once the ancestor is available.
+    fn try_resolve(&self) -> (WorldCache, bool) {
+        let cache = self.world.get();
+        let tick = current_tick();
+        if cache.verified == tick {
+            return (cache, true);
+        }
+
+        let (parent_matrix, parent_changed, resolved) = match &self.parent {
+            None => (Mat4::IDENTITY, 0, true),
+            Some(parent) => match parent.borrow() {
+                Ok(parent) => {
+                    let (parent, resolved) = parent.try_resolve();
+                    (parent.matrix, parent.changed, resolved)
                 }
-            }
+                Err(_) => (cache.parent_matrix, cache.changed, false),
+            },
+        };
+
+        let changed = self.changed.max(parent_changed);
+        let world = match changed == cache.changed {
+            true => cache,
+            false => WorldCache::new(changed, parent_matrix, self.local_matrix),
+        };
+
+        if !resolved {
+            return (world, false);
         }
+
+        let world = WorldCache {
+            verified: tick,
+            ..world
+        };
+        self.world.set(world);
+        (world, true)
     }
 }
 
@@ -169,44 +356,111 @@ pub enum SetParentError {
     PearlError(PearlMutError),
 }
 
+/// Hierarchy operations for transform pearls.
+///
+/// These live on the pearl rather than the transform, as both the child and the parent
+/// have to know about each other.
 pub trait SetTransformParent {
-    fn set_parent(&mut self, parent: Pearl<BobaTransform>) -> Result<(), SetParentError>;
+    /// Sets the parent of the transform, appending it to the parents children.
+    ///
+    /// If `keep_world_transform` is true, the local transform is recalculated so that the
+    /// transform stays where it is in the world. Otherwise the local transform is kept
+    /// and the transform moves along with its new parent.
+    fn set_parent(
+        &self,
+        parent: Pearl<BobaTransform>,
+        keep_world_transform: bool,
+    ) -> Result<(), SetParentError>;
+
+    /// Removes the transform from its parent, making it a root transform.
+    ///
+    /// `keep_world_transform` behaves the same as in [`SetTransformParent::set_parent`].
+    fn clear_parent(&self, keep_world_transform: bool) -> Result<(), SetParentError>;
+
+    /// Gets the index of the transform in the children of its parent.
+    ///
+    /// Returns `Ok(None)` if the transform has no parent.
+    fn sibling_index(&self) -> Result<Option<usize>, SetParentError>;
+
+    /// Moves the transform to `index` in the children of its parent.
+    ///
+    /// The index is clamped to the number of siblings. Does nothing if the transform has no parent.
+    fn set_sibling_index(&self, index: usize) -> Result<(), SetParentError>;
 }
 
 impl SetTransformParent for Pearl<BobaTransform> {
-    fn set_parent(&mut self, parent: Pearl<BobaTransform>) -> Result<(), SetParentError> {
+    fn set_parent(
+        &self,
+        parent: Pearl<BobaTransform>,
+        keep_world_transform: bool,
+    ) -> Result<(), SetParentError> {
         if self.id() == parent.id() {
             return Err(SetParentError::RecursionError);
         }
 
-        let mut self_data = match self.borrow_mut() {
-            Ok(d) => d,
-            Err(e) => return Err(SetParentError::PearlError(e)),
-        };
+        let mut self_data = self.borrow_mut().map_err(SetParentError::PearlError)?;
+        if self_data.parent.as_ref() == Some(&parent) {
+            return Ok(());
+        }
 
-        let mut parent_data = match parent.borrow_mut() {
-            Ok(d) => d,
-            Err(e) => return Err(SetParentError::PearlError(e)),
-        };
+        // resolve the world matrix before the new parent is borrowed, as it may be an ancestor
+        let world_matrix = self_data.world_matrix();
+        let mut parent_data = parent.borrow_mut().map_err(SetParentError::PearlError)?;
+        validate_parent_recursive(self.id(), &parent_data)?;
+
+        if let Some(old_parent) = &self_data.parent {
+            let mut old_parent_data = old_parent
+                .borrow_mut()
+                .map_err(SetParentError::PearlError)?;
+            old_parent_data.children.shift_remove(self);
+        }
+
+        parent_data.children.insert(self.clone());
+        let parent_matrix = parent_data.world_matrix();
+        drop(parent_data);
 
-        validate_parent_recursive(self.id(), &*parent_data)?;
+        self_data.parent = Some(parent);
+        self_data.reparent(parent_matrix, world_matrix, keep_world_transform);
+        Ok(())
+    }
 
-        let Some(self_parent) = &self_data.parent else {
-            parent_data.children.insert(self.clone());
-            self_data.parent = Some(parent.clone());
+    fn clear_parent(&self, keep_world_transform: bool) -> Result<(), SetParentError> {
+        let mut self_data = self.borrow_mut().map_err(SetParentError::PearlError)?;
+        let world_matrix = self_data.world_matrix();
+        let Some(parent) = &self_data.parent else {
             return Ok(());
         };
 
-        let mut self_parent_data = match self_parent.borrow_mut() {
-            Ok(d) => d,
-            Err(e) => return Err(SetParentError::PearlError(e)),
+        let mut parent_data = parent.borrow_mut().map_err(SetParentError::PearlError)?;
+        parent_data.children.shift_remove(self);
+        drop(parent_data);
+
+        self_data.parent = None;
+        self_data.reparent(Mat4::IDENTITY, world_matrix, keep_world_transform);
+        Ok(())
+    }
+
+    fn sibling_index(&self) -> Result<Option<usize>, SetParentError> {
+        let self_data = self.borrow_mut().map_err(SetParentError::PearlError)?;
+        let Some(parent) = &self_data.parent else {
+            return Ok(None);
         };
 
-        self_parent_data.children.remove(self);
+        let parent_data = parent.borrow_mut().map_err(SetParentError::PearlError)?;
+        Ok(parent_data.children.get_index_of(self))
+    }
 
-        drop(self_parent_data);
-        parent_data.children.insert(self.clone());
-        self_data.parent = Some(parent.clone());
+    fn set_sibling_index(&self, index: usize) -> Result<(), SetParentError> {
+        let self_data = self.borrow_mut().map_err(SetParentError::PearlError)?;
+        let Some(parent) = &self_data.parent else {
+            return Ok(());
+        };
+
+        let mut parent_data = parent.borrow_mut().map_err(SetParentError::PearlError)?;
+        let children = &mut parent_data.children;
+        if let Some(current) = children.get_index_of(self) {
+            children.move_index(current, index.min(children.len() - 1));
+        }
 
         Ok(())
     }
@@ -221,10 +475,264 @@ fn validate_parent_recursive(id: &PearlId, target: &BobaTransform) -> Result<(),
         return Err(SetParentError::RecursionError);
     };
 
-    let parent_data = match parent.borrow_mut() {
-        Ok(d) => d,
-        Err(e) => return Err(SetParentError::PearlError(e)),
-    };
+    let parent_data = parent.borrow_mut().map_err(SetParentError::PearlError)?;
+    validate_parent_recursive(id, &parent_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use boba_core::Pearl;
+    use glam::{Quat, Vec3};
+
+    use super::{BobaTransform, SetParentError, SetTransformParent};
+
+    fn transform(position: Vec3) -> Pearl<BobaTransform> {
+        Pearl::wrap(BobaTransform::from_position(position))
+    }
+
+    fn world_position(transform: &Pearl<BobaTransform>) -> Vec3 {
+        transform.borrow().unwrap().world_position()
+    }
+
+    #[test]
+    fn nested_world_positions() {
+        let root = transform(Vec3::X);
+        let middle = transform(Vec3::Y);
+        let leaf = transform(Vec3::Z);
+        middle.set_parent(root.clone(), false).unwrap();
+        leaf.set_parent(middle.clone(), false).unwrap();
+        assert!(world_position(&leaf).abs_diff_eq(Vec3::ONE, 1e-5));
+
+        root.borrow_mut().unwrap().set_local_position(Vec3::ZERO);
+        assert!(world_position(&leaf).abs_diff_eq(Vec3::new(0., 1., 1.), 1e-5));
+
+        // rotating the middle transform swings the leaf around it
+        let rotation = Quat::from_rotation_y(std::f32::consts::FRAC_PI_2);
+        middle.borrow_mut().unwrap().set_local_rotation(rotation);
+        assert!(world_position(&leaf).abs_diff_eq(Vec3::new(1., 1., 0.), 1e-5));
+    }
+
+    #[test]
+    fn keep_world_transform() {
+        let root = transform(Vec3::X);
+        root.borrow_mut().unwrap().set_local_scale(Vec3::splat(2.));
+        let child = transform(Vec3::Y);
+
+        child.set_parent(root.clone(), true).unwrap();
+        assert!(world_position(&child).abs_diff_eq(Vec3::Y, 1e-5));
+        let child_data = child.borrow().unwrap();
+        assert!(child_data
+            .local_position()
+            .abs_diff_eq(Vec3::new(-0.5, 0.5, 0.), 1e-5));
+        assert!(child_data.lossy_scale().abs_diff_eq(Vec3::ONE, 1e-5));
+        drop(child_data);
+
+        child.clear_parent(false).unwrap();
+        assert!(world_position(&child).abs_diff_eq(Vec3::new(-0.5, 0.5, 0.), 1e-5));
+        assert!(root.borrow().unwrap().children().count() == 0);
+
+        child.set_parent(root.clone(), false).unwrap();
+        child.clear_parent(true).unwrap();
+        assert!(world_position(&child).abs_diff_eq(Vec3::new(0., 1., 0.), 1e-5));
+        assert!(child.borrow().unwrap().parent().is_none());
+    }
+
+    #[test]
+    fn reparent_and_siblings() {
+        let first = transform(Vec3::ZERO);
+        let second = transform(Vec3::X);
+        let children = [0, 1, 2].map(|i| transform(Vec3::Y * i as f32));
+        for child in children.iter() {
+            child.set_parent(first.clone(), false).unwrap();
+        }
+
+        children[2].set_sibling_index(0).unwrap();
+        assert!(children[2].sibling_index().unwrap() == Some(0));
+        assert!(children[0].sibling_index().unwrap() == Some(1));
+
+        children[0].set_parent(second.clone(), false).unwrap();
+        assert!(first.borrow().unwrap().children().count() == 2);
+        assert!(children[1].sibling_index().unwrap() == Some(1));
+        assert!(world_position(&children[0]).abs_diff_eq(Vec3::X, 1e-5));
+        assert!(children[0].borrow().unwrap().parent() == Some(&second));
+    }
+
+    #[test]
+    fn lazy_propagation() {
+        let root = transform(Vec3::ZERO);
+        let child = transform(Vec3::X);
+        child.set_parent(root.clone(), false).unwrap();
+        let tick = child.borrow().unwrap().changed_tick();
+
+        // moving the root does not need to touch the child at all
+        let child_data = child.borrow_mut().unwrap();
+        for i in 0..100 {
+            root.borrow_mut()
+                .unwrap()
+                .set_local_position(Vec3::Y * i as f32);
+        }
+        drop(child_data);
+
+        let child_data = child.borrow().unwrap();
+        assert!(child_data
+            .world_position()
+            .abs_diff_eq(Vec3::new(1., 99., 0.), 1e-5));
+        assert!(child_data.changed_tick() > tick);
 
-    return validate_parent_recursive(id, &*parent_data);
+        // reading again without changes keeps the same tick
+        let tick = child_data.changed_tick();
+        assert!(child_data.changed_tick() == tick);
+    }
+
+    fn assert_rotation(a: Quat, b: Quat) {
+        assert!(a.dot(b).abs() > 1. - 1e-5);
+    }
+
+    // a child under a parent that is moved, rotated and scaled
+    fn rotated_and_scaled() -> (Pearl<BobaTransform>, Pearl<BobaTransform>) {
+        let rotation = Quat::from_rotation_y(std::f32::consts::FRAC_PI_2);
+        let parent = BobaTransform::new(Vec3::new(1., 2., 3.), rotation, Vec3::splat(2.));
+        let parent = Pearl::wrap(parent);
+        let child = transform(Vec3::X);
+        child.set_parent(parent.clone(), false).unwrap();
+        (parent, child)
+    }
+
+    #[test]
+    fn world_space_setters() {
+        let (_parent, child) = rotated_and_scaled();
+        let mut child = child.borrow_mut().unwrap();
+        assert!(child
+            .world_position()
+            .abs_diff_eq(Vec3::new(1., 2., 1.), 1e-5));
+
+        let position = Vec3::new(-4., 5., 6.);
+        child.set_world_position(position);
+        assert!(child.world_position().abs_diff_eq(position, 1e-5));
+        let matrix_position = child.world_matrix().transform_point3(Vec3::ZERO);
+        assert!(matrix_position.abs_diff_eq(position, 1e-5));
+
+        let rotation = Quat::from_rotation_x(0.5) * Quat::from_rotation_z(1.2);
+        child.set_world_rotation(rotation);
+        assert_rotation(child.world_rotation(), rotation);
+        let matrix_forward = child.world_matrix().transform_vector3(Vec3::Z).normalize();
+        assert!(matrix_forward.abs_diff_eq(rotation * Vec3::Z, 1e-5));
+        assert!(child.world_position().abs_diff_eq(position, 1e-5));
+
+        child.set_local_scale(Vec3::new(1., 2., 3.));
+        assert!(child.lossy_scale().abs_diff_eq(Vec3::new(2., 4., 6.), 1e-5));
+        let matrix = child.world_matrix();
+        assert!((matrix.transform_vector3(Vec3::Y).length() - 4.).abs() < 1e-5);
+    }
+
+    #[test]
+    fn directions() {
+        let (_parent, child) = rotated_and_scaled();
+        let mut child = child.borrow_mut().unwrap();
+        child.set_local_rotation(Quat::from_rotation_x(0.7));
+        child.set_local_scale(Vec3::new(1., 3., 2.));
+
+        let matrix = child.world_matrix();
+        let axis = |axis: Vec3| matrix.transform_vector3(axis).normalize();
+        assert!(child.forward().abs_diff_eq(axis(Vec3::Z), 1e-5));
+        assert!(child.right().abs_diff_eq(axis(Vec3::NEG_X), 1e-5));
+        assert!(child.up().abs_diff_eq(axis(Vec3::Y), 1e-5));
+    }
+
+    #[test]
+    fn translate_and_rotate() {
+        let (_parent, child) = rotated_and_scaled();
+        let mut child = child.borrow_mut().unwrap();
+
+        let start = child.world_position();
+        child.translate(Vec3::new(1., -1., 2.));
+        let position = child.world_matrix().transform_point3(Vec3::ZERO);
+        assert!(position.abs_diff_eq(start + Vec3::new(1., -1., 2.), 1e-5));
+
+        let start = child.world_rotation();
+        let rotation = Quat::from_rotation_z(0.3);
+        child.rotate(rotation);
+        assert_rotation(child.world_rotation(), rotation * start);
+
+        let point = Vec3::new(0., 2., 0.);
+        let start_position = child.world_position();
+        let start_rotation = child.world_rotation();
+        let rotation = Quat::from_rotation_y(std::f32::consts::PI);
+        child.rotate_around(point, rotation);
+        let expected = point + rotation * (start_position - point);
+        assert!(child.world_position().abs_diff_eq(expected, 1e-4));
+        assert_rotation(child.world_rotation(), rotation * start_rotation);
+        let matrix_forward = child.world_matrix().transform_vector3(Vec3::Z).normalize();
+        assert!(matrix_forward.abs_diff_eq(rotation * start_rotation * Vec3::Z, 1e-5));
+    }
+
+    #[test]
+    fn look_at_world_space() {
+        let (_parent, child) = rotated_and_scaled();
+        let mut child = child.borrow_mut().unwrap();
+
+        // the target is in world space, not relative to the local position
+        let target = Vec3::new(1., 2., 10.);
+        child.look_at(target);
+        let direction = (target - child.world_position()).normalize();
+        assert!(child.forward().abs_diff_eq(direction, 1e-5));
+        assert!(child.right().y.abs() < 1e-5);
+
+        // a root transform looks from its local position
+        let mut root = BobaTransform::from_position(Vec3::X);
+        root.look_at(Vec3::new(1., 0., -5.));
+        assert!(root.forward().abs_diff_eq(Vec3::NEG_Z, 1e-5));
+        assert!(root.local_position() == Vec3::X);
+    }
+
+    #[test]
+    fn borrowed_parent() {
+        let root = transform(Vec3::ZERO);
+        let child = transform(Vec3::X);
+        child.set_parent(root.clone(), false).unwrap();
+        assert!(world_position(&child).abs_diff_eq(Vec3::X, 1e-5));
+
+        // while the parent is borrowed, the last known parent matrix is used
+        let mut root_data = root.borrow_mut().unwrap();
+        root_data.set_local_position(Vec3::Y);
+        assert!(world_position(&child).abs_diff_eq(Vec3::X, 1e-5));
+        child.borrow_mut().unwrap().set_local_position(Vec3::Z);
+        assert!(world_position(&child).abs_diff_eq(Vec3::Z, 1e-5));
+        drop(root_data);
+
+        // and the real value is resolved once it is available again
+        assert!(world_position(&child).abs_diff_eq(Vec3::new(0., 1., 1.), 1e-5));
+    }
+
+    #[test]
+    fn borrowed_grandparent() {
+        let root = transform(Vec3::ZERO);
+        let middle = transform(Vec3::ZERO);
+        let leaf = transform(Vec3::X);
+        middle.set_parent(root.clone(), false).unwrap();
+        leaf.set_parent(middle.clone(), false).unwrap();
+        assert!(world_position(&leaf).abs_diff_eq(Vec3::X, 1e-5));
+
+        // a stale value read while an ancestor is borrowed is not kept
+        let mut root_data = root.borrow_mut().unwrap();
+        root_data.set_local_position(Vec3::Y);
+        assert!(world_position(&leaf).abs_diff_eq(Vec3::X, 1e-5));
+        drop(root_data);
+        assert!(world_position(&leaf).abs_diff_eq(Vec3::new(1., 1., 0.), 1e-5));
+        assert!(world_position(&middle).abs_diff_eq(Vec3::Y, 1e-5));
+    }
+
+    #[test]
+    fn recursion() {
+        let root = transform(Vec3::ZERO);
+        let child = transform(Vec3::ZERO);
+        let grandchild = transform(Vec3::ZERO);
+        child.set_parent(root.clone(), false).unwrap();
+        grandchild.set_parent(child.clone(), false).unwrap();
+
+        let result = root.set_parent(grandchild.clone(), false);
+        assert!(matches!(result, Err(SetParentError::RecursionError)));
+        let result = root.set_parent(root.clone(), false);
+        assert!(matches!(result, Err(SetParentError::RecursionError)));
+    }
 }
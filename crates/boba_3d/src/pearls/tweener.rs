@@ -0,0 +1,155 @@
+use boba_core::{stages::BobaUpdate, BobaResources, BobaResult, PearlStage};
+
+use crate::tween::TweenTrack;
+
+/// The id of a track played by a [`BobaTweener`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TweenId(u64);
+
+type CompletionCallback = Box<dyn FnOnce(&mut BobaResources)>;
+
+struct PlayingTrack {
+    id: TweenId,
+    track: Box<dyn TweenTrack>,
+    on_complete: Option<CompletionCallback>,
+}
+
+/// A pearl that plays tween tracks during the [`BobaUpdate`] stage.
+///
+/// Finished tracks are removed automatically, after calling their completion callback.
+pub struct BobaTweener {
+    /// A multiplier applied to the delta time before it is passed to the tracks.
+    pub time_scale: f32,
+
+    next_id: u64,
+    playing: Vec<PlayingTrack>,
+}
+
+boba_core::register_pearl_stages!(BobaTweener: BobaUpdate);
+
+impl Default for BobaTweener {
+    fn default() -> Self {
+        Self {
+            time_scale: 1.,
+            next_id: 0,
+            playing: Vec::new(),
+        }
+    }
+}
+
+impl BobaTweener {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Starts playing `track` on the next update.
+    pub fn play(&mut self, track: impl TweenTrack + 'static) -> TweenId {
+        self.push(Box::new(track), None)
+    }
+
+    /// Starts playing `track` on the next update, and calls `on_complete` once it finishes.
+    ///
+    /// The callback is called while the tweener is borrowed, so it cannot access the tweener pearl.
+    pub fn play_then(
+        &mut self,
+        track: impl TweenTrack + 'static,
+        on_complete: impl FnOnce(&mut BobaResources) + 'static,
+    ) -> TweenId {
+        self.push(Box::new(track), Some(Box::new(on_complete)))
+    }
+
+    /// Stops the track with `id` where it is, without calling its completion callback.
+    ///
+    /// Returns false if the track was not playing.
+    pub fn stop(&mut self, id: TweenId) -> bool {
+        let len = self.playing.len();
+        self.playing.retain(|p| p.id != id);
+        self.playing.len() != len
+    }
+
+    /// Stops all tracks, without calling their completion callbacks.
+    pub fn stop_all(&mut self) {
+        self.playing.clear();
+    }
+
+    /// Returns true if the track with `id` is still playing.
+    pub fn is_playing(&self, id: TweenId) -> bool {
+        self.playing.iter().any(|p| p.id == id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.playing.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.playing.is_empty()
+    }
+
+    fn push(
+        &mut self,
+        track: Box<dyn TweenTrack>,
+        on_complete: Option<CompletionCallback>,
+    ) -> TweenId {
+        let id = TweenId(self.next_id);
+        self.next_id += 1;
+        self.playing.push(PlayingTrack {
+            id,
+            track,
+            on_complete,
+        });
+        id
+    }
+}
+
+impl PearlStage<BobaUpdate> for BobaTweener {
+    fn update(&mut self, delta: &f32, resources: &mut BobaResources) -> BobaResult {
+        let delta = delta * self.time_scale;
+        let mut index = 0;
+        while let Some(playing) = self.playing.get_mut(index) {
+            if playing.track.advance(delta).is_none() {
+                index += 1;
+                continue;
+            }
+
+            let finished = self.playing.remove(index);
+            if let Some(on_complete) = finished.on_complete {
+                on_complete(resources);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use boba_core::{BobaResources, PearlStage};
+
+    use crate::tween::{Tween, TweenRepeat, ValueTween};
+
+    use super::BobaTweener;
+
+    #[test]
+    fn play_and_complete() {
+        let mut resources = BobaResources::default();
+        resources.add(0u32);
+
+        let mut tweener = BobaTweener::new();
+        let once = tweener.play_then(ValueTween::new(Tween::new(0., 1., 1.), |_| ()), |r| {
+            *r.get_mut::<u32>().unwrap() += 1;
+        });
+        let looping = tweener.play(ValueTween::new(
+            Tween::new(0., 1., 1.).with_repeat(TweenRepeat::Loop),
+            |_| (),
+        ));
+
+        tweener.update(&0.5, &mut resources).unwrap();
+        assert!(tweener.is_playing(once));
+        tweener.update(&0.5, &mut resources).unwrap();
+        assert!(!tweener.is_playing(once));
+        assert!(*resources.get::<u32>().unwrap() == 1);
+
+        assert!(tweener.stop(looping));
+        assert!(tweener.is_empty());
+    }
+}
@@ -0,0 +1,456 @@
+use std::{cell::Cell, hash::Hash};
+
+use boba_core::Pearl;
+use glam::Vec3;
+use indexmap::IndexMap;
+use log::error;
+
+use crate::{
+    geometry::{Aabb, BoundingSphere, Ray},
+    pearls::BobaTransform,
+};
+
+/// A result returned by the queries on a [`SpatialIndex`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpatialHit<K> {
+    pub key: K,
+    pub distance: f32,
+}
+
+struct Entry {
+    transform: Pearl<BobaTransform>,
+    local_bounds: Aabb,
+    bounds: Aabb,
+    tick: u64,
+    leaf: usize,
+}
+
+enum NodeKind<K> {
+    Leaf(K),
+    Branch(usize, usize),
+}
+
+struct Node<K> {
+    bounds: Aabb,
+    parent: Option<usize>,
+    kind: NodeKind<K>,
+}
+
+/// A dynamic bounding volume hierarchy over the world space bounds of transforms.
+///
+/// Each entry is identified by a key (usually the [`PearlId`](boba_core::PearlId) of the pearl that
+/// owns the transform), and has local space bounds that follow its transform around.
+/// Call [`Self::update`] once per frame, or add a [`SyncSpatialIndex`](crate::stages::SyncSpatialIndex)
+/// stage, to pick up transforms that have moved.
+///
+/// Leaves are stored with bounds grown by [`Self::margin`], so small movements do not
+/// need to restructure the tree.
+pub struct SpatialIndex<K> {
+    /// The distance leaf bounds are grown by in every direction.
+    pub margin: f32,
+
+    entries: IndexMap<K, Entry>,
+    nodes: Vec<Node<K>>,
+    free: Vec<usize>,
+    root: Option<usize>,
+}
+
+impl<K> Default for SpatialIndex<K> {
+    fn default() -> Self {
+        Self {
+            margin: 0.1,
+            entries: Default::default(),
+            nodes: Default::default(),
+            free: Default::default(),
+            root: None,
+        }
+    }
+}
+
+impl<K> SpatialIndex<K>
+where
+    K: Copy + Eq + Hash,
+{
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains(&self, key: K) -> bool {
+        self.entries.contains_key(&key)
+    }
+
+    /// Gets the world space bounds of the entry with `key`, as of the last update.
+    pub fn bounds(&self, key: K) -> Option<Aabb> {
+        self.entries.get(&key).map(|e| e.bounds)
+    }
+
+    /// Adds or replaces an entry that keeps `local_bounds` around `transform`.
+    pub fn insert(&mut self, key: K, transform: Pearl<BobaTransform>, local_bounds: Aabb) {
+        self.remove(key);
+
+        let (bounds, tick) = match transform.borrow() {
+            Ok(t) => (
+                local_bounds.transformed(&t.world_matrix()),
+                t.changed_tick(),
+            ),
+            Err(e) => {
+                error!("Could not read transform for spatial index. Error: {e}");
+                (local_bounds, 0)
+            }
+        };
+
+        let leaf = self.insert_leaf(key, bounds.expanded(self.margin));
+        let entry = Entry {
+            transform,
+            local_bounds,
+            bounds,
+            tick,
+            leaf,
+        };
+        self.entries.insert(key, entry);
+    }
+
+    /// Removes the entry with `key`, returning its transform.
+    pub fn remove(&mut self, key: K) -> Option<Pearl<BobaTransform>> {
+        let entry = self.entries.swap_remove(&key)?;
+        self.remove_leaf(entry.leaf);
+        Some(entry.transform)
+    }
+
+    /// Changes the local space bounds of the entry with `key`.
+    pub fn set_local_bounds(&mut self, key: K, local_bounds: Aabb) {
+        if let Some(entry) = self.entries.get_mut(&key) {
+            entry.local_bounds = local_bounds;
+            entry.tick = 0;
+        }
+    }
+
+    /// Recalculates the bounds of every entry whose transform has changed since the last update.
+    pub fn update(&mut self) {
+        for index in 0..self.entries.len() {
+            let (key, entry) = self.entries.get_index_mut(index).unwrap();
+            let (matrix, tick) = match entry.transform.borrow() {
+                Ok(t) => (t.world_matrix(), t.changed_tick()),
+                Err(e) => {
+                    error!("Could not update spatial index entry. Error: {e}");
+                    continue;
+                }
+            };
+
+            if tick == entry.tick {
+                continue;
+            }
+
+            entry.tick = tick;
+            entry.bounds = entry.local_bounds.transformed(&matrix);
+            if self.nodes[entry.leaf].bounds.contains_aabb(&entry.bounds) {
+                continue;
+            }
+
+            let (key, fat_bounds, leaf) = (*key, entry.bounds.expanded(self.margin), entry.leaf);
+            self.remove_leaf(leaf);
+            let leaf = self.insert_leaf(key, fat_bounds);
+            self.entries[index].leaf = leaf;
+        }
+    }
+
+    /// Finds the closest entry whose bounds are hit by `ray` within `max_distance`.
+    pub fn raycast(&self, ray: &Ray, max_distance: f32) -> Option<SpatialHit<K>> {
+        let best = Cell::new(None::<SpatialHit<K>>);
+        let limit = || best.get().map_or(max_distance, |b| b.distance);
+        self.traverse(
+            |bounds| match ray.intersect_aabb(bounds) {
+                Some(distance) => distance <= limit(),
+                None => false,
+            },
+            |key, entry| {
+                let Some(distance) = ray.intersect_aabb(&entry.bounds) else {
+                    return;
+                };
+
+                if distance <= limit() {
+                    best.set(Some(SpatialHit { key, distance }));
+                }
+            },
+        );
+
+        best.get()
+    }
+
+    /// Finds every entry whose bounds are hit by `ray` within `max_distance`, closest first.
+    pub fn raycast_all(&self, ray: &Ray, max_distance: f32) -> Vec<SpatialHit<K>> {
+        let mut hits = Vec::new();
+        let in_range = |bounds: &Aabb| {
+            let distance = ray.intersect_aabb(bounds)?;
+            (distance <= max_distance).then_some(distance)
+        };
+
+        self.traverse(
+            |bounds| in_range(bounds).is_some(),
+            |key, entry| {
+                if let Some(distance) = in_range(&entry.bounds) {
+                    hits.push(SpatialHit { key, distance });
+                }
+            },
+        );
+
+        hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        hits
+    }
+
+    /// Finds every entry whose bounds overlap `aabb`.
+    pub fn overlap_aabb(&self, aabb: &Aabb) -> Vec<K> {
+        let mut keys = Vec::new();
+        self.traverse(
+            |bounds| bounds.intersects_aabb(aabb),
+            |key, entry| {
+                if entry.bounds.intersects_aabb(aabb) {
+                    keys.push(key);
+                }
+            },
+        );
+        keys
+    }
+
+    /// Finds every entry whose bounds overlap `sphere`.
+    pub fn overlap_sphere(&self, sphere: &BoundingSphere) -> Vec<K> {
+        let mut keys = Vec::new();
+        self.traverse(
+            |bounds| bounds.intersects_sphere(sphere),
+            |key, entry| {
+                if entry.bounds.intersects_sphere(sphere) {
+                    keys.push(key);
+                }
+            },
+        );
+        keys
+    }
+
+    /// Finds the entry whose bounds are closest to `point` within `max_distance`.
+    ///
+    /// Points inside of an entries bounds are at a distance of zero.
+    pub fn nearest(&self, point: Vec3, max_distance: f32) -> Option<SpatialHit<K>> {
+        let best = Cell::new(None::<SpatialHit<K>>);
+        let limit = || best.get().map_or(max_distance, |b| b.distance);
+        let distance_to = |bounds: &Aabb| bounds.closest_point(point).distance(point);
+        self.traverse(
+            |bounds| distance_to(bounds) <= limit(),
+            |key, entry| {
+                let distance = distance_to(&entry.bounds);
+                if distance <= limit() {
+                    best.set(Some(SpatialHit { key, distance }));
+                }
+            },
+        );
+
+        best.get()
+    }
+
+    /// Walks the tree, entering nodes that pass `enter` and visiting the entries of the leaves found.
+    fn traverse(&self, mut enter: impl FnMut(&Aabb) -> bool, mut visit: impl FnMut(K, &Entry)) {
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !enter(&node.bounds) {
+                continue;
+            }
+
+            match node.kind {
+                NodeKind::Leaf(key) => visit(key, &self.entries[&key]),
+                NodeKind::Branch(a, b) => stack.extend([a, b]),
+            }
+        }
+    }
+
+    fn allocate(&mut self, node: Node<K>) -> usize {
+        match self.free.pop() {
+            Some(index) => {
+                self.nodes[index] = node;
+                index
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn insert_leaf(&mut self, key: K, bounds: Aabb) -> usize {
+        let leaf = self.allocate(Node {
+            bounds,
+            parent: None,
+            kind: NodeKind::Leaf(key),
+        });
+
+        let Some(mut sibling) = self.root else {
+            self.root = Some(leaf);
+            return leaf;
+        };
+
+        // descend towards the child that grows the least when the leaf is added
+        while let NodeKind::Branch(a, b) = self.nodes[sibling].kind {
+            let cost = |index: usize| {
+                let node_bounds = self.nodes[index].bounds;
+                node_bounds.union(&bounds).surface_area() - node_bounds.surface_area()
+            };
+
+            let combined = self.nodes[sibling].bounds.union(&bounds).surface_area();
+            let (cost_a, cost_b) = (cost(a), cost(b));
+            if combined < cost_a.min(cost_b) {
+                break;
+            }
+
+            sibling = match cost_a < cost_b {
+                true => a,
+                false => b,
+            };
+        }
+
+        let old_parent = self.nodes[sibling].parent;
+        let parent = self.allocate(Node {
+            bounds: self.nodes[sibling].bounds.union(&bounds),
+            parent: old_parent,
+            kind: NodeKind::Branch(sibling, leaf),
+        });
+
+        self.nodes[sibling].parent = Some(parent);
+        self.nodes[leaf].parent = Some(parent);
+        match old_parent {
+            None => self.root = Some(parent),
+            Some(old_parent) => self.replace_child(old_parent, sibling, parent),
+        }
+
+        self.refit(old_parent);
+        leaf
+    }
+
+    fn remove_leaf(&mut self, leaf: usize) {
+        self.free.push(leaf);
+        let Some(parent) = self.nodes[leaf].parent else {
+            self.root = None;
+            return;
+        };
+
+        let sibling = match self.nodes[parent].kind {
+            NodeKind::Branch(a, b) if a == leaf => b,
+            NodeKind::Branch(a, _) => a,
+            NodeKind::Leaf(_) => unreachable!("leaf parents are always branches"),
+        };
+
+        self.free.push(parent);
+        let grandparent = self.nodes[parent].parent;
+        self.nodes[sibling].parent = grandparent;
+        match grandparent {
+            None => self.root = Some(sibling),
+            Some(grandparent) => {
+                self.replace_child(grandparent, parent, sibling);
+                self.refit(Some(grandparent));
+            }
+        }
+    }
+
+    fn replace_child(&mut self, parent: usize, old: usize, new: usize) {
+        if let NodeKind::Branch(a, b) = &mut self.nodes[parent].kind {
+            match *a == old {
+                true => *a = new,
+                false => *b = new,
+            }
+        }
+    }
+
+    fn refit(&mut self, mut node: Option<usize>) {
+        while let Some(index) = node {
+            if let NodeKind::Branch(a, b) = self.nodes[index].kind {
+                self.nodes[index].bounds = self.nodes[a].bounds.union(&self.nodes[b].bounds);
+            }
+            node = self.nodes[index].parent;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use boba_core::Pearl;
+    use glam::Vec3;
+
+    use crate::{
+        geometry::{Aabb, BoundingSphere, Ray},
+        pearls::BobaTransform,
+    };
+
+    use super::SpatialIndex;
+
+    fn grid() -> (SpatialIndex<usize>, Vec<Pearl<BobaTransform>>) {
+        let mut index = SpatialIndex::new();
+        let unit = Aabb::from_center_half_extents(Vec3::ZERO, Vec3::splat(0.5));
+        let mut transforms = Vec::new();
+        for i in 0..100 {
+            let position = Vec3::new((i % 10) as f32 * 2., 0., (i / 10) as f32 * 2.);
+            let transform = Pearl::wrap(BobaTransform::from_position(position));
+            index.insert(i, transform.clone(), unit);
+            transforms.push(transform);
+        }
+
+        (index, transforms)
+    }
+
+    #[test]
+    fn queries() {
+        let (index, _) = grid();
+        assert!(index.len() == 100);
+
+        let ray = Ray::new(Vec3::new(4., 0., -10.), Vec3::Z);
+        let hit = index.raycast(&ray, f32::MAX).unwrap();
+        assert!(hit.key == 2 && hit.distance == 9.5);
+        assert!(index.raycast(&ray, 5.).is_none());
+
+        let hits = index.raycast_all(&ray, f32::MAX);
+        let keys: Vec<_> = hits.iter().map(|h| h.key).collect();
+        assert!(keys == (0..10).map(|i| i * 10 + 2).collect::<Vec<_>>());
+
+        let mut overlap = index.overlap_aabb(&Aabb::new(Vec3::ZERO, Vec3::new(2., 0., 2.)));
+        overlap.sort();
+        assert!(overlap == [0, 1, 10, 11]);
+        assert!(index
+            .overlap_sphere(&BoundingSphere::new(Vec3::new(1., 0., 1.), 0.5))
+            .is_empty());
+
+        let nearest = index.nearest(Vec3::new(7.9, 0., 6.2), f32::MAX).unwrap();
+        assert!(nearest.key == 34);
+        assert!(index.nearest(Vec3::Y * 10., 1.).is_none());
+    }
+
+    #[test]
+    fn follow_transforms() {
+        let (mut index, transforms) = grid();
+        let target = Vec3::new(100., 0., 100.);
+        transforms[55]
+            .borrow_mut()
+            .unwrap()
+            .set_local_position(target);
+        index.update();
+
+        let nearest = index.nearest(target, f32::MAX).unwrap();
+        assert!(nearest.key == 55 && nearest.distance == 0.);
+        assert!(index
+            .overlap_aabb(&Aabb::new(Vec3::splat(9.9), Vec3::splat(10.1)))
+            .is_empty());
+
+        index.remove(55);
+        assert!(index.nearest(target, 10.).is_none());
+        assert!(index.len() == 99);
+
+        for key in 0..100 {
+            index.remove(key);
+        }
+        assert!(index.is_empty() && index.raycast(&Ray::new(Vec3::ZERO, Vec3::X), 1.).is_none());
+    }
+}
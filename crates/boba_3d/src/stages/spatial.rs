@@ -0,0 +1,33 @@
+use std::{hash::Hash, marker::PhantomData};
+
+use boba_core::{BobaResources, BobaResult, BobaStage, PearlRegistry};
+
+use crate::spatial::SpatialIndex;
+
+/// A stage that updates the [`SpatialIndex`] resource with keys of type `K`.
+///
+/// Add it before any stage that runs spatial queries, so the queries see the
+/// transforms as they are at the start of the frame.
+pub struct SyncSpatialIndex<K> {
+    _key: PhantomData<K>,
+}
+
+impl<K> Default for SyncSpatialIndex<K> {
+    fn default() -> Self {
+        Self {
+            _key: Default::default(),
+        }
+    }
+}
+
+impl<K> BobaStage for SyncSpatialIndex<K>
+where
+    K: Copy + Eq + Hash + 'static,
+{
+    type Data = ();
+
+    fn run(&mut self, _: &mut PearlRegistry, resources: &mut BobaResources) -> BobaResult {
+        resources.get_mut::<SpatialIndex<K>>()?.update();
+        Ok(())
+    }
+}
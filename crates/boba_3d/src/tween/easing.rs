@@ -0,0 +1,96 @@
+use std::f32::consts::PI;
+
+/// A curve that maps the linear progress of a tween to an eased progress.
+///
+/// All curves map `0.0` to `0.0` and `1.0` to `1.0`, but some (like [`Easing::BackOut`])
+/// overshoot in between.
+#[derive(Debug, Default, Clone, Copy)]
+pub enum Easing {
+    #[default]
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    SineIn,
+    SineOut,
+    SineInOut,
+    ExpoIn,
+    ExpoOut,
+    ExpoInOut,
+    BackIn,
+    BackOut,
+    BackInOut,
+    ElasticOut,
+    BounceOut,
+    /// A custom curve.
+    Custom(fn(f32) -> f32),
+}
+
+impl Easing {
+    /// Applies the curve to `t`, which is clamped between `0.0` and `1.0`.
+    pub fn apply(&self, t: f32) -> f32 {
+        const BACK: f32 = 1.70158;
+        const BACK_IN_OUT: f32 = BACK * 1.525;
+
+        let t = t.clamp(0., 1.);
+        match self {
+            Easing::Linear => t,
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => 1. - (1. - t).powi(2),
+            Easing::QuadInOut => match t < 0.5 {
+                true => 2. * t * t,
+                false => 1. - (-2. * t + 2.).powi(2) / 2.,
+            },
+            Easing::CubicIn => t.powi(3),
+            Easing::CubicOut => 1. - (1. - t).powi(3),
+            Easing::CubicInOut => match t < 0.5 {
+                true => 4. * t.powi(3),
+                false => 1. - (-2. * t + 2.).powi(3) / 2.,
+            },
+            Easing::SineIn => 1. - (t * PI / 2.).cos(),
+            Easing::SineOut => (t * PI / 2.).sin(),
+            Easing::SineInOut => -((t * PI).cos() - 1.) / 2.,
+            Easing::ExpoIn => match t == 0. {
+                true => 0.,
+                false => 2f32.powf(10. * t - 10.),
+            },
+            Easing::ExpoOut => match t == 1. {
+                true => 1.,
+                false => 1. - 2f32.powf(-10. * t),
+            },
+            Easing::ExpoInOut => match t {
+                t if t == 0. || t == 1. => t,
+                t if t < 0.5 => 2f32.powf(20. * t - 10.) / 2.,
+                t => (2. - 2f32.powf(-20. * t + 10.)) / 2.,
+            },
+            Easing::BackIn => (BACK + 1.) * t.powi(3) - BACK * t * t,
+            Easing::BackOut => 1. + (BACK + 1.) * (t - 1.).powi(3) + BACK * (t - 1.).powi(2),
+            Easing::BackInOut => match t < 0.5 {
+                true => (2. * t).powi(2) * ((BACK_IN_OUT + 1.) * 2. * t - BACK_IN_OUT) / 2.,
+                false => {
+                    ((2. * t - 2.).powi(2) * ((BACK_IN_OUT + 1.) * (t * 2. - 2.) + BACK_IN_OUT)
+                        + 2.)
+                        / 2.
+                }
+            },
+            Easing::ElasticOut => match t {
+                t if t == 0. || t == 1. => t,
+                t => 2f32.powf(-10. * t) * ((t * 10. - 0.75) * (2. * PI / 3.)).sin() + 1.,
+            },
+            Easing::BounceOut => {
+                const N: f32 = 7.5625;
+                const D: f32 = 2.75;
+                match t {
+                    t if t < 1. / D => N * t * t,
+                    t if t < 2. / D => N * (t - 1.5 / D).powi(2) + 0.75,
+                    t if t < 2.5 / D => N * (t - 2.25 / D).powi(2) + 0.9375,
+                    t => N * (t - 2.625 / D).powi(2) + 0.984375,
+                }
+            }
+            Easing::Custom(f) => f(t),
+        }
+    }
+}
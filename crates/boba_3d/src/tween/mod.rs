@@ -0,0 +1,5 @@
+mod easing;
+mod track;
+
+pub use easing::*;
+pub use track::*;
@@ -0,0 +1,461 @@
+use boba_core::Pearl;
+use glam::{Quat, Vec2, Vec3, Vec4};
+use log::error;
+
+use crate::pearls::BobaTransform;
+
+use super::Easing;
+
+/// A value that can be interpolated by a [`Tween`].
+pub trait Tweenable: Copy + 'static {
+    /// Interpolates between `from` and `to`.
+    ///
+    /// `t` is usually between `0.0` and `1.0`, but can go outside of that range for overshooting easings.
+    fn interpolate(from: Self, to: Self, t: f32) -> Self;
+}
+
+impl Tweenable for f32 {
+    fn interpolate(from: Self, to: Self, t: f32) -> Self {
+        from + (to - from) * t
+    }
+}
+
+impl Tweenable for Vec2 {
+    fn interpolate(from: Self, to: Self, t: f32) -> Self {
+        from.lerp(to, t)
+    }
+}
+
+impl Tweenable for Vec3 {
+    fn interpolate(from: Self, to: Self, t: f32) -> Self {
+        from.lerp(to, t)
+    }
+}
+
+impl Tweenable for Vec4 {
+    fn interpolate(from: Self, to: Self, t: f32) -> Self {
+        from.lerp(to, t)
+    }
+}
+
+impl Tweenable for Quat {
+    fn interpolate(from: Self, to: Self, t: f32) -> Self {
+        from.slerp(to, t)
+    }
+}
+
+/// How a [`Tween`] behaves once it reaches its end.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TweenRepeat {
+    /// Stops at the end.
+    #[default]
+    Once,
+    /// Jumps back to the start and plays again forever.
+    Loop,
+    /// Plays backwards to the start, then forwards again, forever.
+    PingPong,
+}
+
+/// An interpolation between two values over time.
+///
+/// A tween only keeps track of time. To apply its value to something,
+/// wrap it in a [`ValueTween`] or a [`TransformTween`].
+#[derive(Debug, Clone)]
+pub struct Tween<T> {
+    pub from: T,
+    pub to: T,
+    pub duration: f32,
+    pub easing: Easing,
+    pub repeat: TweenRepeat,
+
+    elapsed: f32,
+}
+
+impl<T: Tweenable> Tween<T> {
+    pub fn new(from: T, to: T, duration: f32) -> Self {
+        Self {
+            from,
+            to,
+            duration,
+            easing: Easing::Linear,
+            repeat: TweenRepeat::Once,
+            elapsed: 0.,
+        }
+    }
+
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    pub fn with_repeat(mut self, repeat: TweenRepeat) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    /// Gets the time since the start of the current loop.
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    /// Gets the linear progress between `from` and `to`, before easing is applied.
+    pub fn progress(&self) -> f32 {
+        if self.duration <= 0. {
+            return 1.;
+        }
+
+        let progress = self.elapsed / self.duration;
+        match self.repeat == TweenRepeat::PingPong && progress > 1. {
+            true => 2. - progress,
+            false => progress.min(1.),
+        }
+    }
+
+    /// Gets the current value of the tween.
+    pub fn value(&self) -> T {
+        T::interpolate(self.from, self.to, self.easing.apply(self.progress()))
+    }
+
+    /// Returns true if the tween has reached its end and will not play any further.
+    ///
+    /// Looping tweens only finish if their duration is zero.
+    pub fn is_finished(&self) -> bool {
+        self.duration <= 0. || (self.repeat == TweenRepeat::Once && self.elapsed >= self.duration)
+    }
+
+    /// Rewinds the tween to the start.
+    pub fn reset(&mut self) {
+        self.elapsed = 0.;
+    }
+
+    /// Advances the tween by `delta` seconds.
+    ///
+    /// Returns the time that was left over once the tween has finished, or `None` while it is still running.
+    pub fn advance(&mut self, delta: f32) -> Option<f32> {
+        if self.duration <= 0. {
+            return Some(delta);
+        }
+
+        self.elapsed += delta;
+        match self.repeat {
+            TweenRepeat::Once => {
+                let leftover = self.elapsed - self.duration;
+                if leftover < 0. {
+                    return None;
+                }
+
+                self.elapsed = self.duration;
+                Some(leftover)
+            }
+            TweenRepeat::Loop => {
+                self.elapsed %= self.duration;
+                None
+            }
+            TweenRepeat::PingPong => {
+                self.elapsed %= 2. * self.duration;
+                None
+            }
+        }
+    }
+}
+
+/// Something that plays over time, and applies its state every time it is advanced.
+///
+/// Tracks are played by a [`BobaTweener`](crate::pearls::BobaTweener), and can be chained
+/// using a [`TweenSequence`].
+pub trait TweenTrack {
+    /// Advances the track by `delta` seconds and applies its current state.
+    ///
+    /// Returns the time that was left over once the track has finished, or `None` while it is still running.
+    fn advance(&mut self, delta: f32) -> Option<f32>;
+
+    /// Rewinds the track to the start.
+    fn reset(&mut self);
+}
+
+/// A [`Tween`] that passes its value to a setter every time it is advanced.
+///
+/// This is used to animate arbitrary values, like the color of a UI element
+/// or the volume of a sound.
+pub struct ValueTween<T, F> {
+    tween: Tween<T>,
+    setter: F,
+}
+
+impl<T, F> ValueTween<T, F>
+where
+    T: Tweenable,
+    F: FnMut(T),
+{
+    pub fn new(tween: Tween<T>, setter: F) -> Self {
+        Self { tween, setter }
+    }
+}
+
+impl<T, F> TweenTrack for ValueTween<T, F>
+where
+    T: Tweenable,
+    F: FnMut(T),
+{
+    fn advance(&mut self, delta: f32) -> Option<f32> {
+        let leftover = self.tween.advance(delta);
+        (self.setter)(self.tween.value());
+        leftover
+    }
+
+    fn reset(&mut self) {
+        self.tween.reset();
+    }
+}
+
+enum TransformProperty {
+    Position(Tween<Vec3>),
+    Rotation(Tween<Quat>),
+    Scale(Tween<Vec3>),
+}
+
+/// A [`Tween`] that animates the local position, rotation or scale of a [`BobaTransform`].
+pub struct TransformTween {
+    transform: Pearl<BobaTransform>,
+    property: TransformProperty,
+}
+
+impl TransformTween {
+    pub fn position(transform: Pearl<BobaTransform>, tween: Tween<Vec3>) -> Self {
+        let property = TransformProperty::Position(tween);
+        Self {
+            transform,
+            property,
+        }
+    }
+
+    pub fn rotation(transform: Pearl<BobaTransform>, tween: Tween<Quat>) -> Self {
+        let property = TransformProperty::Rotation(tween);
+        Self {
+            transform,
+            property,
+        }
+    }
+
+    pub fn scale(transform: Pearl<BobaTransform>, tween: Tween<Vec3>) -> Self {
+        let property = TransformProperty::Scale(tween);
+        Self {
+            transform,
+            property,
+        }
+    }
+}
+
+impl TweenTrack for TransformTween {
+    fn advance(&mut self, delta: f32) -> Option<f32> {
+        let leftover = match &mut self.property {
+            TransformProperty::Position(tween) => tween.advance(delta),
+            TransformProperty::Rotation(tween) => tween.advance(delta),
+            TransformProperty::Scale(tween) => tween.advance(delta),
+        };
+
+        let mut transform = match self.transform.borrow_mut() {
+            Ok(transform) => transform,
+            Err(e) => {
+                error!("Could not apply tween to transform. Error: {e}");
+                return leftover;
+            }
+        };
+
+        match &self.property {
+            TransformProperty::Position(tween) => transform.set_local_position(tween.value()),
+            TransformProperty::Rotation(tween) => transform.set_local_rotation(tween.value()),
+            TransformProperty::Scale(tween) => transform.set_local_scale(tween.value()),
+        }
+
+        leftover
+    }
+
+    fn reset(&mut self) {
+        match &mut self.property {
+            TransformProperty::Position(tween) => tween.reset(),
+            TransformProperty::Rotation(tween) => tween.reset(),
+            TransformProperty::Scale(tween) => tween.reset(),
+        }
+    }
+}
+
+struct TweenDelay {
+    duration: f32,
+    elapsed: f32,
+}
+
+impl TweenTrack for TweenDelay {
+    fn advance(&mut self, delta: f32) -> Option<f32> {
+        self.elapsed += delta;
+        match self.elapsed >= self.duration {
+            true => Some(self.elapsed - self.duration),
+            false => None,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.elapsed = 0.;
+    }
+}
+
+struct TweenCall<F>(F);
+
+impl<F: FnMut()> TweenTrack for TweenCall<F> {
+    fn advance(&mut self, delta: f32) -> Option<f32> {
+        (self.0)();
+        Some(delta)
+    }
+
+    fn reset(&mut self) {}
+}
+
+/// A list of tracks that are played one after the other.
+///
+/// Time left over by a finished track is passed on to the next one,
+/// so sequences stay in sync regardless of the frame rate.
+#[derive(Default)]
+pub struct TweenSequence {
+    tracks: Vec<Box<dyn TweenTrack>>,
+    current: usize,
+    looping: bool,
+}
+
+impl TweenSequence {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Appends `track` to the end of the sequence.
+    pub fn then(mut self, track: impl TweenTrack + 'static) -> Self {
+        self.tracks.push(Box::new(track));
+        self
+    }
+
+    /// Appends a pause of `duration` seconds to the end of the sequence.
+    pub fn then_wait(self, duration: f32) -> Self {
+        self.then(TweenDelay {
+            duration,
+            elapsed: 0.,
+        })
+    }
+
+    /// Appends a call to `callback` to the end of the sequence.
+    pub fn then_call(self, callback: impl FnMut() + 'static) -> Self {
+        self.then(TweenCall(callback))
+    }
+
+    /// Makes the sequence start over every time it reaches the end.
+    pub fn looping(mut self) -> Self {
+        self.looping = true;
+        self
+    }
+}
+
+impl TweenTrack for TweenSequence {
+    fn advance(&mut self, mut delta: f32) -> Option<f32> {
+        let mut pass_start = delta;
+        while let Some(track) = self.tracks.get_mut(self.current) {
+            delta = track.advance(delta)?;
+            self.current += 1;
+
+            if self.current == self.tracks.len() && self.looping {
+                // a loop that takes no time would never return
+                if delta >= pass_start {
+                    return None;
+                }
+
+                pass_start = delta;
+                self.reset();
+            }
+        }
+
+        Some(delta)
+    }
+
+    fn reset(&mut self) {
+        self.current = 0;
+        for track in self.tracks.iter_mut() {
+            track.reset();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use boba_core::Pearl;
+    use glam::Vec3;
+
+    use crate::{pearls::BobaTransform, tween::Easing};
+
+    use super::{TransformTween, Tween, TweenRepeat, TweenSequence, TweenTrack, ValueTween};
+
+    #[test]
+    fn once() {
+        let mut tween = Tween::new(0., 10., 2.);
+        assert!(tween.advance(1.).is_none());
+        assert!(tween.value() == 5.);
+
+        assert!(tween.advance(1.5) == Some(0.5));
+        assert!(tween.value() == 10.);
+        assert!(tween.is_finished());
+
+        let mut tween = Tween::new(0., 1., 1.).with_easing(Easing::QuadIn);
+        tween.advance(0.5);
+        assert!(tween.value() == 0.25);
+    }
+
+    #[test]
+    fn repeat() {
+        let mut tween = Tween::new(0., 4., 1.).with_repeat(TweenRepeat::Loop);
+        assert!(tween.advance(1.25).is_none());
+        assert!(tween.value() == 1.);
+
+        let mut tween = Tween::new(0., 4., 1.).with_repeat(TweenRepeat::PingPong);
+        assert!(tween.advance(1.25).is_none());
+        assert!(tween.value() == 3.);
+        tween.advance(1.);
+        assert!(tween.value() == 1.);
+        assert!(!tween.is_finished());
+    }
+
+    #[test]
+    fn sequence() {
+        let value = Rc::new(RefCell::new(0.));
+        let calls = Rc::new(RefCell::new(0));
+        let transform = Pearl::wrap(BobaTransform::default());
+
+        let setter = value.clone();
+        let counter = calls.clone();
+        let mut sequence = TweenSequence::new()
+            .then(ValueTween::new(Tween::new(0., 1., 1.), move |v| {
+                *setter.borrow_mut() = v
+            }))
+            .then_wait(1.)
+            .then(TransformTween::position(
+                transform.clone(),
+                Tween::new(Vec3::ZERO, Vec3::X, 1.),
+            ))
+            .then_call(move || *counter.borrow_mut() += 1);
+
+        assert!(sequence.advance(0.5).is_none());
+        assert!(*value.borrow() == 0.5);
+
+        // the leftover time carries over into the next tracks
+        assert!(sequence.advance(2.).is_none());
+        assert!(*value.borrow() == 1.);
+        let position = transform.borrow().unwrap().local_position();
+        assert!(position.abs_diff_eq(Vec3::X * 0.5, 1e-5));
+
+        assert!(sequence.advance(1.) == Some(0.5));
+        assert!(*calls.borrow() == 1);
+
+        let mut sequence = sequence.looping();
+        sequence.reset();
+        assert!(sequence.advance(3.5).is_none());
+        assert!(*calls.borrow() == 2);
+        assert!(*value.borrow() == 0.5);
+    }
+}
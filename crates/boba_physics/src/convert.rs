@@ -0,0 +1,34 @@
+use boba_3d::glam::{Mat4, Quat, Vec3};
+use rapier3d::{
+    math::{Isometry, Real, Vector},
+    na::{Quaternion, Translation3, UnitQuaternion},
+};
+
+pub fn to_vector(v: Vec3) -> Vector<Real> {
+    Vector::new(v.x, v.y, v.z)
+}
+
+pub fn from_vector(v: &Vector<Real>) -> Vec3 {
+    Vec3::new(v.x, v.y, v.z)
+}
+
+pub fn to_isometry(position: Vec3, rotation: Quat) -> Isometry<Real> {
+    let rotation = Quaternion::new(rotation.w, rotation.x, rotation.y, rotation.z);
+    Isometry::from_parts(
+        Translation3::new(position.x, position.y, position.z),
+        UnitQuaternion::from_quaternion(rotation),
+    )
+}
+
+/// Converts a matrix to an isometry, dropping its scale.
+pub fn matrix_to_isometry(matrix: &Mat4) -> Isometry<Real> {
+    let (_, rotation, position) = matrix.to_scale_rotation_translation();
+    to_isometry(position, rotation)
+}
+
+pub fn from_isometry(isometry: &Isometry<Real>) -> (Vec3, Quat) {
+    let position = from_vector(&isometry.translation.vector);
+    let rotation = isometry.rotation.quaternion();
+    let rotation = Quat::from_xyzw(rotation.i, rotation.j, rotation.k, rotation.w);
+    (position, rotation)
+}
@@ -0,0 +1,11 @@
+mod convert;
+mod plugin;
+mod world;
+
+pub use plugin::*;
+pub use world::*;
+
+pub mod pearls;
+pub mod stages;
+
+pub use rapier3d;
@@ -0,0 +1,95 @@
+use boba_3d::pearls::BobaTransform;
+use boba_core::{register_pearl_stages, BobaResources, BobaResult, Pearl, PearlStage};
+use rapier3d::prelude::{Collider, ColliderHandle};
+
+use crate::{convert::matrix_to_isometry, stages::PhysicsSync, OwnedHandle, PhysicsWorld};
+
+use super::BobaRigidBody;
+
+/// A pearl that adds a collider to the [`PhysicsWorld`].
+///
+/// Colliders with a body are attached to it, offset by where `transform` is relative to the
+/// transform of the body at the time it is added. After that, `transform` is not read again
+/// and the collider only moves with its body. Colliders without a body are static,
+/// and follow `transform` around.
+///
+/// The collider is removed on the next physics step after the pearl is destroyed.
+pub struct BobaCollider {
+    pub transform: Pearl<BobaTransform>,
+    pub body: Option<Pearl<BobaRigidBody>>,
+
+    pending: Option<Collider>,
+    handle: Option<OwnedHandle<ColliderHandle>>,
+}
+
+register_pearl_stages!(BobaCollider: PhysicsSync);
+
+impl BobaCollider {
+    /// Creates a static collider, usually from a
+    /// [`ColliderBuilder`](rapier3d::prelude::ColliderBuilder).
+    pub fn new(transform: Pearl<BobaTransform>, collider: impl Into<Collider>) -> Self {
+        Self {
+            transform,
+            body: None,
+            pending: Some(collider.into()),
+            handle: None,
+        }
+    }
+
+    /// Creates a collider attached to `body`.
+    pub fn attached(
+        transform: Pearl<BobaTransform>,
+        collider: impl Into<Collider>,
+        body: Pearl<BobaRigidBody>,
+    ) -> Self {
+        Self {
+            transform,
+            body: Some(body),
+            pending: Some(collider.into()),
+            handle: None,
+        }
+    }
+
+    /// Gets the handle of the collider in the [`PhysicsWorld`], once it has been added.
+    pub fn handle(&self) -> Option<ColliderHandle> {
+        self.handle.as_ref().map(OwnedHandle::handle)
+    }
+}
+
+impl PearlStage<PhysicsSync> for BobaCollider {
+    fn update(&mut self, _: &(), resources: &mut BobaResources) -> BobaResult {
+        if self.pending.is_none() {
+            return Ok(());
+        }
+
+        // everything is borrowed before the collider is taken,
+        // so it is kept for the next step if anything is not available yet
+        let mut world = resources.get_mut::<PhysicsWorld>()?;
+        let transform = self.transform.borrow()?;
+        let Some(body) = &self.body else {
+            let Some(collider) = self.pending.take() else {
+                return Ok(());
+            };
+
+            let handle = world.insert_collider(self.transform.clone(), collider)?;
+            self.handle = Some(world.owned(handle));
+            return Ok(());
+        };
+
+        let body = body.borrow()?;
+        let Some(parent) = body.handle() else {
+            // the body has not been added yet, so try again next step
+            return Ok(());
+        };
+
+        let offset = body.transform.borrow()?.world_matrix().inverse() * transform.world_matrix();
+        let Some(mut collider) = self.pending.take() else {
+            return Ok(());
+        };
+
+        collider.set_position_wrt_parent(matrix_to_isometry(&offset));
+        let handle = world.insert_collider_with_parent(collider, parent);
+        self.handle = Some(world.owned(handle));
+        Ok(())
+    }
+}
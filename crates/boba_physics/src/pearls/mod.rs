@@ -0,0 +1,5 @@
+mod collider;
+mod rigid_body;
+
+pub use collider::*;
+pub use rigid_body::*;
@@ -0,0 +1,57 @@
+use boba_3d::pearls::BobaTransform;
+use boba_core::{register_pearl_stages, BobaResources, BobaResult, Pearl, PearlStage};
+use rapier3d::prelude::{RigidBody, RigidBodyHandle};
+
+use crate::{stages::PhysicsSync, OwnedHandle, PhysicsWorld};
+
+/// A pearl that adds a rigid body to the [`PhysicsWorld`], and moves `transform` along with it.
+///
+/// The body is added on the next physics step after the pearl is registered,
+/// and removed on the next step after the pearl is destroyed.
+pub struct BobaRigidBody {
+    pub transform: Pearl<BobaTransform>,
+
+    pending: Option<RigidBody>,
+    handle: Option<OwnedHandle<RigidBodyHandle>>,
+}
+
+register_pearl_stages!(BobaRigidBody: PhysicsSync);
+
+impl BobaRigidBody {
+    /// Creates a new rigid body pearl, usually from a
+    /// [`RigidBodyBuilder`](rapier3d::prelude::RigidBodyBuilder).
+    ///
+    /// The position of the body is ignored, and taken from `transform` instead.
+    pub fn new(transform: Pearl<BobaTransform>, body: impl Into<RigidBody>) -> Self {
+        Self {
+            transform,
+            pending: Some(body.into()),
+            handle: None,
+        }
+    }
+
+    /// Gets the handle of the body in the [`PhysicsWorld`], once it has been added.
+    pub fn handle(&self) -> Option<RigidBodyHandle> {
+        self.handle.as_ref().map(OwnedHandle::handle)
+    }
+}
+
+impl PearlStage<PhysicsSync> for BobaRigidBody {
+    fn update(&mut self, _: &(), resources: &mut BobaResources) -> BobaResult {
+        if self.pending.is_none() {
+            return Ok(());
+        }
+
+        // the world and transform are borrowed before the body is taken,
+        // so it is kept for the next step if either is not available yet
+        let mut world = resources.get_mut::<PhysicsWorld>()?;
+        let _transform = self.transform.borrow()?;
+        let Some(body) = self.pending.take() else {
+            return Ok(());
+        };
+
+        let handle = world.insert_body(self.transform.clone(), body)?;
+        self.handle = Some(world.owned(handle));
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+use boba_3d::glam::Vec3;
+use boba_core::{BobaResources, PearlRegistry, StageCollection};
+use milk_tea::MilkTeaPlugin;
+
+use crate::{stages::PhysicsStep, PhysicsWorld};
+
+/// Adds the [`PhysicsWorld`] resource and the [`PhysicsStep`] stage to an app.
+///
+/// The step is prepended to the main stages, so updates see the results of the physics for that frame.
+pub struct PhysicsPlugin {
+    pub gravity: Vec3,
+    pub timestep: f32,
+    pub max_steps: u32,
+}
+
+impl Default for PhysicsPlugin {
+    fn default() -> Self {
+        Self {
+            gravity: Vec3::NEG_Y * 9.81,
+            timestep: 1. / 60.,
+            max_steps: 5,
+        }
+    }
+}
+
+impl MilkTeaPlugin for PhysicsPlugin {
+    fn setup(
+        self,
+        _: &mut PearlRegistry,
+        _: &mut StageCollection,
+        main_stages: &mut StageCollection,
+        resources: &mut BobaResources,
+    ) {
+        resources.add(PhysicsWorld::new(self.gravity, self.timestep));
+        let mut step = PhysicsStep::default();
+        step.max_steps = self.max_steps;
+        main_stages.prepend(step);
+    }
+}
@@ -0,0 +1,3 @@
+mod step;
+
+pub use step::*;
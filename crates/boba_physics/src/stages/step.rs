@@ -0,0 +1,286 @@
+use std::time::Instant;
+
+use boba_core::{BobaResources, BobaResult, BobaStage, PearlRegistry};
+use rapier3d::prelude::CollisionEvent;
+
+use crate::PhysicsWorld;
+
+/// The stage that steps the [`PhysicsWorld`] at a fixed rate.
+///
+/// Every frame the elapsed time is accumulated, and as many fixed steps are run as fit in it,
+/// up to [`Self::max_steps`]. Before each step the [`PhysicsSync`] pearls are updated,
+/// and after each step an [`OnPhysicsCollision`] stage is run for every collision event.
+pub struct PhysicsStep {
+    /// The maximum amount of steps run in a single frame.
+    ///
+    /// Time beyond that is dropped, so a slow frame does not cause even slower frames after it.
+    pub max_steps: u32,
+
+    instant: Option<Instant>,
+    accumulator: f32,
+}
+
+impl Default for PhysicsStep {
+    fn default() -> Self {
+        Self {
+            max_steps: 5,
+            instant: None,
+            accumulator: 0.,
+        }
+    }
+}
+
+impl PhysicsStep {
+    /// Advances the simulation by `delta` seconds, running as many fixed steps as needed.
+    ///
+    /// This is called by [`BobaStage::run`] with the time since the last frame,
+    /// and can be called directly to drive the simulation manually.
+    pub fn advance(
+        &mut self,
+        delta: f32,
+        registry: &mut PearlRegistry,
+        resources: &mut BobaResources,
+    ) -> BobaResult {
+        let timestep = resources.get::<PhysicsWorld>()?.timestep();
+        if timestep <= 0. {
+            return Ok(());
+        }
+
+        self.accumulator += delta;
+        let mut steps = 0;
+        while self.accumulator >= timestep {
+            if steps == self.max_steps {
+                self.accumulator %= timestep;
+                break;
+            }
+
+            registry.run_stage::<PhysicsSync>(&(), resources);
+            let events = resources.get_mut::<PhysicsWorld>()?.step();
+            for event in events {
+                OnPhysicsCollision { event }.run(registry, resources)?;
+            }
+
+            self.accumulator -= timestep;
+            steps += 1;
+        }
+
+        Ok(())
+    }
+}
+
+impl BobaStage for PhysicsStep {
+    type Data = f32;
+
+    fn run(&mut self, registry: &mut PearlRegistry, resources: &mut BobaResources) -> BobaResult {
+        let delta = match self.instant {
+            Some(instant) => instant.elapsed().as_secs_f32(),
+            None => 0.,
+        };
+
+        self.instant = Some(Instant::now());
+        self.advance(delta, registry, resources)
+    }
+}
+
+/// A stage run right before every fixed step, used by pearls to add themselves to the [`PhysicsWorld`].
+pub struct PhysicsSync;
+
+impl BobaStage for PhysicsSync {
+    type Data = ();
+
+    fn run(&mut self, registry: &mut PearlRegistry, resources: &mut BobaResources) -> BobaResult {
+        registry.run_stage::<PhysicsSync>(&(), resources);
+        Ok(())
+    }
+}
+
+/// A stage run for every collision that starts or stops during a fixed step.
+pub struct OnPhysicsCollision {
+    event: CollisionEvent,
+}
+
+impl BobaStage for OnPhysicsCollision {
+    type Data = CollisionEvent;
+
+    fn run(&mut self, registry: &mut PearlRegistry, resources: &mut BobaResources) -> BobaResult {
+        registry.run_stage::<OnPhysicsCollision>(&self.event, resources);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use boba_3d::{glam::Vec3, pearls::BobaTransform};
+    use boba_core::{
+        register_pearl_stages, BobaResources, BobaResult, Pearl, PearlRegistry, PearlStage,
+    };
+    use rapier3d::prelude::{ColliderBuilder, CollisionEvent, RigidBodyBuilder};
+
+    use crate::{
+        pearls::{BobaCollider, BobaRigidBody},
+        PhysicsWorld,
+    };
+
+    use super::{OnPhysicsCollision, PhysicsStep, PhysicsSync};
+
+    #[derive(Default)]
+    struct CollisionCounter {
+        started: usize,
+    }
+
+    register_pearl_stages!(CollisionCounter: OnPhysicsCollision);
+
+    impl PearlStage<OnPhysicsCollision> for CollisionCounter {
+        fn update(&mut self, data: &CollisionEvent, _: &mut BobaResources) -> BobaResult {
+            if data.started() {
+                self.started += 1;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn falling_ball() {
+        let mut registry = PearlRegistry::default();
+        let mut resources = BobaResources::default();
+        resources.add(PhysicsWorld::default());
+
+        let ground = Pearl::wrap(BobaTransform::default());
+        let ground_collider = ColliderBuilder::cuboid(10., 0.5, 10.)
+            .active_events(rapier3d::prelude::ActiveEvents::COLLISION_EVENTS);
+        registry.add(&Pearl::wrap(BobaCollider::new(ground, ground_collider)));
+
+        // add the collider first, to check that it waits for the body
+        let ball = Pearl::wrap(BobaTransform::from_position(Vec3::Y * 3.));
+        let body = Pearl::wrap(BobaRigidBody::new(
+            ball.clone(),
+            RigidBodyBuilder::dynamic(),
+        ));
+        let collider = ColliderBuilder::ball(0.5);
+        let collider = Pearl::wrap(BobaCollider::attached(ball.clone(), collider, body.clone()));
+        registry.add(&collider);
+        registry.add(&body);
+
+        let counter = Pearl::wrap(CollisionCounter::default());
+        registry.add(&counter);
+
+        let mut step = PhysicsStep::default();
+        for _ in 0..180 {
+            step.advance(1. / 60., &mut registry, &mut resources)
+                .unwrap();
+        }
+
+        assert!(body.borrow().unwrap().handle().is_some());
+        assert!(collider.borrow().unwrap().handle().is_some());
+        assert!(counter.borrow().unwrap().started == 1);
+
+        // the ball should rest on top of the ground
+        let height = ball.borrow().unwrap().world_position().y;
+        assert!((height - 1.).abs() < 0.05);
+
+        // moving the transform teleports the body
+        ball.borrow_mut()
+            .unwrap()
+            .set_local_position(Vec3::new(20., 5., 0.));
+        step.advance(1. / 60., &mut registry, &mut resources)
+            .unwrap();
+        let position = ball.borrow().unwrap().world_position();
+        assert!(position.x == 20. && position.y < 5.);
+    }
+
+    #[test]
+    fn pending_until_ready() {
+        let mut registry = PearlRegistry::default();
+        let mut resources = BobaResources::default();
+
+        let ball = Pearl::wrap(BobaTransform::from_position(Vec3::Y));
+        let body = Pearl::wrap(BobaRigidBody::new(
+            ball.clone(),
+            RigidBodyBuilder::dynamic(),
+        ));
+        let collider = ColliderBuilder::ball(0.5);
+        let collider = Pearl::wrap(BobaCollider::attached(ball.clone(), collider, body.clone()));
+        let floor = Pearl::wrap(BobaTransform::default());
+        let ground = BobaCollider::new(floor.clone(), ColliderBuilder::cuboid(1., 1., 1.));
+        let ground = Pearl::wrap(ground);
+        registry.add(&body);
+        registry.add(&collider);
+        registry.add(&ground);
+
+        // syncing before the world exists keeps everything pending
+        registry.run_stage::<PhysicsSync>(&(), &mut resources);
+        resources.add(PhysicsWorld::default());
+
+        // and so does syncing while the transforms are borrowed
+        let ball_data = ball.borrow_mut().unwrap();
+        let floor_data = floor.borrow_mut().unwrap();
+        registry.run_stage::<PhysicsSync>(&(), &mut resources);
+        drop((ball_data, floor_data));
+        assert!(body.borrow().unwrap().handle().is_none());
+        assert!(ground.borrow().unwrap().handle().is_none());
+
+        let mut step = PhysicsStep::default();
+        step.advance(1. / 60., &mut registry, &mut resources)
+            .unwrap();
+        step.advance(1. / 60., &mut registry, &mut resources)
+            .unwrap();
+        assert!(body.borrow().unwrap().handle().is_some());
+        assert!(collider.borrow().unwrap().handle().is_some());
+        assert!(ground.borrow().unwrap().handle().is_some());
+
+        let world = resources.get::<PhysicsWorld>().unwrap();
+        assert!(world.colliders.len() == 2);
+    }
+
+    #[test]
+    fn destroyed_pearls() {
+        let mut registry = PearlRegistry::default();
+        let mut resources = BobaResources::default();
+        resources.add(PhysicsWorld::default());
+
+        let ball = Pearl::wrap(BobaTransform::from_position(Vec3::Y));
+        let body = Pearl::wrap(BobaRigidBody::new(
+            ball.clone(),
+            RigidBodyBuilder::dynamic(),
+        ));
+        let collider = ColliderBuilder::ball(0.5);
+        let collider = Pearl::wrap(BobaCollider::attached(ball.clone(), collider, body.clone()));
+        let wall = Pearl::wrap(BobaTransform::from_position(Vec3::X * 5.));
+        let wall = Pearl::wrap(BobaCollider::new(wall, ColliderBuilder::cuboid(1., 1., 1.)));
+        registry.add(&body);
+        registry.add(&collider);
+        registry.add(&wall);
+
+        let mut step = PhysicsStep::default();
+        step.advance(1. / 60., &mut registry, &mut resources)
+            .unwrap();
+        step.advance(1. / 60., &mut registry, &mut resources)
+            .unwrap();
+        let world = resources.get::<PhysicsWorld>().unwrap();
+        assert!(world.bodies.len() == 1 && world.colliders.len() == 2);
+        drop(world);
+
+        // destroying the body removes it and its attached collider on the next step,
+        // and the transform is no longer moved by the simulation
+        body.destroy().unwrap();
+        step.advance(1. / 60., &mut registry, &mut resources)
+            .unwrap();
+        let world = resources.get::<PhysicsWorld>().unwrap();
+        assert!(world.bodies.is_empty() && world.colliders.len() == 1);
+        drop(world);
+
+        let height = ball.borrow().unwrap().world_position().y;
+        step.advance(1. / 60., &mut registry, &mut resources)
+            .unwrap();
+        assert!(ball.borrow().unwrap().world_position().y == height);
+
+        wall.destroy().unwrap();
+        step.advance(1. / 60., &mut registry, &mut resources)
+            .unwrap();
+        assert!(resources
+            .get::<PhysicsWorld>()
+            .unwrap()
+            .colliders
+            .is_empty());
+    }
+}
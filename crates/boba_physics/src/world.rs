@@ -0,0 +1,372 @@
+use boba_3d::{glam::Vec3, pearls::BobaTransform};
+use boba_core::{Pearl, PearlError};
+use indexmap::IndexMap;
+use log::error;
+use rapier3d::{
+    crossbeam::channel::{unbounded, Receiver, Sender},
+    prelude::*,
+};
+
+use crate::convert::{from_isometry, from_vector, matrix_to_isometry, to_vector};
+
+struct Tracked {
+    transform: Pearl<BobaTransform>,
+    tick: u64,
+}
+
+/// A body or collider whose owner was dropped
+pub(crate) enum Removal {
+    Body(RigidBodyHandle),
+    Collider(ColliderHandle),
+}
+
+impl From<RigidBodyHandle> for Removal {
+    fn from(handle: RigidBodyHandle) -> Self {
+        Self::Body(handle)
+    }
+}
+
+impl From<ColliderHandle> for Removal {
+    fn from(handle: ColliderHandle) -> Self {
+        Self::Collider(handle)
+    }
+}
+
+/// A handle that removes its body or collider from the [`PhysicsWorld`] before the next step
+/// once it is dropped.
+///
+/// The physics pearls own one of these, so destroying a pearl also removes it from the simulation.
+pub(crate) struct OwnedHandle<H: Copy + Into<Removal>> {
+    handle: H,
+    removals: Sender<Removal>,
+}
+
+impl<H: Copy + Into<Removal>> OwnedHandle<H> {
+    pub fn handle(&self) -> H {
+        self.handle
+    }
+}
+
+impl<H: Copy + Into<Removal>> Drop for OwnedHandle<H> {
+    fn drop(&mut self) {
+        // the world may already be gone, in which case there is nothing to remove
+        let _ = self.removals.send(self.handle.into());
+    }
+}
+
+/// The rapier simulation, stored as a resource.
+///
+/// Bodies and colliders are usually added through the [`BobaRigidBody`](crate::pearls::BobaRigidBody)
+/// and [`BobaCollider`](crate::pearls::BobaCollider) pearls, which keep them in sync with a [`BobaTransform`].
+/// The rapier sets are public so that joints, forces and queries can be used directly.
+pub struct PhysicsWorld {
+    pub gravity: Vec3,
+    pub integration_parameters: IntegrationParameters,
+    pub bodies: RigidBodySet,
+    pub colliders: ColliderSet,
+    pub impulse_joints: ImpulseJointSet,
+    pub multibody_joints: MultibodyJointSet,
+    pub query_pipeline: QueryPipeline,
+
+    pipeline: PhysicsPipeline,
+    islands: IslandManager,
+    broad_phase: BroadPhase,
+    narrow_phase: NarrowPhase,
+    ccd_solver: CCDSolver,
+    events: ChannelEventCollector,
+    collisions: Receiver<CollisionEvent>,
+    contact_forces: Receiver<ContactForceEvent>,
+    removal_send: Sender<Removal>,
+    removals: Receiver<Removal>,
+
+    tracked_bodies: IndexMap<RigidBodyHandle, Tracked>,
+    tracked_colliders: IndexMap<ColliderHandle, Tracked>,
+}
+
+impl Default for PhysicsWorld {
+    fn default() -> Self {
+        Self::new(Vec3::NEG_Y * 9.81, 1. / 60.)
+    }
+}
+
+impl PhysicsWorld {
+    pub fn new(gravity: Vec3, timestep: f32) -> Self {
+        let (collision_send, collisions) = unbounded();
+        let (contact_force_send, contact_forces) = unbounded();
+        let (removal_send, removals) = unbounded();
+        let integration_parameters = IntegrationParameters {
+            dt: timestep,
+            ..Default::default()
+        };
+
+        Self {
+            gravity,
+            integration_parameters,
+            bodies: Default::default(),
+            colliders: Default::default(),
+            impulse_joints: Default::default(),
+            multibody_joints: Default::default(),
+            query_pipeline: Default::default(),
+            pipeline: Default::default(),
+            islands: Default::default(),
+            broad_phase: Default::default(),
+            narrow_phase: Default::default(),
+            ccd_solver: Default::default(),
+            events: ChannelEventCollector::new(collision_send, contact_force_send),
+            collisions,
+            contact_forces,
+            removal_send,
+            removals,
+            tracked_bodies: Default::default(),
+            tracked_colliders: Default::default(),
+        }
+    }
+
+    /// Gets the length of a single fixed step in seconds.
+    pub fn timestep(&self) -> f32 {
+        self.integration_parameters.dt
+    }
+
+    pub fn set_timestep(&mut self, timestep: f32) {
+        self.integration_parameters.dt = timestep;
+    }
+
+    /// Adds `body` to the world, placed at the world position and rotation of `transform`.
+    ///
+    /// The transform is moved by the simulation, and moving the transform moves the body.
+    pub fn insert_body(
+        &mut self,
+        transform: Pearl<BobaTransform>,
+        mut body: RigidBody,
+    ) -> Result<RigidBodyHandle, PearlError> {
+        let tick = {
+            let transform = transform.borrow()?;
+            body.set_position(matrix_to_isometry(&transform.world_matrix()), true);
+            transform.changed_tick()
+        };
+
+        let handle = self.bodies.insert(body);
+        self.tracked_bodies
+            .insert(handle, Tracked { transform, tick });
+        Ok(handle)
+    }
+
+    /// Adds a collider that is not attached to a body, placed at `transform`.
+    ///
+    /// Moving the transform moves the collider.
+    pub fn insert_collider(
+        &mut self,
+        transform: Pearl<BobaTransform>,
+        mut collider: Collider,
+    ) -> Result<ColliderHandle, PearlError> {
+        let tick = {
+            let transform = transform.borrow()?;
+            collider.set_position(matrix_to_isometry(&transform.world_matrix()));
+            transform.changed_tick()
+        };
+
+        let handle = self.colliders.insert(collider);
+        self.tracked_colliders
+            .insert(handle, Tracked { transform, tick });
+        Ok(handle)
+    }
+
+    /// Attaches `collider` to the body with `parent`, offset by its position relative to that body.
+    ///
+    /// The collider is not tracked by a transform, and moves along with its body.
+    pub fn insert_collider_with_parent(
+        &mut self,
+        collider: Collider,
+        parent: RigidBodyHandle,
+    ) -> ColliderHandle {
+        self.colliders
+            .insert_with_parent(collider, parent, &mut self.bodies)
+    }
+
+    /// Removes a body and all of its colliders from the world.
+    pub fn remove_body(&mut self, handle: RigidBodyHandle) -> Option<RigidBody> {
+        self.tracked_bodies.shift_remove(&handle);
+        self.bodies.remove(
+            handle,
+            &mut self.islands,
+            &mut self.colliders,
+            &mut self.impulse_joints,
+            &mut self.multibody_joints,
+            true,
+        )
+    }
+
+    pub fn remove_collider(&mut self, handle: ColliderHandle) -> Option<Collider> {
+        self.tracked_colliders.shift_remove(&handle);
+        self.colliders
+            .remove(handle, &mut self.islands, &mut self.bodies, true)
+    }
+
+    /// Wraps `handle` so that it is removed from the world once the wrapper is dropped
+    pub(crate) fn owned<H: Copy + Into<Removal>>(&self, handle: H) -> OwnedHandle<H> {
+        OwnedHandle {
+            handle,
+            removals: self.removal_send.clone(),
+        }
+    }
+
+    /// Casts a ray against all colliders, returning the closest hit collider and the distance to it.
+    pub fn cast_ray(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        max_distance: f32,
+    ) -> Option<(ColliderHandle, f32)> {
+        let ray = Ray::new(to_vector(origin).into(), to_vector(direction.normalize()));
+        self.query_pipeline.cast_ray(
+            &self.bodies,
+            &self.colliders,
+            &ray,
+            max_distance,
+            true,
+            QueryFilter::default(),
+        )
+    }
+
+    /// Gets the linear velocity of the body with `handle`.
+    pub fn linear_velocity(&self, handle: RigidBodyHandle) -> Option<Vec3> {
+        let body = self.bodies.get(handle)?;
+        Some(from_vector(body.linvel()))
+    }
+
+    /// Runs a single fixed step of the simulation.
+    ///
+    /// Bodies and colliders of destroyed pearls are removed, and transforms that were moved
+    /// since the last step are pushed into the simulation first.
+    /// The transforms of all bodies are written back afterwards.
+    /// Returns the collision events that happened during the step.
+    pub fn step(&mut self) -> Vec<CollisionEvent> {
+        self.remove_dropped();
+        self.pull_transforms();
+
+        self.pipeline.step(
+            &to_vector(self.gravity),
+            &self.integration_parameters,
+            &mut self.islands,
+            &mut self.broad_phase,
+            &mut self.narrow_phase,
+            &mut self.bodies,
+            &mut self.colliders,
+            &mut self.impulse_joints,
+            &mut self.multibody_joints,
+            &mut self.ccd_solver,
+            Some(&mut self.query_pipeline),
+            &(),
+            &self.events,
+        );
+
+        self.push_transforms();
+        while self.contact_forces.try_recv().is_ok() {}
+        self.collisions.try_iter().collect()
+    }
+
+    fn remove_dropped(&mut self) {
+        let removals = self.removals.try_iter().collect::<Vec<_>>();
+        for removal in removals {
+            match removal {
+                Removal::Body(handle) => {
+                    self.remove_body(handle);
+                }
+                Removal::Collider(handle) => {
+                    self.remove_collider(handle);
+                }
+            }
+        }
+    }
+
+    fn pull_transforms(&mut self) {
+        let mut destroyed = Vec::new();
+        for (handle, tracked) in self.tracked_bodies.iter_mut() {
+            let transform = match tracked.transform.borrow() {
+                Ok(transform) => transform,
+                Err(PearlError::Destroyed) => {
+                    destroyed.push(*handle);
+                    continue;
+                }
+                Err(e) => {
+                    error!("Could not read rigid body transform. Error: {e}");
+                    continue;
+                }
+            };
+
+            let tick = transform.changed_tick();
+            if tick == tracked.tick {
+                continue;
+            }
+
+            tracked.tick = tick;
+            let Some(body) = self.bodies.get_mut(*handle) else {
+                continue;
+            };
+
+            let position = matrix_to_isometry(&transform.world_matrix());
+            match body.is_kinematic() {
+                true => body.set_next_kinematic_position(position),
+                false => body.set_position(position, true),
+            }
+        }
+
+        for handle in destroyed {
+            self.remove_body(handle);
+        }
+
+        let mut destroyed = Vec::new();
+        for (handle, tracked) in self.tracked_colliders.iter_mut() {
+            let transform = match tracked.transform.borrow() {
+                Ok(transform) => transform,
+                Err(PearlError::Destroyed) => {
+                    destroyed.push(*handle);
+                    continue;
+                }
+                Err(e) => {
+                    error!("Could not read collider transform. Error: {e}");
+                    continue;
+                }
+            };
+
+            let tick = transform.changed_tick();
+            if tick == tracked.tick {
+                continue;
+            }
+
+            tracked.tick = tick;
+            if let Some(collider) = self.colliders.get_mut(*handle) {
+                collider.set_position(matrix_to_isometry(&transform.world_matrix()));
+            }
+        }
+
+        for handle in destroyed {
+            self.remove_collider(handle);
+        }
+    }
+
+    fn push_transforms(&mut self) {
+        for (handle, tracked) in self.tracked_bodies.iter_mut() {
+            let Some(body) = self.bodies.get(*handle) else {
+                continue;
+            };
+
+            if !body.is_dynamic() || body.is_sleeping() {
+                continue;
+            }
+
+            let mut transform = match tracked.transform.borrow_mut() {
+                Ok(transform) => transform,
+                Err(e) => {
+                    error!("Could not write rigid body transform. Error: {e}");
+                    continue;
+                }
+            };
+
+            let (position, rotation) = from_isometry(body.position());
+            transform.set_world_position(position);
+            transform.set_world_rotation(rotation);
+            tracked.tick = transform.changed_tick();
+        }
+    }
+}
@@ -1,17 +1,42 @@
-use std::marker::PhantomData;
+use std::{collections::HashMap, marker::PhantomData};
 
-use boba_core::{stages::BobaUpdate, BobaResources, BobaStage, PearlRegistry, StageCollection};
+use boba_core::{stages::BobaUpdate, BobaResources, PearlRegistry, StageCollection};
 
+use log::{error, info};
 use winit::{
+    dpi::PhysicalSize,
     error::OsError,
     event::{Event, WindowEvent},
     event_loop::EventLoop,
     window::{Window, WindowBuilder},
 };
 
-use crate::{event_types::MilkTeaSize, MilkTeaEvent, MilkTeaPlugin};
-pub trait MilkTeaAdapter: MilkTeaPlugin + 'static {
-    fn build(window: Window) -> Self;
+use crate::{
+    event_types::{MilkTeaKeyboard, MilkTeaSize},
+    input::{GamepadBackend, MilkTeaInput},
+    record::{InputRecorder, InputReplay},
+    ForwardedEvent, MilkTeaPlugin, MilkTeaPlugins, MilkTeaWindowId, MilkTeaWindowSettings,
+};
+
+pub trait MilkTeaAdapter: 'static {
+    /// Sets up the stages, pearls and resources the adapter needs.
+    ///
+    /// This is called when the [`Bobarista`] is created, before any window exists.
+    fn setup(
+        registry: &mut PearlRegistry,
+        startup_stages: &mut StageCollection,
+        main_stages: &mut StageCollection,
+        resources: &mut BobaResources,
+    );
+
+    /// Builds the adapter using the main window.
+    fn build(id: MilkTeaWindowId, window: Window) -> Self;
+
+    /// Adds an extra window to the adapter.
+    fn add_window(&mut self, id: MilkTeaWindowId, window: Window);
+
+    /// Removes a window from the adapter, closing it.
+    fn remove_window(&mut self, id: MilkTeaWindowId);
 }
 
 pub struct Bobarista<RenderAdapter>
@@ -22,7 +47,14 @@ where
     pub startup_stages: StageCollection,
     pub main_stages: StageCollection,
     pub resources: BobaResources,
+    pub plugins: MilkTeaPlugins,
+    pub gamepads: Option<Box<dyn GamepadBackend>>,
+    pub recorder: Option<InputRecorder>,
+    pub replay: Option<InputReplay>,
 
+    windows: Vec<MilkTeaWindowSettings>,
+    frame: u64,
+    started: bool,
     _renderer: PhantomData<RenderAdapter>,
 }
 
@@ -37,11 +69,19 @@ where
             startup_stages: Default::default(),
             main_stages: Default::default(),
             resources: Default::default(),
+            plugins: Default::default(),
+            gamepads: default_gamepad_backend(),
+            recorder: None,
+            replay: None,
+            windows: vec![MilkTeaWindowSettings::default()],
+            frame: 0,
+            started: false,
             _renderer: Default::default(),
         };
 
-        // add default stages
+        // add default stages and resources
         new.main_stages.append(BobaUpdate::default());
+        new.resources.add(MilkTeaInput::default());
 
         // set up render plugin
         Renderer::setup(
@@ -60,59 +100,231 @@ impl<RenderAdapter> Bobarista<RenderAdapter>
 where
     RenderAdapter: MilkTeaAdapter,
 {
+    /// Gets the index of the frame that is currently being processed.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Adds or replaces a plugin to be set up right before the startup stages run.
+    pub fn add_plugin<P>(&mut self, plugin: P)
+    where
+        P: MilkTeaPlugin,
+    {
+        self.plugins.insert(plugin);
+    }
+
+    /// Adds a window that will be created when the app starts running.
+    pub fn add_window(&mut self, settings: MilkTeaWindowSettings) -> MilkTeaWindowId {
+        self.windows.push(settings);
+        MilkTeaWindowId(self.windows.len() - 1)
+    }
+
+    /// Gets the settings for the window with `id`, if it exists.
+    pub fn window_settings_mut(
+        &mut self,
+        id: MilkTeaWindowId,
+    ) -> Option<&mut MilkTeaWindowSettings> {
+        self.windows.get_mut(id.0)
+    }
+
     pub fn run(mut self) -> Result<(), OsError> {
         env_logger::init();
 
-        // Create main event loop and winit window
+        // Create main event loop and winit windows
         let event_loop = EventLoop::new();
-        let window = WindowBuilder::new()
-            .with_title("Milk Tea Window")
-            .build(&event_loop)?;
+        let mut window_ids = HashMap::new();
+        let mut adapter = None;
+        for (index, settings) in self.windows.iter().enumerate() {
+            let id = MilkTeaWindowId(index);
+            let mut builder = WindowBuilder::new().with_title(&settings.title);
+            if let Some((width, height)) = settings.size {
+                builder = builder.with_inner_size(PhysicalSize::new(width, height));
+            }
+
+            let window = builder.build(&event_loop)?;
+            window_ids.insert(window.id(), id);
+            match &mut adapter {
+                None => adapter = Some(RenderAdapter::build(id, window)),
+                Some(adapter) => adapter.add_window(id, window),
+            }
+        }
 
         // add windows to resources
-        self.resources.add(RenderAdapter::build(window));
+        if let Some(adapter) = adapter {
+            self.resources.add(adapter);
+        }
 
         // run the startup stages
-        self.startup_stages
-            .run(&mut self.registry, &mut self.resources);
+        self.startup();
 
         // run the main event loop
         event_loop.run(move |event, _, control_flow| {
             control_flow.set_poll();
 
             match event {
-                Event::WindowEvent { ref event, .. } => match event {
-                    WindowEvent::CloseRequested => control_flow.set_exit(),
-                    WindowEvent::Resized(size) => {
-                        MilkTeaEvent::new(MilkTeaSize::new(size.width, size.height))
-                            .run(&mut self.registry, &mut self.resources)
-                            .unwrap();
-                    }
-                    WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
-                        MilkTeaEvent::new(MilkTeaSize::new(
-                            new_inner_size.width,
-                            new_inner_size.height,
-                        ))
-                        .run(&mut self.registry, &mut self.resources)
-                        .unwrap();
-                    }
-                    WindowEvent::KeyboardInput {
-                        device_id: _,
-                        input,
-                        is_synthetic: _,
-                    } => {
-                        MilkTeaEvent::new(input.clone())
-                            .run(&mut self.registry, &mut self.resources)
-                            .unwrap();
+                Event::WindowEvent {
+                    ref event,
+                    window_id,
+                } => {
+                    let Some(&window) = window_ids.get(&window_id) else {
+                        return;
+                    };
+
+                    match event {
+                        WindowEvent::CloseRequested => match window {
+                            MilkTeaWindowId::MAIN => control_flow.set_exit(),
+                            _ => {
+                                window_ids.remove(&window_id);
+                                match self.resources.get_mut::<RenderAdapter>() {
+                                    Ok(mut adapter) => adapter.remove_window(window),
+                                    Err(e) => error!("Could not close window. Error: {e}"),
+                                }
+                            }
+                        },
+                        WindowEvent::Resized(size) => {
+                            let size = MilkTeaSize::new(window, size.width, size.height);
+                            self.forward_live(ForwardedEvent::Resize(size));
+                        }
+                        WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
+                            let (width, height) = (new_inner_size.width, new_inner_size.height);
+                            let size = MilkTeaSize::new(window, width, height);
+                            self.forward_live(ForwardedEvent::Resize(size));
+                        }
+                        WindowEvent::KeyboardInput {
+                            device_id: _,
+                            input,
+                            is_synthetic: _,
+                        } => {
+                            let input = *input;
+                            self.forward_live(ForwardedEvent::Keyboard(MilkTeaKeyboard {
+                                window,
+                                input,
+                            }));
+                        }
+                        WindowEvent::MouseInput { state, button, .. } => {
+                            self.forward_live(ForwardedEvent::Mouse {
+                                window,
+                                button: *button,
+                                state: *state,
+                            });
+                        }
+                        _ => (),
                     }
-                    _ => (),
-                },
-                Event::MainEventsCleared => {
-                    self.main_stages
-                        .run(&mut self.registry, &mut self.resources);
                 }
+                Event::MainEventsCleared => self.run_frame(true),
                 _ => (),
             }
         })
     }
+
+    /// Runs the app for `frames` frames without creating a window.
+    ///
+    /// The render adapter is never built, so only the stages and pearls that do not
+    /// depend on it will do meaningful work. Input comes from [`Self::replay`] if one is set,
+    /// or from [`Self::gamepads`] otherwise. Calling this again continues from the last frame.
+    pub fn run_headless(&mut self, frames: u64) {
+        self.startup();
+        for _ in 0..frames {
+            self.run_frame(false);
+        }
+    }
+
+    fn startup(&mut self) {
+        if self.started {
+            return;
+        }
+
+        self.started = true;
+        self.plugins.setup(
+            &mut self.registry,
+            &mut self.startup_stages,
+            &mut self.main_stages,
+            &mut self.resources,
+        );
+        self.startup_stages
+            .run(&mut self.registry, &mut self.resources);
+    }
+
+    fn run_frame(&mut self, windowed: bool) {
+        // gather the input for this frame from either the replay or the gamepads
+        let events = match &mut self.replay {
+            Some(replay) => {
+                let mut events = replay.take_frame(self.frame);
+                if windowed {
+                    // the live window owns its own size
+                    events.retain(|e| !matches!(e, ForwardedEvent::Resize(_)));
+                }
+
+                if replay.is_finished() {
+                    info!("Input replay finished on frame {}.", self.frame);
+                    self.replay = None;
+                }
+
+                events
+            }
+            None => {
+                let mut events = Vec::new();
+                if let Some(gamepads) = &mut self.gamepads {
+                    while let Some(event) = gamepads.poll() {
+                        events.push(ForwardedEvent::Gamepad(event));
+                    }
+                }
+
+                events
+            }
+        };
+
+        for event in events {
+            self.forward(event);
+        }
+
+        self.main_stages
+            .run(&mut self.registry, &mut self.resources);
+
+        if let Ok(mut input) = self.resources.get_mut::<MilkTeaInput>() {
+            input.end_frame();
+        }
+
+        if let Some(recorder) = &mut self.recorder {
+            if let Err(e) = recorder.flush() {
+                error!("Could not flush input recording. Error: {e}");
+            }
+        }
+
+        self.frame += 1;
+    }
+
+    /// Forwards an event from the window, unless it is being overridden by a replay.
+    fn forward_live(&mut self, event: ForwardedEvent) {
+        let is_resize = matches!(event, ForwardedEvent::Resize(_));
+        if self.replay.is_none() || is_resize {
+            self.forward(event);
+        }
+    }
+
+    fn forward(&mut self, event: ForwardedEvent) {
+        if let Some(recorder) = &mut self.recorder {
+            if let Err(e) = recorder.record(self.frame, &event) {
+                error!("Could not record event. Error: {e}");
+            }
+        }
+
+        event.dispatch(&mut self.registry, &mut self.resources);
+    }
+}
+
+#[cfg(feature = "gamepad")]
+fn default_gamepad_backend() -> Option<Box<dyn GamepadBackend>> {
+    match crate::input::GilrsBackend::new() {
+        Ok(backend) => Some(Box::new(backend)),
+        Err(e) => {
+            log::warn!("Could not initialize gamepad support. Error: {e}");
+            None
+        }
+    }
+}
+
+#[cfg(not(feature = "gamepad"))]
+fn default_gamepad_backend() -> Option<Box<dyn GamepadBackend>> {
+    None
 }
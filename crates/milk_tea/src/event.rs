@@ -1,4 +1,13 @@
 use boba_core::{BobaResources, BobaResult, BobaStage, PearlRegistry};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use winit::event::{ElementState, MouseButton};
+
+use crate::{
+    event_types::{MilkTeaGamepadEvent, MilkTeaKeyboard, MilkTeaSize},
+    input::{InputButton, MilkTeaInput},
+    MilkTeaWindowId,
+};
 
 pub struct MilkTeaEvent<T> {
     data: T,
@@ -18,3 +27,60 @@ impl<T: 'static> BobaStage for MilkTeaEvent<T> {
         Ok(())
     }
 }
+
+/// An event forwarded into the app from the window or a gamepad.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum ForwardedEvent {
+    Resize(MilkTeaSize),
+    Keyboard(MilkTeaKeyboard),
+    Mouse {
+        window: MilkTeaWindowId,
+        button: MouseButton,
+        state: ElementState,
+    },
+    Gamepad(MilkTeaGamepadEvent),
+}
+
+impl ForwardedEvent {
+    /// Applies the event to the [`MilkTeaInput`] resource,
+    /// and runs the matching [`MilkTeaEvent`] stage.
+    pub fn dispatch(&self, registry: &mut PearlRegistry, resources: &mut BobaResources) {
+        match *self {
+            Self::Resize(size) => run_event(size, registry, resources),
+            Self::Keyboard(keyboard) => {
+                let input = keyboard.input;
+                if let Some(key) = input.virtual_keycode {
+                    update_input(resources, |i| {
+                        i.update_button(InputButton::Key(key), input.state)
+                    });
+                }
+                run_event(keyboard, registry, resources);
+            }
+            Self::Mouse { button, state, .. } => {
+                update_input(resources, |i| {
+                    i.update_button(InputButton::Mouse(button), state)
+                });
+            }
+            Self::Gamepad(event) => {
+                update_input(resources, |i| i.update_gamepad(&event));
+                run_event(event, registry, resources);
+            }
+        }
+    }
+}
+
+fn run_event<T: 'static>(data: T, registry: &mut PearlRegistry, resources: &mut BobaResources) {
+    if let Err(e) = MilkTeaEvent::new(data).run(registry, resources) {
+        warn!(
+            "Could not run MilkTeaEvent<{}>. Error: {e}",
+            std::any::type_name::<T>()
+        );
+    }
+}
+
+fn update_input(resources: &BobaResources, update: impl FnOnce(&mut MilkTeaInput)) {
+    match resources.get_mut::<MilkTeaInput>() {
+        Ok(mut input) => update(&mut input),
+        Err(e) => warn!("Could not update input state. Error: {e}"),
+    }
+}
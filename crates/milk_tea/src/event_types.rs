@@ -1,11 +1,48 @@
-#[derive(Debug, Clone, Copy)]
+use serde::{Deserialize, Serialize};
+use winit::event::{ElementState, KeyboardInput};
+
+use crate::{
+    input::{GamepadAxis, GamepadButton, GamepadId},
+    MilkTeaWindowId,
+};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct MilkTeaSize {
+    pub window: MilkTeaWindowId,
     pub width: u32,
     pub height: u32,
 }
 
 impl MilkTeaSize {
-    pub fn new(width: u32, height: u32) -> Self {
-        Self { width, height }
+    pub fn new(window: MilkTeaWindowId, width: u32, height: u32) -> Self {
+        Self {
+            window,
+            width,
+            height,
+        }
     }
 }
+
+/// A keyboard event received by one of the app windows.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct MilkTeaKeyboard {
+    pub window: MilkTeaWindowId,
+    pub input: KeyboardInput,
+}
+
+/// A gamepad connection, button or axis event.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum MilkTeaGamepadEvent {
+    Connected(GamepadId),
+    Disconnected(GamepadId),
+    Button {
+        id: GamepadId,
+        button: GamepadButton,
+        state: ElementState,
+    },
+    Axis {
+        id: GamepadId,
+        axis: GamepadAxis,
+        value: f32,
+    },
+}
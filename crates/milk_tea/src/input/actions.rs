@@ -0,0 +1,201 @@
+use std::{fs, path::Path};
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{GamepadAxis, InputButton};
+
+/// An error returned when loading or saving an [`ActionMap`].
+#[derive(Debug, Error)]
+pub enum ActionMapError {
+    #[error("Could not access action map file. Error: {0}")]
+    Io(std::io::Error),
+    #[error("Could not parse action map. Error: {0}")]
+    Parse(ron::error::SpannedError),
+    #[error("Could not serialize action map. Error: {0}")]
+    Serialize(ron::Error),
+}
+
+/// A binding that drives a named axis.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AxisBinding {
+    /// Two buttons that push the axis towards `1.0` and `-1.0` respectively.
+    Buttons {
+        positive: InputButton,
+        negative: InputButton,
+    },
+    /// An analog axis on any connected gamepad.
+    Gamepad(GamepadAxis),
+}
+
+impl AxisBinding {
+    pub fn buttons(positive: impl Into<InputButton>, negative: impl Into<InputButton>) -> Self {
+        Self::Buttons {
+            positive: positive.into(),
+            negative: negative.into(),
+        }
+    }
+}
+
+/// A collection of named actions and axes, each bound to one or more inputs.
+///
+/// The map can be loaded from and saved to a [RON](https://github.com/ron-rs/ron) file,
+/// and rebound at runtime through the [`MilkTeaInput`](super::MilkTeaInput) resource.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ActionMap {
+    #[serde(default)]
+    actions: IndexMap<String, Vec<InputButton>>,
+    #[serde(default)]
+    axes: IndexMap<String, Vec<AxisBinding>>,
+}
+
+impl ActionMap {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Loads an action map from the RON file at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ActionMapError> {
+        let source = fs::read_to_string(path).map_err(ActionMapError::Io)?;
+        Self::from_ron(&source)
+    }
+
+    /// Saves the action map as a RON file at `path`.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ActionMapError> {
+        fs::write(path, self.to_ron()?).map_err(ActionMapError::Io)
+    }
+
+    /// Parses an action map from a RON string.
+    pub fn from_ron(source: &str) -> Result<Self, ActionMapError> {
+        ron::from_str(source).map_err(ActionMapError::Parse)
+    }
+
+    /// Serializes the action map into a RON string.
+    pub fn to_ron(&self) -> Result<String, ActionMapError> {
+        ron::ser::to_string_pretty(self, Default::default()).map_err(ActionMapError::Serialize)
+    }
+
+    /// Binds `button` to `action`.
+    ///
+    /// If the button is already bound to the action, nothing happens.
+    pub fn bind_action(&mut self, action: &str, button: impl Into<InputButton>) {
+        let button = button.into();
+        let bindings = self.actions.entry(action.into()).or_default();
+        if !bindings.contains(&button) {
+            bindings.push(button);
+        }
+    }
+
+    /// Removes `button` from the bindings of `action`.
+    pub fn unbind_action(&mut self, action: &str, button: impl Into<InputButton>) {
+        let button = button.into();
+        if let Some(bindings) = self.actions.get_mut(action) {
+            bindings.retain(|b| *b != button);
+        }
+    }
+
+    /// Removes `action` and all of its bindings from the map.
+    pub fn remove_action(&mut self, action: &str) {
+        self.actions.shift_remove(action);
+    }
+
+    /// Gets all the buttons bound to `action`.
+    pub fn action_bindings(&self, action: &str) -> &[InputButton] {
+        match self.actions.get(action) {
+            Some(bindings) => bindings,
+            None => &[],
+        }
+    }
+
+    /// Iterates over the names of all actions in the map.
+    pub fn actions(&self) -> impl Iterator<Item = &str> {
+        self.actions.keys().map(|k| k.as_str())
+    }
+
+    /// Binds `binding` to `axis`.
+    ///
+    /// If the binding is already bound to the axis, nothing happens.
+    pub fn bind_axis(&mut self, axis: &str, binding: AxisBinding) {
+        let bindings = self.axes.entry(axis.into()).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    /// Removes `binding` from the bindings of `axis`.
+    pub fn unbind_axis(&mut self, axis: &str, binding: &AxisBinding) {
+        if let Some(bindings) = self.axes.get_mut(axis) {
+            bindings.retain(|b| b != binding);
+        }
+    }
+
+    /// Removes `axis` and all of its bindings from the map.
+    pub fn remove_axis(&mut self, axis: &str) {
+        self.axes.shift_remove(axis);
+    }
+
+    /// Gets all the bindings for `axis`.
+    pub fn axis_bindings(&self, axis: &str) -> &[AxisBinding] {
+        match self.axes.get(axis) {
+            Some(bindings) => bindings,
+            None => &[],
+        }
+    }
+
+    /// Iterates over the names of all axes in the map.
+    pub fn axes(&self) -> impl Iterator<Item = &str> {
+        self.axes.keys().map(|k| k.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winit::event::{MouseButton, VirtualKeyCode};
+
+    use super::{ActionMap, AxisBinding, InputButton};
+
+    #[test]
+    fn bind_and_unbind() {
+        let mut map = ActionMap::new();
+        map.bind_action("jump", VirtualKeyCode::Space);
+        map.bind_action("jump", MouseButton::Left);
+        map.bind_action("jump", VirtualKeyCode::Space);
+        assert!(map.action_bindings("jump").len() == 2);
+
+        map.unbind_action("jump", VirtualKeyCode::Space);
+        assert!(map.action_bindings("jump") == [InputButton::Mouse(MouseButton::Left)]);
+
+        map.remove_action("jump");
+        assert!(map.action_bindings("jump").is_empty());
+    }
+
+    #[test]
+    fn ron_round_trip() {
+        let mut map = ActionMap::new();
+        map.bind_action("jump", VirtualKeyCode::Space);
+        map.bind_axis(
+            "move_x",
+            AxisBinding::buttons(VirtualKeyCode::D, VirtualKeyCode::A),
+        );
+
+        let loaded = ActionMap::from_ron(&map.to_ron().unwrap()).unwrap();
+        assert!(loaded.action_bindings("jump") == map.action_bindings("jump"));
+        assert!(loaded.axis_bindings("move_x") == map.axis_bindings("move_x"));
+    }
+
+    #[test]
+    fn parse_config() {
+        let map = ActionMap::from_ron(
+            r#"(
+                actions: { "fire": [Mouse(Left), Key(LControl)] },
+                axes: { "move_y": [Buttons(positive: Key(W), negative: Key(S))] },
+            )"#,
+        )
+        .unwrap();
+
+        assert!(map.action_bindings("fire").len() == 2);
+        assert!(map.axis_bindings("move_y").len() == 1);
+        assert!(ActionMap::from_ron("(actions: { \"fire\": [Nope] })").is_err());
+    }
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use winit::event::{MouseButton, VirtualKeyCode};
+
+use super::GamepadButton;
+
+/// A single physical input that can be bound to an action or axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum InputButton {
+    Key(VirtualKeyCode),
+    Mouse(MouseButton),
+    /// A button on any connected gamepad.
+    Gamepad(GamepadButton),
+}
+
+impl From<VirtualKeyCode> for InputButton {
+    fn from(value: VirtualKeyCode) -> Self {
+        Self::Key(value)
+    }
+}
+
+impl From<MouseButton> for InputButton {
+    fn from(value: MouseButton) -> Self {
+        Self::Mouse(value)
+    }
+}
+
+impl From<GamepadButton> for InputButton {
+    fn from(value: GamepadButton) -> Self {
+        Self::Gamepad(value)
+    }
+}
@@ -0,0 +1,167 @@
+use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::event_types::MilkTeaGamepadEvent;
+
+/// The id of a connected gamepad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct GamepadId(pub usize);
+
+/// A digital button on a gamepad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GamepadButton {
+    South,
+    East,
+    North,
+    West,
+    LeftTrigger,
+    LeftTrigger2,
+    RightTrigger,
+    RightTrigger2,
+    Select,
+    Start,
+    Mode,
+    LeftThumb,
+    RightThumb,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+/// An analog axis on a gamepad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+    LeftZ,
+    RightStickX,
+    RightStickY,
+    RightZ,
+    DPadX,
+    DPadY,
+}
+
+/// A source of gamepad events that is polled once per frame.
+pub trait GamepadBackend {
+    /// Gets the next pending gamepad event, if there is one.
+    fn poll(&mut self) -> Option<MilkTeaGamepadEvent>;
+}
+
+/// A gamepad backend that only produces the events pushed into it.
+///
+/// Clones share the same event queue, so one clone can be handed to a
+/// [`Bobarista`](crate::Bobarista) while another is used to simulate input.
+#[derive(Default, Clone)]
+pub struct MockGamepadBackend {
+    events: Rc<RefCell<VecDeque<MilkTeaGamepadEvent>>>,
+}
+
+impl MockGamepadBackend {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Queues `event` to be returned by the next poll.
+    pub fn push(&self, event: MilkTeaGamepadEvent) {
+        self.events.borrow_mut().push_back(event);
+    }
+}
+
+impl GamepadBackend for MockGamepadBackend {
+    fn poll(&mut self) -> Option<MilkTeaGamepadEvent> {
+        self.events.borrow_mut().pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use boba_core::{BobaResources, PearlRegistry};
+    use winit::event::ElementState;
+
+    use crate::{
+        event_types::MilkTeaGamepadEvent,
+        input::{ActionMap, AxisBinding, MilkTeaInput},
+        ForwardedEvent,
+    };
+
+    use super::{GamepadAxis, GamepadBackend, GamepadButton, GamepadId, MockGamepadBackend};
+
+    fn pump(backend: &MockGamepadBackend, resources: &mut BobaResources) {
+        let mut backend = backend.clone();
+        while let Some(event) = backend.poll() {
+            ForwardedEvent::Gamepad(event).dispatch(&mut PearlRegistry::default(), resources);
+        }
+    }
+
+    #[test]
+    fn buttons() {
+        let mut actions = ActionMap::new();
+        actions.bind_action("jump", GamepadButton::South);
+
+        let mut resources = BobaResources::default();
+        resources.add(MilkTeaInput::new(actions));
+
+        let backend = MockGamepadBackend::new();
+        let (pad1, pad2) = (GamepadId(0), GamepadId(1));
+        backend.push(MilkTeaGamepadEvent::Connected(pad1));
+        backend.push(MilkTeaGamepadEvent::Connected(pad2));
+        for id in [pad1, pad2] {
+            backend.push(MilkTeaGamepadEvent::Button {
+                id,
+                button: GamepadButton::South,
+                state: ElementState::Pressed,
+            });
+        }
+        pump(&backend, &mut resources);
+        assert!(resources
+            .get::<MilkTeaInput>()
+            .unwrap()
+            .action_pressed("jump"));
+        assert!(resources.get::<MilkTeaInput>().unwrap().gamepads().count() == 2);
+
+        resources.get_mut::<MilkTeaInput>().unwrap().end_frame();
+        backend.push(MilkTeaGamepadEvent::Disconnected(pad1));
+        pump(&backend, &mut resources);
+        assert!(resources.get::<MilkTeaInput>().unwrap().action_held("jump"));
+
+        backend.push(MilkTeaGamepadEvent::Button {
+            id: pad2,
+            button: GamepadButton::South,
+            state: ElementState::Released,
+        });
+        pump(&backend, &mut resources);
+        assert!(resources
+            .get::<MilkTeaInput>()
+            .unwrap()
+            .action_released("jump"));
+    }
+
+    #[test]
+    fn axes() {
+        let mut actions = ActionMap::new();
+        actions.bind_axis("move_x", AxisBinding::Gamepad(GamepadAxis::LeftStickX));
+
+        let mut resources = BobaResources::default();
+        resources.add(MilkTeaInput::new(actions));
+
+        let backend = MockGamepadBackend::new();
+        backend.push(MilkTeaGamepadEvent::Connected(GamepadId(0)));
+        backend.push(MilkTeaGamepadEvent::Axis {
+            id: GamepadId(0),
+            axis: GamepadAxis::LeftStickX,
+            value: -0.5,
+        });
+        pump(&backend, &mut resources);
+        assert!(resources.get::<MilkTeaInput>().unwrap().axis("move_x") == -0.5);
+
+        backend.push(MilkTeaGamepadEvent::Axis {
+            id: GamepadId(0),
+            axis: GamepadAxis::LeftStickX,
+            value: 0.05,
+        });
+        pump(&backend, &mut resources);
+        assert!(resources.get::<MilkTeaInput>().unwrap().axis("move_x") == 0.);
+    }
+}
@@ -0,0 +1,97 @@
+use gilrs::{Axis, Button, EventType, Gilrs};
+use winit::event::ElementState;
+
+use crate::event_types::MilkTeaGamepadEvent;
+
+use super::{GamepadAxis, GamepadBackend, GamepadButton, GamepadId};
+
+/// A gamepad backend that polls the system gamepads using [`gilrs`].
+pub struct GilrsBackend {
+    gilrs: Gilrs,
+}
+
+impl GilrsBackend {
+    pub fn new() -> Result<Self, gilrs::Error> {
+        Ok(Self {
+            gilrs: Gilrs::new()?,
+        })
+    }
+}
+
+impl GamepadBackend for GilrsBackend {
+    fn poll(&mut self) -> Option<MilkTeaGamepadEvent> {
+        while let Some(event) = self.gilrs.next_event() {
+            let id = GamepadId(event.id.into());
+            let event = match event.event {
+                EventType::Connected => Some(MilkTeaGamepadEvent::Connected(id)),
+                EventType::Disconnected => Some(MilkTeaGamepadEvent::Disconnected(id)),
+                EventType::ButtonPressed(button, _) => {
+                    convert_button(button).map(|button| MilkTeaGamepadEvent::Button {
+                        id,
+                        button,
+                        state: ElementState::Pressed,
+                    })
+                }
+                EventType::ButtonReleased(button, _) => {
+                    convert_button(button).map(|button| MilkTeaGamepadEvent::Button {
+                        id,
+                        button,
+                        state: ElementState::Released,
+                    })
+                }
+                EventType::AxisChanged(axis, value, _) => {
+                    convert_axis(axis).map(|axis| MilkTeaGamepadEvent::Axis { id, axis, value })
+                }
+                _ => None,
+            };
+
+            // skip events that have no milk tea equivalent
+            if event.is_some() {
+                return event;
+            }
+        }
+
+        None
+    }
+}
+
+fn convert_button(button: Button) -> Option<GamepadButton> {
+    let button = match button {
+        Button::South => GamepadButton::South,
+        Button::East => GamepadButton::East,
+        Button::North => GamepadButton::North,
+        Button::West => GamepadButton::West,
+        Button::LeftTrigger => GamepadButton::LeftTrigger,
+        Button::LeftTrigger2 => GamepadButton::LeftTrigger2,
+        Button::RightTrigger => GamepadButton::RightTrigger,
+        Button::RightTrigger2 => GamepadButton::RightTrigger2,
+        Button::Select => GamepadButton::Select,
+        Button::Start => GamepadButton::Start,
+        Button::Mode => GamepadButton::Mode,
+        Button::LeftThumb => GamepadButton::LeftThumb,
+        Button::RightThumb => GamepadButton::RightThumb,
+        Button::DPadUp => GamepadButton::DPadUp,
+        Button::DPadDown => GamepadButton::DPadDown,
+        Button::DPadLeft => GamepadButton::DPadLeft,
+        Button::DPadRight => GamepadButton::DPadRight,
+        Button::C | Button::Z | Button::Unknown => return None,
+    };
+
+    Some(button)
+}
+
+fn convert_axis(axis: Axis) -> Option<GamepadAxis> {
+    let axis = match axis {
+        Axis::LeftStickX => GamepadAxis::LeftStickX,
+        Axis::LeftStickY => GamepadAxis::LeftStickY,
+        Axis::LeftZ => GamepadAxis::LeftZ,
+        Axis::RightStickX => GamepadAxis::RightStickX,
+        Axis::RightStickY => GamepadAxis::RightStickY,
+        Axis::RightZ => GamepadAxis::RightZ,
+        Axis::DPadX => GamepadAxis::DPadX,
+        Axis::DPadY => GamepadAxis::DPadY,
+        Axis::Unknown => return None,
+    };
+
+    Some(axis)
+}
@@ -0,0 +1,15 @@
+mod actions;
+mod button;
+mod gamepad;
+mod state;
+
+#[cfg(feature = "gamepad")]
+mod gilrs;
+
+pub use actions::*;
+pub use button::*;
+pub use gamepad::*;
+pub use state::*;
+
+#[cfg(feature = "gamepad")]
+pub use self::gilrs::*;
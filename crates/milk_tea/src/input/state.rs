@@ -0,0 +1,241 @@
+use std::collections::{HashMap, HashSet};
+
+use winit::event::ElementState;
+
+use crate::event_types::MilkTeaGamepadEvent;
+
+use super::{ActionMap, AxisBinding, GamepadAxis, GamepadButton, GamepadId, InputButton};
+
+/// The polled input state for the current frame.
+///
+/// It is kept up to date by [`Bobarista`](crate::Bobarista) and added to its resources by default,
+/// so pearls can query buttons, actions and axes instead of listening for raw events.
+pub struct MilkTeaInput {
+    pub actions: ActionMap,
+    /// Gamepad axis values with a magnitude below this are treated as `0.0`.
+    pub deadzone: f32,
+
+    held: HashSet<InputButton>,
+    pressed: HashSet<InputButton>,
+    released: HashSet<InputButton>,
+
+    gamepads: HashMap<GamepadId, GamepadState>,
+}
+
+#[derive(Default)]
+struct GamepadState {
+    buttons: HashSet<GamepadButton>,
+    axes: HashMap<GamepadAxis, f32>,
+}
+
+impl Default for MilkTeaInput {
+    fn default() -> Self {
+        Self::new(ActionMap::default())
+    }
+}
+
+impl MilkTeaInput {
+    pub fn new(actions: ActionMap) -> Self {
+        Self {
+            actions,
+            deadzone: 0.1,
+            held: Default::default(),
+            pressed: Default::default(),
+            released: Default::default(),
+            gamepads: Default::default(),
+        }
+    }
+
+    /// Returns true if `button` is currently held down.
+    pub fn is_held(&self, button: impl Into<InputButton>) -> bool {
+        self.held.contains(&button.into())
+    }
+
+    /// Returns true if `button` was pressed this frame.
+    pub fn is_pressed(&self, button: impl Into<InputButton>) -> bool {
+        self.pressed.contains(&button.into())
+    }
+
+    /// Returns true if `button` was released this frame.
+    pub fn is_released(&self, button: impl Into<InputButton>) -> bool {
+        self.released.contains(&button.into())
+    }
+
+    /// Iterates over the ids of all connected gamepads.
+    pub fn gamepads(&self) -> impl Iterator<Item = GamepadId> + '_ {
+        self.gamepads.keys().copied()
+    }
+
+    /// Gets the value of `axis` on the gamepad with `id`, with the deadzone applied.
+    pub fn gamepad_axis(&self, id: GamepadId, axis: GamepadAxis) -> f32 {
+        let value = self
+            .gamepads
+            .get(&id)
+            .and_then(|pad| pad.axes.get(&axis))
+            .copied()
+            .unwrap_or(0.);
+
+        self.apply_deadzone(value)
+    }
+
+    /// Returns true if any button bound to `action` is currently held down.
+    pub fn action_held(&self, action: &str) -> bool {
+        let bindings = self.actions.action_bindings(action);
+        bindings.iter().any(|b| self.held.contains(b))
+    }
+
+    /// Returns true if any button bound to `action` was pressed this frame.
+    pub fn action_pressed(&self, action: &str) -> bool {
+        let bindings = self.actions.action_bindings(action);
+        bindings.iter().any(|b| self.pressed.contains(b))
+    }
+
+    /// Returns true if any button bound to `action` was released this frame.
+    pub fn action_released(&self, action: &str) -> bool {
+        let bindings = self.actions.action_bindings(action);
+        bindings.iter().any(|b| self.released.contains(b))
+    }
+
+    /// Gets the value of `axis`, clamped between `-1.0` and `1.0`.
+    pub fn axis(&self, axis: &str) -> f32 {
+        let value: f32 = self
+            .actions
+            .axis_bindings(axis)
+            .iter()
+            .map(|binding| self.binding_value(binding))
+            .sum();
+
+        value.clamp(-1., 1.)
+    }
+
+    fn binding_value(&self, binding: &AxisBinding) -> f32 {
+        match binding {
+            AxisBinding::Buttons { positive, negative } => {
+                let mut value = 0.;
+                if self.held.contains(positive) {
+                    value += 1.;
+                }
+                if self.held.contains(negative) {
+                    value -= 1.;
+                }
+                value
+            }
+            AxisBinding::Gamepad(axis) => self
+                .gamepads()
+                .map(|id| self.gamepad_axis(id, *axis))
+                .fold(0., |a: f32, b: f32| if b.abs() > a.abs() { b } else { a }),
+        }
+    }
+
+    fn apply_deadzone(&self, value: f32) -> f32 {
+        match value.abs() < self.deadzone {
+            true => 0.,
+            false => value,
+        }
+    }
+
+    /// Updates the state of `button`.
+    pub(crate) fn update_button(&mut self, button: InputButton, state: ElementState) {
+        match state {
+            ElementState::Pressed => {
+                if self.held.insert(button) {
+                    self.pressed.insert(button);
+                }
+            }
+            ElementState::Released => {
+                if self.held.remove(&button) {
+                    self.released.insert(button);
+                }
+            }
+        }
+    }
+
+    /// Updates the state of the gamepad associated with `event`.
+    pub(crate) fn update_gamepad(&mut self, event: &MilkTeaGamepadEvent) {
+        match *event {
+            MilkTeaGamepadEvent::Connected(id) => {
+                self.gamepads.entry(id).or_default();
+            }
+            MilkTeaGamepadEvent::Disconnected(id) => {
+                let Some(pad) = self.gamepads.remove(&id) else {
+                    return;
+                };
+
+                for button in pad.buttons {
+                    self.release_gamepad_button(button);
+                }
+            }
+            MilkTeaGamepadEvent::Button { id, button, state } => {
+                let pad = self.gamepads.entry(id).or_default();
+                match state {
+                    ElementState::Pressed => {
+                        pad.buttons.insert(button);
+                        self.update_button(InputButton::Gamepad(button), state);
+                    }
+                    ElementState::Released => {
+                        if pad.buttons.remove(&button) {
+                            self.release_gamepad_button(button);
+                        }
+                    }
+                }
+            }
+            MilkTeaGamepadEvent::Axis { id, axis, value } => {
+                let pad = self.gamepads.entry(id).or_default();
+                pad.axes.insert(axis, value);
+            }
+        }
+    }
+
+    /// Releases `button` unless it is still held on another gamepad.
+    fn release_gamepad_button(&mut self, button: GamepadButton) {
+        if !self.gamepads.values().any(|p| p.buttons.contains(&button)) {
+            self.update_button(InputButton::Gamepad(button), ElementState::Released);
+        }
+    }
+
+    /// Clears all the per frame state. Called after the main stages have run.
+    pub(crate) fn end_frame(&mut self) {
+        self.pressed.clear();
+        self.released.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winit::event::{ElementState, VirtualKeyCode};
+
+    use super::MilkTeaInput;
+
+    #[test]
+    fn press_hold_release() {
+        let mut input = MilkTeaInput::default();
+        let key = VirtualKeyCode::Space;
+
+        input.update_button(key.into(), ElementState::Pressed);
+        assert!(input.is_pressed(key) && input.is_held(key) && !input.is_released(key));
+
+        // the button stays held on the next frame, but is no longer pressed
+        input.end_frame();
+        assert!(!input.is_pressed(key) && input.is_held(key) && !input.is_released(key));
+
+        input.update_button(key.into(), ElementState::Released);
+        assert!(!input.is_pressed(key) && !input.is_held(key) && input.is_released(key));
+
+        input.end_frame();
+        assert!(!input.is_pressed(key) && !input.is_held(key) && !input.is_released(key));
+    }
+
+    #[test]
+    fn press_release_same_frame() {
+        let mut input = MilkTeaInput::default();
+        let key = VirtualKeyCode::Space;
+
+        // a quick tap is still seen as both a press and a release for that frame
+        input.update_button(key.into(), ElementState::Pressed);
+        input.update_button(key.into(), ElementState::Released);
+        assert!(input.is_pressed(key) && !input.is_held(key) && input.is_released(key));
+
+        input.end_frame();
+        assert!(!input.is_pressed(key) && !input.is_held(key) && !input.is_released(key));
+    }
+}
@@ -1,12 +1,16 @@
 mod app;
 mod event;
 mod plugin;
+mod window;
 
 pub use app::*;
 pub use event::*;
 pub use plugin::*;
+pub use window::*;
 
 pub mod event_types;
+pub mod input;
+pub mod record;
 
 // re-exports
 pub use winit;
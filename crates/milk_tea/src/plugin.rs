@@ -1,10 +1,293 @@
+use std::any::TypeId;
+
 use boba_core::{BobaResources, PearlRegistry, StageCollection};
+use indexmap::{IndexMap, IndexSet};
+use log::error;
+
+/// A unit of setup logic that can be added to a [`Bobarista`](crate::Bobarista).
+///
+/// Plugins are set up once, right before the startup stages run, and are consumed in the process.
+pub trait MilkTeaPlugin: 'static {
+    /// Declares the plugins that must be set up before this one.
+    fn dependencies(&self, _dependencies: &mut PluginDependencies) {}
 
-pub trait MilkTeaPlugin {
     fn setup(
+        self,
         registry: &mut PearlRegistry,
         startup_stages: &mut StageCollection,
         main_stages: &mut StageCollection,
         resources: &mut BobaResources,
     );
 }
+
+/// The dependencies declared by a plugin in [`MilkTeaPlugin::dependencies`].
+#[derive(Default)]
+pub struct PluginDependencies {
+    required: Vec<(TypeId, Box<dyn DynamicPlugin>)>,
+}
+
+impl PluginDependencies {
+    /// Requires a plugin of type `P`.
+    ///
+    /// If no plugin of that type has been added to the app, a default one will be used.
+    pub fn require<P>(&mut self)
+    where
+        P: MilkTeaPlugin + Default,
+    {
+        self.require_with(P::default());
+    }
+
+    /// Requires a plugin of type `P`.
+    ///
+    /// If no plugin of that type has been added to the app, `plugin` will be used.
+    pub fn require_with<P>(&mut self, plugin: P)
+    where
+        P: MilkTeaPlugin,
+    {
+        self.required.push((TypeId::of::<P>(), Box::new(plugin)));
+    }
+}
+
+/// A collection of plugins waiting to be set up.
+///
+/// Only one plugin of each type is kept, and plugins are set up after their dependencies.
+#[derive(Default)]
+pub struct MilkTeaPlugins {
+    plugins: IndexMap<TypeId, Box<dyn DynamicPlugin>>,
+}
+
+impl MilkTeaPlugins {
+    /// Adds or replaces a plugin in the collection.
+    ///
+    /// If a plugin of the same type exists, it will be replaced. If it does not it will be appended.
+    pub fn insert<P>(&mut self, plugin: P)
+    where
+        P: MilkTeaPlugin,
+    {
+        self.plugins.insert(TypeId::of::<P>(), Box::new(plugin));
+    }
+
+    /// Returns true if a plugin of type `P` is in the collection.
+    pub fn contains<P>(&self) -> bool
+    where
+        P: MilkTeaPlugin,
+    {
+        self.plugins.contains_key(&TypeId::of::<P>())
+    }
+
+    pub fn len(&self) -> usize {
+        self.plugins.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+
+    /// Resolves the dependencies of every plugin, then sets them all up in dependency order.
+    ///
+    /// The collection is empty afterwards.
+    pub(crate) fn setup(
+        &mut self,
+        registry: &mut PearlRegistry,
+        startup_stages: &mut StageCollection,
+        main_stages: &mut StageCollection,
+        resources: &mut BobaResources,
+    ) {
+        // gather dependencies, including the ones declared by dependencies
+        let mut edges = IndexMap::<TypeId, Vec<TypeId>>::new();
+        let mut index = 0;
+        while let Some((id, plugin)) = self.plugins.get_index(index) {
+            let mut dependencies = PluginDependencies::default();
+            plugin.dependencies(&mut dependencies);
+
+            let id = *id;
+            let required = edges.entry(id).or_default();
+            for (dependency_id, dependency) in dependencies.required {
+                required.push(dependency_id);
+                self.plugins.entry(dependency_id).or_insert(dependency);
+            }
+
+            index += 1;
+        }
+
+        // order the plugins so that dependencies come first
+        let mut order = IndexSet::new();
+        let mut visiting = IndexSet::new();
+        for id in self.plugins.keys() {
+            visit(*id, &edges, &mut visiting, &mut order);
+        }
+
+        for id in order {
+            let plugin = self.plugins.remove(&id).unwrap();
+            plugin.dynamic_setup(registry, startup_stages, main_stages, resources);
+        }
+    }
+}
+
+fn visit(
+    id: TypeId,
+    edges: &IndexMap<TypeId, Vec<TypeId>>,
+    visiting: &mut IndexSet<TypeId>,
+    order: &mut IndexSet<TypeId>,
+) {
+    if order.contains(&id) {
+        return;
+    }
+
+    if !visiting.insert(id) {
+        error!("A plugin dependency was recursive. The cycle will be set up in the order it was added.");
+        return;
+    }
+
+    for dependency in edges.get(&id).into_iter().flatten() {
+        visit(*dependency, edges, visiting, order);
+    }
+
+    visiting.remove(&id);
+    order.insert(id);
+}
+
+trait DynamicPlugin {
+    fn dependencies(&self, dependencies: &mut PluginDependencies);
+    fn dynamic_setup(
+        self: Box<Self>,
+        registry: &mut PearlRegistry,
+        startup_stages: &mut StageCollection,
+        main_stages: &mut StageCollection,
+        resources: &mut BobaResources,
+    );
+}
+
+impl<P> DynamicPlugin for P
+where
+    P: MilkTeaPlugin,
+{
+    fn dependencies(&self, dependencies: &mut PluginDependencies) {
+        MilkTeaPlugin::dependencies(self, dependencies);
+    }
+
+    fn dynamic_setup(
+        self: Box<Self>,
+        registry: &mut PearlRegistry,
+        startup_stages: &mut StageCollection,
+        main_stages: &mut StageCollection,
+        resources: &mut BobaResources,
+    ) {
+        self.setup(registry, startup_stages, main_stages, resources);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use boba_core::{BobaResources, PearlRegistry, StageCollection};
+
+    use super::{MilkTeaPlugin, MilkTeaPlugins, PluginDependencies};
+
+    #[derive(Default)]
+    struct SetupOrder(Vec<&'static str>);
+
+    struct Audio {
+        volume: f32,
+    }
+
+    impl Default for Audio {
+        fn default() -> Self {
+            Self { volume: 1. }
+        }
+    }
+
+    #[derive(Default)]
+    struct Physics;
+
+    #[derive(Default)]
+    struct Ui;
+
+    fn log(resources: &mut BobaResources, name: &'static str) {
+        if resources.get::<SetupOrder>().is_err() {
+            resources.add(SetupOrder::default());
+        }
+        resources.get_mut::<SetupOrder>().unwrap().0.push(name);
+    }
+
+    impl MilkTeaPlugin for Audio {
+        fn setup(
+            self,
+            _: &mut PearlRegistry,
+            _: &mut StageCollection,
+            _: &mut StageCollection,
+            resources: &mut BobaResources,
+        ) {
+            log(resources, "audio");
+            resources.add(self.volume);
+        }
+    }
+
+    impl MilkTeaPlugin for Physics {
+        fn dependencies(&self, dependencies: &mut PluginDependencies) {
+            dependencies.require::<Audio>();
+        }
+
+        fn setup(
+            self,
+            _: &mut PearlRegistry,
+            _: &mut StageCollection,
+            _: &mut StageCollection,
+            resources: &mut BobaResources,
+        ) {
+            log(resources, "physics");
+        }
+    }
+
+    impl MilkTeaPlugin for Ui {
+        fn dependencies(&self, dependencies: &mut PluginDependencies) {
+            dependencies.require::<Physics>();
+            dependencies.require::<Audio>();
+        }
+
+        fn setup(
+            self,
+            _: &mut PearlRegistry,
+            _: &mut StageCollection,
+            _: &mut StageCollection,
+            resources: &mut BobaResources,
+        ) {
+            log(resources, "ui");
+        }
+    }
+
+    fn setup(plugins: &mut MilkTeaPlugins) -> BobaResources {
+        let mut resources = BobaResources::default();
+        plugins.setup(
+            &mut PearlRegistry::default(),
+            &mut StageCollection::default(),
+            &mut StageCollection::default(),
+            &mut resources,
+        );
+        resources
+    }
+
+    #[test]
+    fn dependencies_first() {
+        let mut plugins = MilkTeaPlugins::default();
+        plugins.insert(Ui);
+
+        let resources = setup(&mut plugins);
+        let order = resources.get::<SetupOrder>().unwrap();
+        assert!(order.0 == ["audio", "physics", "ui"]);
+        assert!(plugins.is_empty());
+    }
+
+    #[test]
+    fn deduplicate() {
+        let mut plugins = MilkTeaPlugins::default();
+        plugins.insert(Audio { volume: 0.2 });
+        plugins.insert(Physics);
+        plugins.insert(Audio { volume: 0.5 });
+        assert!(plugins.len() == 2);
+
+        // the explicitly added audio plugin should win over the default dependency
+        let resources = setup(&mut plugins);
+        assert!(resources.get::<SetupOrder>().unwrap().0 == ["audio", "physics"]);
+        assert!(*resources.get::<f32>().unwrap() == 0.5);
+    }
+}
@@ -0,0 +1,211 @@
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::ForwardedEvent;
+
+/// An error returned when recording or replaying input.
+#[derive(Debug, Error)]
+pub enum RecordError {
+    #[error("Could not access recording. Error: {0}")]
+    Io(std::io::Error),
+    #[error("Could not parse line {line} of recording. Error: {error}")]
+    Parse {
+        line: usize,
+        error: ron::error::SpannedError,
+    },
+    #[error("Could not serialize event. Error: {0}")]
+    Serialize(ron::Error),
+}
+
+/// A forwarded event along with the frame it was received on.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    pub frame: u64,
+    pub event: ForwardedEvent,
+}
+
+/// Writes every forwarded event to a recording, one RON encoded event per line.
+pub struct InputRecorder {
+    writer: Box<dyn Write>,
+}
+
+impl InputRecorder {
+    /// Creates a recorder that writes to a new file at `path`.
+    pub fn create(path: impl AsRef<Path>) -> Result<Self, RecordError> {
+        let file = File::create(path).map_err(RecordError::Io)?;
+        Ok(Self::from_writer(BufWriter::new(file)))
+    }
+
+    pub fn from_writer(writer: impl Write + 'static) -> Self {
+        Self {
+            writer: Box::new(writer),
+        }
+    }
+
+    /// Writes `event` to the recording as being received on `frame`.
+    pub fn record(&mut self, frame: u64, event: &ForwardedEvent) -> Result<(), RecordError> {
+        let line = ron::to_string(&RecordedEvent {
+            frame,
+            event: *event,
+        })
+        .map_err(RecordError::Serialize)?;
+
+        writeln!(self.writer, "{line}").map_err(RecordError::Io)
+    }
+
+    /// Flushes all buffered events to the underlying writer.
+    pub fn flush(&mut self) -> Result<(), RecordError> {
+        self.writer.flush().map_err(RecordError::Io)
+    }
+}
+
+/// A recording of forwarded events that can be fed back into an app.
+pub struct InputReplay {
+    events: VecDeque<RecordedEvent>,
+}
+
+impl InputReplay {
+    /// Loads a recording from the file at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RecordError> {
+        let file = File::open(path).map_err(RecordError::Io)?;
+        Self::from_reader(BufReader::new(file))
+    }
+
+    pub fn from_reader(reader: impl BufRead) -> Result<Self, RecordError> {
+        let mut events = VecDeque::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line.map_err(RecordError::Io)?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let event = ron::from_str(&line).map_err(|error| RecordError::Parse {
+                line: index + 1,
+                error,
+            })?;
+            events.push_back(event);
+        }
+
+        Ok(Self { events })
+    }
+
+    /// Returns true if every recorded event has been replayed.
+    pub fn is_finished(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Gets the frame of the last event in the recording.
+    pub fn last_frame(&self) -> Option<u64> {
+        self.events.back().map(|e| e.frame)
+    }
+
+    /// Removes and returns all events recorded on or before `frame`, in order.
+    pub fn take_frame(&mut self, frame: u64) -> Vec<ForwardedEvent> {
+        let mut events = Vec::new();
+        while let Some(recorded) = self.events.front() {
+            if recorded.frame > frame {
+                break;
+            }
+
+            events.push(recorded.event);
+            self.events.pop_front();
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use boba_core::{BobaResources, PearlRegistry, StageCollection};
+    use winit::window::Window;
+
+    use crate::{
+        event_types::MilkTeaGamepadEvent,
+        input::{GamepadButton, GamepadId, MilkTeaInput, MockGamepadBackend},
+        Bobarista, MilkTeaAdapter, MilkTeaWindowId,
+    };
+
+    use super::{InputRecorder, InputReplay};
+
+    struct HeadlessAdapter;
+
+    impl MilkTeaAdapter for HeadlessAdapter {
+        fn setup(
+            _: &mut PearlRegistry,
+            _: &mut StageCollection,
+            _: &mut StageCollection,
+            _: &mut BobaResources,
+        ) {
+        }
+
+        fn build(_: MilkTeaWindowId, _: Window) -> Self {
+            Self
+        }
+
+        fn add_window(&mut self, _: MilkTeaWindowId, _: Window) {}
+
+        fn remove_window(&mut self, _: MilkTeaWindowId) {}
+    }
+
+    fn south_held(app: &Bobarista<HeadlessAdapter>) -> bool {
+        let input = app.resources.get::<MilkTeaInput>().unwrap();
+        input.is_held(GamepadButton::South)
+    }
+
+    #[test]
+    fn record_and_replay() {
+        let name = format!("milk_tea_record_and_replay_{}.ron", std::process::id());
+        let path = std::env::temp_dir().join(name);
+
+        // record a button press on the third frame
+        let gamepads = MockGamepadBackend::new();
+        let mut app = Bobarista::<HeadlessAdapter>::default();
+        app.gamepads = Some(Box::new(gamepads.clone()));
+        app.recorder = Some(InputRecorder::create(&path).unwrap());
+        app.run_headless(2);
+        gamepads.push(MilkTeaGamepadEvent::Connected(GamepadId(0)));
+        gamepads.push(MilkTeaGamepadEvent::Button {
+            id: GamepadId(0),
+            button: GamepadButton::South,
+            state: winit::event::ElementState::Pressed,
+        });
+        app.run_headless(1);
+        assert!(south_held(&app));
+        drop(app);
+
+        // replay it into a fresh app
+        let replay = InputReplay::load(&path).unwrap();
+        assert!(replay.last_frame() == Some(2));
+
+        let mut app = Bobarista::<HeadlessAdapter>::default();
+        app.gamepads = None;
+        app.replay = Some(replay);
+        app.run_headless(2);
+        assert!(!south_held(&app));
+        app.run_headless(1);
+        assert!(south_held(&app));
+        assert!(app.replay.is_none());
+        assert!(app.frame() == 3);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn parse_error() {
+        let recording =
+            "(frame: 0, event: Resize((window: (0), width: 1, height: 1)))\nnot an event\n";
+        let result = InputReplay::from_reader(recording.as_bytes());
+        assert!(matches!(
+            result,
+            Err(super::RecordError::Parse { line: 2, .. })
+        ));
+    }
+}
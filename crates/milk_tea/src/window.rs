@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+/// The id of a window created by [`Bobarista`](crate::Bobarista).
+///
+/// The main window always has the id [`MilkTeaWindowId::MAIN`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MilkTeaWindowId(pub usize);
+
+impl MilkTeaWindowId {
+    pub const MAIN: Self = Self(0);
+}
+
+impl Default for MilkTeaWindowId {
+    fn default() -> Self {
+        Self::MAIN
+    }
+}
+
+/// Settings used to create a window when the app starts running.
+#[derive(Debug, Clone)]
+pub struct MilkTeaWindowSettings {
+    pub title: String,
+    pub size: Option<(u32, u32)>,
+}
+
+impl Default for MilkTeaWindowSettings {
+    fn default() -> Self {
+        Self::new("Milk Tea Window")
+    }
+}
+
+impl MilkTeaWindowSettings {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.into(),
+            size: None,
+        }
+    }
+
+    pub fn with_size(mut self, width: u32, height: u32) -> Self {
+        self.size = Some((width, height));
+        self
+    }
+}
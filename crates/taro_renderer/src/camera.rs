@@ -1,13 +1,18 @@
 use boba_3d::{
-    glam::{Mat4, Quat, Vec3},
+    geometry::Ray,
+    glam::{Mat4, Quat, Vec2, Vec3},
     pearls::BobaTransform,
 };
-use boba_core::Pearl;
+use boba_core::{Pearl, PearlError};
 use log::error;
 
 use crate::{
-    shading::{buffers::CameraMatrix, data_types::DepthView, Taro},
-    TaroHardware, TaroRenderPasses, TaroRenderPearls,
+    shading::{
+        buffers::{CameraMatrix, UniformBuffer},
+        data_types::{Texture2D, Texture2DView},
+        Bind, BindSingle, Taro,
+    },
+    TaroCameraRegion, TaroHardware, TaroRenderGraph, TaroRenderPearls, TaroSurfaceId, TaroViewport,
 };
 
 #[derive(Default)]
@@ -15,80 +20,363 @@ pub struct TaroCameras {
     pub cameras: Vec<TaroCamera>,
 }
 
+/// The projection used to map view space onto the camera viewport
+#[derive(Debug, Clone)]
+pub enum TaroProjection {
+    /// Perspective projection with a vertical field of view in degrees
+    Perspective { fovy: f32 },
+    /// Orthographic projection with a visible height in world units.
+    ///
+    /// The visible width is derived from the aspect ratio of the viewport.
+    Orthographic { height: f32 },
+    /// A user provided projection matrix.
+    ///
+    /// The near and far planes of the settings are ignored for custom projections.
+    Custom(Mat4),
+}
+
 #[derive(Debug, Clone)]
 pub struct TaroCameraSettings {
-    pub fovy: f32,
+    pub projection: TaroProjection,
     pub znear: f32,
     pub zfar: f32,
 }
 
+impl Default for TaroCameraSettings {
+    fn default() -> Self {
+        Self::perspective(60.0, 0.1, 100.0)
+    }
+}
+
+impl TaroCameraSettings {
+    pub fn perspective(fovy: f32, znear: f32, zfar: f32) -> Self {
+        Self {
+            projection: TaroProjection::Perspective { fovy },
+            znear,
+            zfar,
+        }
+    }
+
+    pub fn orthographic(height: f32, znear: f32, zfar: f32) -> Self {
+        Self {
+            projection: TaroProjection::Orthographic { height },
+            znear,
+            zfar,
+        }
+    }
+
+    pub fn custom(matrix: Mat4) -> Self {
+        Self {
+            projection: TaroProjection::Custom(matrix),
+            ..Default::default()
+        }
+    }
+
+    /// Calculates the projection matrix for a viewport with the given `aspect` ratio
+    pub fn projection_matrix(&self, aspect: f32) -> Mat4 {
+        match &self.projection {
+            TaroProjection::Perspective { fovy } => {
+                Mat4::perspective_rh(fovy.to_radians(), aspect, self.znear, self.zfar)
+            }
+            TaroProjection::Orthographic { height } => {
+                let half_height = height / 2.;
+                let half_width = half_height * aspect;
+                Mat4::orthographic_rh(
+                    -half_width,
+                    half_width,
+                    -half_height,
+                    half_height,
+                    self.znear,
+                    self.zfar,
+                )
+            }
+            TaroProjection::Custom(matrix) => *matrix,
+        }
+    }
+}
+
+/// The destination that a camera renders into
+#[derive(Clone)]
+pub enum TaroCameraTarget {
+    /// A surface provided by the [`TaroSurfaceManager`](crate::stages::TaroSurfaceManager)
+    Surface(TaroSurfaceId),
+    /// An offscreen texture created with [`Texture2DView::new_render_target`].
+    ///
+    /// Texture targets are rendered before any surfaces, so the texture can be
+    /// sampled by other cameras in the same frame. A camera should never see
+    /// a material that samples its own target texture.
+    Texture(Taro<Texture2DView>),
+}
+
+impl Default for TaroCameraTarget {
+    fn default() -> Self {
+        Self::Surface(Default::default())
+    }
+}
+
+impl From<TaroSurfaceId> for TaroCameraTarget {
+    fn from(id: TaroSurfaceId) -> Self {
+        Self::Surface(id)
+    }
+}
+
+impl From<Taro<Texture2DView>> for TaroCameraTarget {
+    fn from(texture: Taro<Texture2DView>) -> Self {
+        Self::Texture(texture)
+    }
+}
+
+impl TaroCameraTarget {
+    pub fn is_surface(&self, id: TaroSurfaceId) -> bool {
+        matches!(self, Self::Surface(surface) if *surface == id)
+    }
+
+    pub fn texture(&self) -> Option<&Taro<Texture2DView>> {
+        match self {
+            Self::Texture(texture) => Some(texture),
+            Self::Surface(_) => None,
+        }
+    }
+}
+
+/// The position and matrices of a camera for the frame being rendered
+#[derive(Clone)]
+pub struct TaroCameraView {
+    pub position: Vec3,
+    pub view_matrix: Mat4,
+    pub projection_matrix: Mat4,
+    /// The combined view projection matrix, ready to be uploaded to shaders
+    pub camera_matrix: CameraMatrix,
+    /// The uniform holding the camera matrix, which mesh shaders bind to group 0.
+    ///
+    /// Every camera owns its own uniform, so cameras rendered in the same frame
+    /// each draw with their own matrix.
+    pub binding: Taro<BindSingle<UniformBuffer<CameraMatrix>>>,
+}
+
+impl Default for TaroCameraView {
+    fn default() -> Self {
+        Self {
+            position: Vec3::ZERO,
+            view_matrix: Mat4::IDENTITY,
+            projection_matrix: Mat4::IDENTITY,
+            camera_matrix: CameraMatrix::default(),
+            binding: Bind::new(UniformBuffer::new(), wgpu::ShaderStages::VERTEX),
+        }
+    }
+}
+
 pub struct TaroCamera {
     size: (u32, u32),
-    camera_matrix: CameraMatrix,
-    depth_texture: Taro<DepthView>,
+    format: wgpu::TextureFormat,
+    view: TaroCameraView,
 
     pub transform: Pearl<BobaTransform>,
     pub settings: TaroCameraSettings,
-    pub passes: TaroRenderPasses,
+    pub passes: TaroRenderGraph,
+    pub target: TaroCameraTarget,
+    /// The normalized area of the target this camera renders into
+    pub viewport: TaroViewport,
+    /// An optional normalized area of the target that drawing is clipped to.
+    ///
+    /// The scissor is always limited to the area of the viewport.
+    pub scissor: Option<TaroViewport>,
+    /// Cameras on the same target are rendered in ascending priority order,
+    /// so cameras with a higher priority are drawn on top.
+    pub priority: i32,
 }
 
 impl TaroCamera {
     pub fn new(transform: Pearl<BobaTransform>, settings: TaroCameraSettings) -> Self {
         Self {
             size: (1, 1),
-            camera_matrix: CameraMatrix::default(),
-            depth_texture: DepthView::new((1, 1)),
+            format: Texture2D::RENDER_TARGET_FORMAT,
+            view: TaroCameraView::default(),
             transform,
             settings,
             passes: Default::default(),
+            target: Default::default(),
+            viewport: Default::default(),
+            scissor: None,
+            priority: 0,
         }
     }
 
     pub fn new_simple(transform: BobaTransform, settings: TaroCameraSettings) -> Self {
-        Self {
-            size: (1, 1),
-            camera_matrix: CameraMatrix::default(),
-            depth_texture: DepthView::new((1, 1)),
-            transform: Pearl::wrap(transform),
-            settings,
-            passes: Default::default(),
+        Self::new(Pearl::wrap(transform), settings)
+    }
+
+    pub fn with_target(mut self, target: impl Into<TaroCameraTarget>) -> Self {
+        self.target = target.into();
+        self
+    }
+
+    pub fn with_viewport(mut self, viewport: TaroViewport) -> Self {
+        self.viewport = viewport;
+        self
+    }
+
+    pub fn with_scissor(mut self, scissor: TaroViewport) -> Self {
+        self.scissor = Some(scissor);
+        self
+    }
+
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Returns the size of the target this camera last rendered into
+    pub fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    /// Calculates the pixel region of the target that this camera renders into
+    pub fn region(&self, clear_target: bool) -> TaroCameraRegion {
+        let viewport = self.viewport.pixel_rect(self.size);
+        let scissor = match &self.scissor {
+            Some(scissor) => scissor.pixel_rect(self.size).intersection(&viewport),
+            None => viewport,
+        };
+
+        TaroCameraRegion {
+            target_size: self.size,
+            target_format: self.format,
+            viewport,
+            scissor,
+            clear_target,
         }
     }
 
+    /// Renders the camera into `view`.
+    ///
+    /// Nothing is rendered if the viewport or scissor of this camera is empty.
     pub fn render(
         &mut self,
         pearls: &TaroRenderPearls,
         view: &wgpu::TextureView,
+        clear_target: bool,
         encoder: &mut wgpu::CommandEncoder,
         hardware: &TaroHardware,
     ) {
-        match self.transform.borrow() {
-            Ok(t) => {
-                self.camera_matrix = Self::calculate_matrix(
-                    t.world_position(),
-                    t.world_rotation(),
-                    self.size.0 as f32 / self.size.1 as f32,
-                    &self.settings,
-                );
+        let region = self.region(clear_target);
+        if region.viewport.is_empty() || region.scissor.is_empty() {
+            return;
+        }
+
+        match self.calculate_view() {
+            Ok(view) => {
+                self.view = view;
             }
             Err(e) => {
                 error!("Error when calculating camera matrix. Error: {e}");
             }
         };
 
-        self.passes.render(
-            pearls,
-            &self.camera_matrix,
-            view,
-            &self.depth_texture,
-            encoder,
-            hardware,
-        );
+        let binding = self.view.binding.data();
+        binding.write_buffer(&self.view.camera_matrix, hardware);
+
+        self.passes
+            .render(pearls, &self.view, &region, view, encoder, hardware);
     }
 
-    pub(crate) fn resize(&mut self, size: (u32, u32)) {
+    pub(crate) fn resize(&mut self, size: (u32, u32), format: wgpu::TextureFormat) {
         self.size = size;
-        self.depth_texture = DepthView::new(size);
+        self.format = format;
+    }
+
+    /// Calculates the view matrix from the world transform of the camera
+    pub fn view_matrix(&self) -> Result<Mat4, PearlError> {
+        let transform = self.transform.borrow()?;
+        Ok(Self::look_matrix(
+            transform.world_position(),
+            transform.world_rotation(),
+        ))
+    }
+
+    /// Calculates the projection matrix using the aspect ratio of the camera viewport
+    pub fn projection_matrix(&self) -> Mat4 {
+        let aspect = self.viewport.pixel_rect(self.size).aspect();
+        self.settings.projection_matrix(aspect)
+    }
+
+    pub fn view_projection_matrix(&self) -> Result<Mat4, PearlError> {
+        Ok(self.projection_matrix() * self.view_matrix()?)
+    }
+
+    /// Gets the view that this camera last rendered with
+    pub fn view(&self) -> &TaroCameraView {
+        &self.view
+    }
+
+    /// Calculates the view that the render graph of this camera uses.
+    ///
+    /// The view shares the camera matrix uniform of this camera.
+    pub fn calculate_view(&self) -> Result<TaroCameraView, PearlError> {
+        let position = self.transform.borrow()?.world_position();
+        let view_matrix = self.view_matrix()?;
+        let projection_matrix = self.projection_matrix();
+        Ok(TaroCameraView {
+            position,
+            view_matrix,
+            projection_matrix,
+            camera_matrix: (projection_matrix * view_matrix).into(),
+            binding: self.view.binding.clone(),
+        })
+    }
+
+    /// Converts a world space `point` into pixel coordinates of the camera target.
+    ///
+    /// The origin of the screen is the top left corner of the target.
+    /// Returns `None` if the point is behind the camera.
+    pub fn world_to_screen(&self, point: Vec3) -> Result<Option<Vec2>, PearlError> {
+        // the camera looks down -z in view space, for both perspective and orthographic cameras
+        let view = self.view_matrix()?.transform_point3(point);
+        if view.z >= 0. {
+            return Ok(None);
+        }
+
+        let clip = self.projection_matrix() * view.extend(1.);
+        let ndc = clip.truncate() / clip.w;
+        let normalized = Vec2::new(ndc.x + 1., 1. - ndc.y) / 2.;
+        let rect = self.viewport.pixel_rect(self.size);
+        let origin = Vec2::new(rect.x as f32, rect.y as f32);
+        let size = Vec2::new(rect.width as f32, rect.height as f32);
+        Ok(Some(origin + normalized * size))
+    }
+
+    /// Creates a world space ray going through the pixel at `screen` on the camera target.
+    ///
+    /// The ray starts on the near plane of the camera.
+    pub fn screen_to_world_ray(&self, screen: Vec2) -> Result<Ray, PearlError> {
+        let rect = self.viewport.pixel_rect(self.size);
+        let origin = Vec2::new(rect.x as f32, rect.y as f32);
+        let size = Vec2::new(rect.width.max(1) as f32, rect.height.max(1) as f32);
+        self.viewport_to_world_ray((screen - origin) / size)
+    }
+
+    /// Creates a world space ray going through a normalized `viewport` point.
+    ///
+    /// The origin of the viewport is its top left corner, and `(1, 1)` is the bottom right.
+    pub fn viewport_to_world_ray(&self, viewport: Vec2) -> Result<Ray, PearlError> {
+        let inverse = self.view_projection_matrix()?.inverse();
+        let ndc = Vec2::new(viewport.x * 2. - 1., 1. - viewport.y * 2.);
+        let near = inverse.project_point3(ndc.extend(0.));
+        let far = inverse.project_point3(ndc.extend(1.));
+        Ok(Ray::new(near, far - near))
+    }
+
+    /// Converts a normalized `viewport` point into world space.
+    ///
+    /// The returned point lies `depth` units in front of the camera,
+    /// measured along the direction that the camera is facing.
+    pub fn viewport_to_world(&self, viewport: Vec2, depth: f32) -> Result<Vec3, PearlError> {
+        let ray = self.viewport_to_world_ray(viewport)?;
+        let transform = self.transform.borrow()?;
+        let forward = transform.world_rotation() * Vec3::Z;
+        let plane_point = transform.world_position() + forward * depth;
+        let distance = (plane_point - ray.origin).dot(forward) / ray.direction.dot(forward);
+        Ok(ray.at(distance))
     }
 
     pub fn calculate_matrix(
@@ -97,15 +385,105 @@ impl TaroCamera {
         aspect: f32,
         settings: &TaroCameraSettings,
     ) -> CameraMatrix {
-        let target = position + rotation * Vec3::Z;
-        let view = Mat4::look_at_rh(position, target, Vec3::Y);
-        let proj = Mat4::perspective_rh(
-            settings.fovy.to_radians(),
-            aspect,
-            settings.znear,
-            settings.zfar,
-        );
+        let view = Self::look_matrix(position, rotation);
+        let proj = settings.projection_matrix(aspect);
 
         (proj * view).into()
     }
+
+    fn look_matrix(position: Vec3, rotation: Quat) -> Mat4 {
+        let target = position + rotation * Vec3::Z;
+        Mat4::look_at_rh(position, target, Vec3::Y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shading::buffers::TaroBytesBuilder;
+
+    use super::*;
+
+    fn test_camera(settings: TaroCameraSettings) -> TaroCamera {
+        let transform = BobaTransform::from_position_look_at(Vec3::new(0., 0., -5.), Vec3::ZERO);
+        let mut camera = TaroCamera::new_simple(transform, settings);
+        camera.resize((200, 100), Texture2D::RENDER_TARGET_FORMAT);
+        camera
+    }
+
+    #[test]
+    fn screen_conversions() {
+        let camera = test_camera(TaroCameraSettings::default());
+
+        let center = camera.world_to_screen(Vec3::ZERO).unwrap().unwrap();
+        assert!(center.abs_diff_eq(Vec2::new(100., 50.), 1e-3));
+        assert!(camera
+            .world_to_screen(Vec3::new(0., 0., -10.))
+            .unwrap()
+            .is_none());
+
+        let point = Vec3::new(1., 0.5, 2.);
+        let screen = camera.world_to_screen(point).unwrap().unwrap();
+        let ray = camera.screen_to_world_ray(screen).unwrap();
+        let distance = ray.origin.distance(point);
+        assert!(ray.at(distance).abs_diff_eq(point, 1e-3));
+
+        let world = camera.viewport_to_world(Vec2::splat(0.5), 5.).unwrap();
+        assert!(world.abs_diff_eq(Vec3::ZERO, 1e-3));
+    }
+
+    #[test]
+    fn separate_camera_uniforms() {
+        let left = test_camera(TaroCameraSettings::default());
+        let mut top = test_camera(TaroCameraSettings::orthographic(10., 0.1, 100.));
+        top.transform = Pearl::wrap(BobaTransform::from_position_look_at(
+            Vec3::new(0., 5., 0.),
+            Vec3::ZERO,
+        ));
+
+        // every view of a camera writes into the uniform owned by that camera
+        let left_view = left.calculate_view().unwrap();
+        let top_view = top.calculate_view().unwrap();
+        assert!(left_view.binding.ptr_eq(&left.view().binding));
+        assert!(top_view.binding.ptr_eq(&top.view().binding));
+        assert!(!left_view.binding.ptr_eq(&top_view.binding));
+
+        let left_matrix = left_view.camera_matrix.build_bytes().to_vec();
+        assert!(left_matrix != top_view.camera_matrix.build_bytes());
+    }
+
+    #[test]
+    fn texture_target_uniform() {
+        let surface = test_camera(TaroCameraSettings::default());
+        let texture = Texture2DView::new_render_target((64, 64));
+        let mirror = TaroCamera::new_simple(
+            BobaTransform::from_position_look_at(Vec3::new(0., 0., 5.), Vec3::ZERO),
+            TaroCameraSettings::default(),
+        )
+        .with_target(texture.clone());
+
+        // the texture camera renders with its own uniform and matrix, not the surface camera
+        let mirror_view = mirror.calculate_view().unwrap();
+        let surface_view = surface.calculate_view().unwrap();
+        assert!(mirror.target.texture().unwrap().ptr_eq(&texture));
+        assert!(!mirror_view.binding.ptr_eq(&surface_view.binding));
+        let mirror_matrix = mirror_view.camera_matrix.build_bytes().to_vec();
+        assert!(mirror_matrix != surface_view.camera_matrix.build_bytes());
+    }
+
+    #[test]
+    fn orthographic_viewport() {
+        let mut camera = test_camera(TaroCameraSettings::orthographic(10., 0.1, 100.));
+        camera.viewport = TaroViewport::new(0.5, 0., 0.5, 1.);
+
+        // the right half of the target is square, so the visible area is 10x10 units
+        let corner = camera.viewport_to_world(Vec2::ZERO, 5.).unwrap();
+        assert!(corner.abs_diff_eq(Vec3::new(5., 5., 0.), 1e-3));
+
+        let screen = camera.world_to_screen(corner).unwrap().unwrap();
+        assert!(screen.abs_diff_eq(Vec2::new(100., 0.), 1e-3));
+
+        // points behind an orthographic camera are not on screen either
+        let behind = camera.world_to_screen(Vec3::new(0., 0., -10.)).unwrap();
+        assert!(behind.is_none());
+    }
 }
@@ -0,0 +1,95 @@
+use std::fmt::Display;
+
+use crate::shading::data_types::DepthView;
+
+/// Identifies a texture that passes in a [`TaroRenderGraph`](super::TaroRenderGraph) read and write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TaroAttachment(pub &'static str);
+
+impl TaroAttachment {
+    /// The final target of the camera, such as a surface or render texture.
+    pub const TARGET: Self = Self("target");
+
+    /// The color buffer that the scene is rendered into.
+    ///
+    /// It is created by the graph with the [`COLOR_FORMAT`](Self::COLOR_FORMAT)
+    /// at the size of the target, and is resolved into the target by
+    /// [`TaroPostProcessing`](crate::post::TaroPostProcessing).
+    pub const COLOR: Self = Self("color");
+
+    /// The high dynamic range format used by the [`COLOR`](Self::COLOR) attachment
+    pub const COLOR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+    /// The depth buffer of the camera.
+    ///
+    /// It is created by the graph with the [`DepthView::FORMAT`] at the size of the target.
+    pub const DEPTH: Self = Self("depth");
+
+    /// Returns true if this attachment is provided by the graph itself
+    pub fn is_builtin(&self) -> bool {
+        *self == Self::TARGET || *self == Self::COLOR || *self == Self::DEPTH
+    }
+}
+
+impl Display for TaroAttachment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "'{}'", self.0)
+    }
+}
+
+/// The size of a transient graph texture
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TaroTextureSize {
+    /// The same size as the camera target
+    Target,
+    /// The size of the camera target multiplied by a factor
+    Scaled(f32),
+    /// A fixed size in pixels
+    Fixed(u32, u32),
+}
+
+impl TaroTextureSize {
+    pub fn resolve(&self, target_size: (u32, u32)) -> (u32, u32) {
+        let (width, height) = match *self {
+            Self::Target => target_size,
+            Self::Scaled(factor) => (
+                (target_size.0 as f32 * factor).round() as u32,
+                (target_size.1 as f32 * factor).round() as u32,
+            ),
+            Self::Fixed(width, height) => (width, height),
+        };
+
+        (width.max(1), height.max(1))
+    }
+}
+
+/// Describes a transient texture created by a pass in the graph.
+///
+/// Transient textures are always usable as both a render attachment and a sampled texture.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TaroTextureDesc {
+    pub format: wgpu::TextureFormat,
+    pub size: TaroTextureSize,
+}
+
+impl TaroTextureDesc {
+    pub fn new(format: wgpu::TextureFormat) -> Self {
+        Self {
+            format,
+            size: TaroTextureSize::Target,
+        }
+    }
+
+    pub fn color() -> Self {
+        Self::new(TaroAttachment::COLOR_FORMAT)
+    }
+
+    pub fn depth() -> Self {
+        Self::new(DepthView::FORMAT)
+    }
+
+    pub fn with_size(mut self, size: TaroTextureSize) -> Self {
+        self.size = size;
+        self
+    }
+}
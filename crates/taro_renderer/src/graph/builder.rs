@@ -0,0 +1,54 @@
+use super::{TaroAttachment, TaroTextureDesc};
+
+/// Collects the attachments that a pass reads, writes and creates.
+#[derive(Debug, Default, Clone)]
+pub struct TaroPassBuilder {
+    pub(crate) reads: Vec<TaroAttachment>,
+    pub(crate) writes: Vec<TaroAttachment>,
+    pub(crate) creates: Vec<(TaroAttachment, TaroTextureDesc)>,
+}
+
+impl TaroPassBuilder {
+    /// Declares that the pass samples `attachment`.
+    ///
+    /// The pass will run after every pass that writes to it.
+    pub fn read(&mut self, attachment: TaroAttachment) -> &mut Self {
+        if !self.reads.contains(&attachment) {
+            self.reads.push(attachment);
+        }
+        self
+    }
+
+    /// Declares that the pass renders into `attachment`
+    pub fn write(&mut self, attachment: TaroAttachment) -> &mut Self {
+        if !self.writes.contains(&attachment) {
+            self.writes.push(attachment);
+        }
+        self
+    }
+
+    /// Declares that the pass creates a new transient `attachment` and renders into it.
+    ///
+    /// The pass will run before any other pass that uses it.
+    pub fn create(&mut self, attachment: TaroAttachment, desc: TaroTextureDesc) -> &mut Self {
+        self.creates.retain(|(a, _)| *a != attachment);
+        self.creates.push((attachment, desc));
+        self.write(attachment)
+    }
+
+    pub fn reads(&self, attachment: TaroAttachment) -> bool {
+        self.reads.contains(&attachment)
+    }
+
+    pub fn writes(&self, attachment: TaroAttachment) -> bool {
+        self.writes.contains(&attachment)
+    }
+
+    pub fn creates(&self, attachment: TaroAttachment) -> bool {
+        self.creates.iter().any(|(a, _)| *a == attachment)
+    }
+
+    pub(crate) fn uses(&self) -> impl Iterator<Item = &TaroAttachment> {
+        self.reads.iter().chain(self.writes.iter())
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{TaroCameraRegion, TaroCameraView, TaroHardware, TaroRenderPearls};
+
+use super::{plan::GraphPlan, TaroAttachment};
+
+/// Everything a pass needs to render as part of a [`TaroRenderGraph`](super::TaroRenderGraph)
+pub struct TaroPassContext<'a> {
+    pub pearls: &'a TaroRenderPearls,
+    pub camera: &'a TaroCameraView,
+    pub region: &'a TaroCameraRegion,
+    pub encoder: &'a mut wgpu::CommandEncoder,
+    pub hardware: &'a TaroHardware,
+
+    pub(crate) pass: usize,
+    pub(crate) plan: &'a GraphPlan,
+    pub(crate) target: &'a wgpu::TextureView,
+    pub(crate) views: &'a [wgpu::TextureView],
+}
+
+impl<'a> TaroPassContext<'a> {
+    /// Gets the view for `attachment`.
+    ///
+    /// Returns `None` if the pass did not declare the attachment.
+    pub fn attachment(&self, attachment: TaroAttachment) -> Option<&'a wgpu::TextureView> {
+        if !self.plan.passes[self.pass].uses().any(|a| *a == attachment) {
+            return None;
+        }
+
+        match attachment {
+            TaroAttachment::TARGET => Some(self.target),
+            _ => {
+                let index = self.plan.assignments.get(&attachment)?;
+                self.views.get(*index)
+            }
+        }
+    }
+
+    /// Returns true if this pass is the first to write into `attachment` this frame.
+    ///
+    /// For the target this is only true for the first camera rendering into it.
+    pub fn is_first_write(&self, attachment: TaroAttachment) -> bool {
+        if attachment == TaroAttachment::TARGET && !self.region.clear_target {
+            return false;
+        }
+
+        self.plan.first_writers.get(&attachment) == Some(&self.pass)
+    }
+
+    /// Gets the load operation for a color `attachment`,
+    /// clearing it to `color` if this is the first write.
+    pub fn color_load(
+        &self,
+        attachment: TaroAttachment,
+        color: wgpu::Color,
+    ) -> wgpu::LoadOp<wgpu::Color> {
+        match self.is_first_write(attachment) {
+            true => wgpu::LoadOp::Clear(color),
+            false => wgpu::LoadOp::Load,
+        }
+    }
+
+    /// Gets the load operation for a depth `attachment`,
+    /// clearing it to the far plane if this is the first write.
+    pub fn depth_load(&self, attachment: TaroAttachment) -> wgpu::LoadOp<f32> {
+        match self.is_first_write(attachment) {
+            true => wgpu::LoadOp::Clear(1.0),
+            false => wgpu::LoadOp::Load,
+        }
+    }
+}
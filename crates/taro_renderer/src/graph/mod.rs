@@ -0,0 +1,11 @@
+mod attachment;
+mod builder;
+mod context;
+mod plan;
+mod render_graph;
+
+pub use attachment::*;
+pub use builder::*;
+pub use context::*;
+pub use plan::TaroGraphError;
+pub use render_graph::*;
@@ -0,0 +1,291 @@
+use hashbrown::HashMap;
+use thiserror::Error;
+
+use super::{TaroAttachment, TaroPassBuilder, TaroTextureDesc};
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum TaroGraphError {
+    #[error("Pass '{pass}' uses attachment {attachment} which is never created")]
+    MissingAttachment {
+        pass: &'static str,
+        attachment: TaroAttachment,
+    },
+    #[error("Attachment {attachment} is created by both '{first}' and '{second}'")]
+    DuplicateAttachment {
+        attachment: TaroAttachment,
+        first: &'static str,
+        second: &'static str,
+    },
+    #[error("Pass '{pass}' tries to create the builtin attachment {attachment}")]
+    BuiltinAttachment {
+        pass: &'static str,
+        attachment: TaroAttachment,
+    },
+    #[error("Pass '{pass}' both reads and writes attachment {attachment}")]
+    ReadWriteConflict {
+        pass: &'static str,
+        attachment: TaroAttachment,
+    },
+    #[error("The passes in the render graph depend on each other in a cycle")]
+    Cycle,
+}
+
+/// A texture that the graph has to allocate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct GraphTexture {
+    pub format: wgpu::TextureFormat,
+    pub size: (u32, u32),
+}
+
+/// The execution order and texture layout of a render graph
+#[derive(Debug)]
+pub(crate) struct GraphPlan {
+    /// The pass indices in the order they have to run
+    pub order: Vec<usize>,
+    /// The declarations of each pass in insertion order
+    pub passes: Vec<TaroPassBuilder>,
+    /// The textures that have to be allocated, possibly shared between attachments
+    pub textures: Vec<GraphTexture>,
+    /// The index into `textures` used by each transient attachment
+    pub assignments: HashMap<TaroAttachment, usize>,
+    /// The index of the first pass that writes to each attachment
+    pub first_writers: HashMap<TaroAttachment, usize>,
+}
+
+impl GraphPlan {
+    pub fn build(
+        names: &[&'static str],
+        passes: Vec<TaroPassBuilder>,
+        target_size: (u32, u32),
+    ) -> Result<Self, TaroGraphError> {
+        // find the creator of every transient attachment
+        let mut creators = HashMap::<TaroAttachment, (usize, TaroTextureDesc)>::new();
+        for (index, pass) in passes.iter().enumerate() {
+            for (attachment, desc) in pass.creates.iter() {
+                if attachment.is_builtin() {
+                    return Err(TaroGraphError::BuiltinAttachment {
+                        pass: names[index],
+                        attachment: *attachment,
+                    });
+                }
+
+                if let Some((first, _)) = creators.insert(*attachment, (index, *desc)) {
+                    return Err(TaroGraphError::DuplicateAttachment {
+                        attachment: *attachment,
+                        first: names[first],
+                        second: names[index],
+                    });
+                }
+            }
+        }
+
+        // collect the writers of each attachment, starting with its creator
+        let mut writers = HashMap::<TaroAttachment, Vec<usize>>::new();
+        for (attachment, (index, _)) in creators.iter() {
+            writers.insert(*attachment, vec![*index]);
+        }
+        for (index, pass) in passes.iter().enumerate() {
+            for attachment in pass.writes.iter() {
+                if pass.reads(*attachment) {
+                    return Err(TaroGraphError::ReadWriteConflict {
+                        pass: names[index],
+                        attachment: *attachment,
+                    });
+                }
+
+                let known = attachment.is_builtin() || creators.contains_key(attachment);
+                if !known {
+                    return Err(TaroGraphError::MissingAttachment {
+                        pass: names[index],
+                        attachment: *attachment,
+                    });
+                }
+
+                let list = writers.entry(*attachment).or_default();
+                if !list.contains(&index) {
+                    list.push(index);
+                }
+            }
+        }
+
+        // build the dependencies between passes
+        let mut dependencies = vec![Vec::<usize>::new(); passes.len()];
+        for list in writers.values() {
+            for pair in list.windows(2) {
+                dependencies[pair[1]].push(pair[0]);
+            }
+        }
+        for (index, pass) in passes.iter().enumerate() {
+            for attachment in pass.reads.iter() {
+                match writers.get(attachment).and_then(|list| list.last()) {
+                    Some(last) => dependencies[index].push(*last),
+                    // the target may be read without being written, as it holds earlier cameras
+                    None if *attachment == TaroAttachment::TARGET => (),
+                    None => {
+                        return Err(TaroGraphError::MissingAttachment {
+                            pass: names[index],
+                            attachment: *attachment,
+                        })
+                    }
+                }
+            }
+        }
+
+        // sort the passes, preferring insertion order when there is a choice
+        let mut order = Vec::with_capacity(passes.len());
+        let mut scheduled = vec![false; passes.len()];
+        while order.len() < passes.len() {
+            let next = (0..passes.len()).find(|&index| {
+                !scheduled[index] && dependencies[index].iter().all(|&dep| scheduled[dep])
+            });
+
+            let Some(next) = next else {
+                return Err(TaroGraphError::Cycle);
+            };
+
+            scheduled[next] = true;
+            order.push(next);
+        }
+
+        // find the lifetime of every transient attachment
+        let mut lifetimes = Vec::<(TaroAttachment, GraphTexture, usize, usize)>::new();
+        for (position, &index) in order.iter().enumerate() {
+            for attachment in passes[index].uses() {
+                if let Some(lifetime) = lifetimes.iter_mut().find(|l| l.0 == *attachment) {
+                    lifetime.3 = position;
+                    continue;
+                }
+
+                let desc = match creators.get(attachment) {
+                    Some((_, desc)) => *desc,
+                    None if *attachment == TaroAttachment::COLOR => TaroTextureDesc::color(),
+                    None if *attachment == TaroAttachment::DEPTH => TaroTextureDesc::depth(),
+                    None => continue,
+                };
+
+                let texture = GraphTexture {
+                    format: desc.format,
+                    size: desc.size.resolve(target_size),
+                };
+                lifetimes.push((*attachment, texture, position, position));
+            }
+        }
+
+        // alias attachments that are never alive at the same time
+        let mut textures = Vec::<GraphTexture>::new();
+        let mut texture_free = Vec::<usize>::new();
+        let mut assignments = HashMap::new();
+        for (attachment, texture, first, last) in lifetimes {
+            let shared =
+                (0..textures.len()).find(|&i| textures[i] == texture && texture_free[i] < first);
+
+            let index = match shared {
+                Some(index) => index,
+                None => {
+                    textures.push(texture);
+                    texture_free.push(0);
+                    textures.len() - 1
+                }
+            };
+
+            texture_free[index] = last;
+            assignments.insert(attachment, index);
+        }
+
+        // find the first pass that writes to each attachment
+        let mut first_writers = HashMap::new();
+        for &index in order.iter() {
+            for attachment in passes[index].writes.iter() {
+                first_writers.entry(*attachment).or_insert(index);
+            }
+        }
+
+        Ok(Self {
+            order,
+            passes,
+            textures,
+            assignments,
+            first_writers,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HDR: TaroAttachment = TaroAttachment("hdr");
+    const BLOOM: TaroAttachment = TaroAttachment("bloom");
+    const NAMES: [&str; 4] = ["a", "b", "c", "d"];
+
+    fn hdr_desc() -> TaroTextureDesc {
+        TaroTextureDesc::new(wgpu::TextureFormat::Rgba16Float)
+    }
+
+    #[test]
+    fn dependency_order() {
+        let mut post = TaroPassBuilder::default();
+        post.read(HDR).write(TaroAttachment::TARGET);
+        let mut scene = TaroPassBuilder::default();
+        scene.create(HDR, hdr_desc()).write(TaroAttachment::DEPTH);
+
+        let plan = GraphPlan::build(&NAMES, vec![post, scene], (64, 64)).unwrap();
+        assert!(plan.order == vec![1, 0]);
+        assert!(plan.textures.len() == 2);
+        assert!(plan.first_writers[&TaroAttachment::TARGET] == 0);
+        assert!(!plan.assignments.contains_key(&TaroAttachment::TARGET));
+    }
+
+    #[test]
+    fn aliasing() {
+        let mut scene = TaroPassBuilder::default();
+        scene.create(HDR, hdr_desc());
+        let mut bloom = TaroPassBuilder::default();
+        bloom.read(HDR).create(BLOOM, hdr_desc());
+        let mut tonemap = TaroPassBuilder::default();
+        tonemap.read(BLOOM).write(TaroAttachment::TARGET);
+
+        let plan = GraphPlan::build(&NAMES, vec![scene, bloom, tonemap], (64, 64)).unwrap();
+        assert!(plan.order == vec![0, 1, 2]);
+        // hdr and bloom are alive together during the bloom pass
+        assert!(plan.textures.len() == 2);
+
+        let mut scene = TaroPassBuilder::default();
+        scene.create(HDR, hdr_desc());
+        let mut copy = TaroPassBuilder::default();
+        copy.read(HDR).write(TaroAttachment::TARGET);
+        let mut overlay = TaroPassBuilder::default();
+        overlay.create(BLOOM, hdr_desc());
+        let mut composite = TaroPassBuilder::default();
+        composite.read(BLOOM).write(TaroAttachment::TARGET);
+
+        let passes = vec![scene, copy, overlay, composite];
+        let plan = GraphPlan::build(&NAMES, passes, (64, 64)).unwrap();
+        assert!(plan.textures.len() == 1);
+        assert!(plan.assignments[&HDR] == plan.assignments[&BLOOM]);
+    }
+
+    #[test]
+    fn validation() {
+        let mut post = TaroPassBuilder::default();
+        post.read(HDR).write(TaroAttachment::TARGET);
+        let error = GraphPlan::build(&NAMES, vec![post], (64, 64)).unwrap_err();
+        let missing = TaroGraphError::MissingAttachment {
+            pass: "a",
+            attachment: HDR,
+        };
+        assert!(error == missing);
+
+        let mut first = TaroPassBuilder::default();
+        first.create(HDR, hdr_desc()).read(BLOOM);
+        let mut second = TaroPassBuilder::default();
+        second.create(BLOOM, hdr_desc()).read(HDR);
+        let error = GraphPlan::build(&NAMES, vec![first, second], (64, 64)).unwrap_err();
+        assert!(error == TaroGraphError::Cycle);
+
+        let mut depth = TaroPassBuilder::default();
+        depth.create(TaroAttachment::DEPTH, TaroTextureDesc::depth());
+        let error = GraphPlan::build(&NAMES, vec![depth], (64, 64)).unwrap_err();
+        assert!(matches!(error, TaroGraphError::BuiltinAttachment { .. }));
+    }
+}
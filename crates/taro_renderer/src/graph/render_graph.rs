@@ -0,0 +1,277 @@
+use indexmap::IndexMap;
+use log::error;
+use std::any::{Any, TypeId};
+
+use crate::{
+    passes::BlankRenderPass,
+    post::{TaroPostEffects, TaroPostProcessing},
+    HardwareId, TaroCameraRegion, TaroCameraView, TaroHardware, TaroRenderPearls,
+};
+
+use super::{
+    plan::{GraphPlan, GraphTexture},
+    TaroGraphError, TaroPassBuilder, TaroPassContext,
+};
+
+pub trait TaroRenderPass: 'static {
+    /// Declares the attachments that this pass reads, writes and creates
+    fn attachments(&self, builder: &mut TaroPassBuilder);
+
+    fn render(&mut self, context: &mut TaroPassContext);
+}
+
+/// A collection of passes that are ordered by the attachments they use.
+///
+/// Every frame the graph collects the attachments of each pass, sorts the passes
+/// so that attachments are written before they are read, and allocates the
+/// transient textures, sharing them between attachments whose lifetimes do not overlap.
+/// Passes that do not depend on each other keep the order they were added in.
+pub struct TaroRenderGraph {
+    passes: IndexMap<TypeId, GraphPass>,
+    textures: Option<GraphTextures>,
+}
+
+impl Default for TaroRenderGraph {
+    fn default() -> Self {
+        let mut new = Self {
+            passes: Default::default(),
+            textures: None,
+        };
+
+        new.insert(BlankRenderPass);
+        new.insert(TaroPostProcessing::default());
+
+        new
+    }
+}
+
+impl TaroRenderGraph {
+    pub fn len(&self) -> usize {
+        self.passes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.passes.is_empty()
+    }
+
+    /// Adds or replaces a pass in the graph.
+    ///
+    /// If the pass exists, it will be replaced. If it does not it will be appended.
+    pub fn insert<Pass>(&mut self, pass: Pass)
+    where
+        Pass: TaroRenderPass,
+    {
+        let passid = TypeId::of::<Pass>();
+        self.passes.insert(passid, GraphPass::new(pass));
+    }
+
+    /// Appends a pass to the graph
+    ///
+    /// If an instance of this pass already exists in this graph, it will be removed first.
+    pub fn append<Pass>(&mut self, pass: Pass)
+    where
+        Pass: TaroRenderPass,
+    {
+        let passid = TypeId::of::<Pass>();
+        self.passes.shift_remove(&passid);
+        self.passes.insert(passid, GraphPass::new(pass));
+    }
+
+    /// Prepends a pass to the graph
+    ///
+    /// If an instance of this pass already exists in this graph, it will be removed first.
+    pub fn prepend<Pass>(&mut self, pass: Pass)
+    where
+        Pass: TaroRenderPass,
+    {
+        let passid = TypeId::of::<Pass>();
+        self.passes.shift_remove(&passid);
+
+        let (index, _) = self.passes.insert_full(passid, GraphPass::new(pass));
+        if index > 0 {
+            self.passes.move_index(index, 0);
+        }
+    }
+
+    /// Removes a pass from the graph
+    pub fn remove<Pass>(&mut self)
+    where
+        Pass: TaroRenderPass,
+    {
+        let passid = TypeId::of::<Pass>();
+        self.passes.shift_remove(&passid);
+    }
+
+    pub fn get<Pass>(&self) -> Option<&Pass>
+    where
+        Pass: TaroRenderPass,
+    {
+        let pass = self.passes.get(&TypeId::of::<Pass>())?;
+        pass.pass.as_any().downcast_ref::<Pass>()
+    }
+
+    pub fn get_mut<Pass>(&mut self) -> Option<&mut Pass>
+    where
+        Pass: TaroRenderPass,
+    {
+        let pass = self.passes.get_mut(&TypeId::of::<Pass>())?;
+        pass.pass.as_any_mut().downcast_mut::<Pass>()
+    }
+
+    /// Gets the post processing effects of the graph.
+    ///
+    /// If the graph has no [`TaroPostProcessing`] pass, one will be appended.
+    pub fn post_effects(&mut self) -> &mut TaroPostEffects {
+        if self.get::<TaroPostProcessing>().is_none() {
+            self.append(TaroPostProcessing::default());
+        }
+
+        &mut self.get_mut::<TaroPostProcessing>().unwrap().effects
+    }
+
+    /// Gets the type names of the passes in the order they will be rendered
+    pub fn pass_order(&self) -> Result<Vec<&'static str>, TaroGraphError> {
+        let plan = self.plan((1, 1))?;
+        let names = self.passes.values().map(|p| p.name).collect::<Vec<_>>();
+        Ok(plan.order.iter().map(|&index| names[index]).collect())
+    }
+
+    /// Renders all the passes into `target` in dependency order
+    pub fn render(
+        &mut self,
+        pearls: &TaroRenderPearls,
+        camera: &TaroCameraView,
+        region: &TaroCameraRegion,
+        target: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+        hardware: &TaroHardware,
+    ) {
+        let plan = match self.plan(region.target_size) {
+            Ok(plan) => plan,
+            Err(e) => {
+                error!("Could not build render graph. Error: {e}");
+                return;
+            }
+        };
+
+        // reallocate the textures when the layout or hardware changes
+        if !matches!(&self.textures, Some(textures) if textures.matches(&plan, hardware)) {
+            self.textures = Some(GraphTextures::new(&plan, hardware));
+        }
+        let Some(textures) = &self.textures else {
+            return;
+        };
+
+        for &index in plan.order.iter() {
+            let Some((_, pass)) = self.passes.get_index_mut(index) else {
+                continue;
+            };
+
+            let mut context = TaroPassContext {
+                pearls,
+                camera,
+                region,
+                encoder: &mut *encoder,
+                hardware,
+                pass: index,
+                plan: &plan,
+                target,
+                views: &textures.views,
+            };
+
+            pass.pass.render(&mut context);
+        }
+    }
+
+    fn plan(&self, target_size: (u32, u32)) -> Result<GraphPlan, TaroGraphError> {
+        let names = self.passes.values().map(|p| p.name).collect::<Vec<_>>();
+        let builders = self
+            .passes
+            .values()
+            .map(|p| {
+                let mut builder = TaroPassBuilder::default();
+                p.pass.attachments(&mut builder);
+                builder
+            })
+            .collect();
+
+        GraphPlan::build(&names, builders, target_size)
+    }
+}
+
+struct GraphPass {
+    name: &'static str,
+    pass: Box<dyn DynamicRenderPass>,
+}
+
+impl GraphPass {
+    fn new<Pass: TaroRenderPass>(pass: Pass) -> Self {
+        Self {
+            name: std::any::type_name::<Pass>(),
+            pass: Box::new(pass),
+        }
+    }
+}
+
+/// The allocated transient textures of a graph
+struct GraphTextures {
+    hardware: HardwareId,
+    layout: Vec<GraphTexture>,
+    views: Vec<wgpu::TextureView>,
+}
+
+impl GraphTextures {
+    fn new(plan: &GraphPlan, hardware: &TaroHardware) -> Self {
+        let views = plan
+            .textures
+            .iter()
+            .map(|texture| {
+                let descriptor = wgpu::TextureDescriptor {
+                    label: Some("Taro Graph Texture"),
+                    size: wgpu::Extent3d {
+                        width: texture.size.0,
+                        height: texture.size.1,
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: texture.format,
+                    usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                        | wgpu::TextureUsages::TEXTURE_BINDING,
+                };
+
+                let texture = hardware.device().create_texture(&descriptor);
+                texture.create_view(&wgpu::TextureViewDescriptor::default())
+            })
+            .collect();
+
+        Self {
+            hardware: *hardware.id(),
+            layout: plan.textures.clone(),
+            views,
+        }
+    }
+
+    fn matches(&self, plan: &GraphPlan, hardware: &TaroHardware) -> bool {
+        self.hardware == *hardware.id() && self.layout == plan.textures
+    }
+}
+
+trait DynamicRenderPass: TaroRenderPass {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<Pass> DynamicRenderPass for Pass
+where
+    Pass: TaroRenderPass,
+{
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
@@ -5,6 +5,10 @@ pub struct TaroSurface {
     pub config: wgpu::SurfaceConfiguration,
 }
 
+/// Identifies one of the surfaces managed by a [`TaroSurfaceManager`](crate::stages::TaroSurfaceManager).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct TaroSurfaceId(pub usize);
+
 /// The Id for TaroHardware
 #[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
 pub struct HardwareId {
@@ -76,16 +80,6 @@ impl TaroHardware {
         ))
         .unwrap();
 
-        let config = wgpu::SurfaceConfiguration {
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-            format: surface.get_supported_formats(&adapter)[0],
-            width: size.0,
-            height: size.1,
-            present_mode: wgpu::PresentMode::AutoNoVsync,
-            alpha_mode: wgpu::CompositeAlphaMode::Auto,
-        };
-        surface.configure(&device, &config);
-
         let hardware = Self {
             id: HardwareId::new(),
             instance,
@@ -94,8 +88,35 @@ impl TaroHardware {
             queue,
         };
 
-        let surface = TaroSurface { surface, config };
+        let surface = hardware.configure_surface(surface, size);
 
         (hardware, surface)
     }
+
+    /// Creates an additional surface for `window` that shares this hardware.
+    ///
+    /// # Safety
+    ///
+    /// `window` must be a valid window and must outlive the returned surface.
+    pub unsafe fn create_surface<W>(&self, window: &W, size: (u32, u32)) -> TaroSurface
+    where
+        W: raw_window_handle::HasRawWindowHandle + raw_window_handle::HasRawDisplayHandle,
+    {
+        let surface = unsafe { self.instance.create_surface(&window) };
+        self.configure_surface(surface, size)
+    }
+
+    fn configure_surface(&self, surface: wgpu::Surface, size: (u32, u32)) -> TaroSurface {
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format: surface.get_supported_formats(&self.adapter)[0],
+            width: size.0,
+            height: size.1,
+            present_mode: wgpu::PresentMode::AutoNoVsync,
+            alpha_mode: wgpu::CompositeAlphaMode::Auto,
+        };
+        surface.configure(&self.device, &config);
+
+        TaroSurface { surface, config }
+    }
 }
@@ -1,15 +1,18 @@
 mod camera;
+mod graph;
 mod hardware;
-mod render_passes;
 mod render_pearls;
+mod viewport;
 
 pub use camera::*;
+pub use graph::*;
 pub use hardware::*;
-pub use render_passes::*;
 pub use render_pearls::*;
+pub use viewport::*;
 
 pub mod passes;
 pub mod pearls;
+pub mod post;
 pub mod shading;
 pub mod stages;
 
@@ -1,36 +1,34 @@
-use crate::{
-    shading::{buffers::CameraMatrix, data_types::DepthView, Taro},
-    TaroRenderPass,
-};
+use crate::{TaroAttachment, TaroPassBuilder, TaroPassContext, TaroRenderPass};
 
 pub struct BlankRenderPass;
 
 impl TaroRenderPass for BlankRenderPass {
-    fn render(
-        &mut self,
-        _pearls: &crate::TaroRenderPearls,
-        _camera_matrix: &CameraMatrix,
-        view: &wgpu::TextureView,
-        _depth: &Taro<DepthView>,
-        encoder: &mut wgpu::CommandEncoder,
-        _hardware: &crate::TaroHardware,
-    ) {
-        encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-            label: Some("White Render Pass"),
-            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                view,
-                resolve_target: None,
-                ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(wgpu::Color {
-                        r: 0.0,
-                        g: 0.0,
-                        b: 0.0,
-                        a: 1.0,
-                    }),
-                    store: true,
-                },
-            })],
-            depth_stencil_attachment: None,
-        });
+    fn attachments(&self, builder: &mut TaroPassBuilder) {
+        builder.write(TaroAttachment::COLOR);
+    }
+
+    fn render(&mut self, context: &mut TaroPassContext) {
+        let Some(view) = context.attachment(TaroAttachment::COLOR) else {
+            return;
+        };
+
+        // cameras drawn over earlier cameras clear to transparent so they stay visible
+        let color = match context.region.clear_target {
+            true => wgpu::Color::BLACK,
+            false => wgpu::Color::TRANSPARENT,
+        };
+        let load = context.color_load(TaroAttachment::COLOR, color);
+
+        context
+            .encoder
+            .begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("White Render Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations { load, store: true },
+                })],
+                depth_stencil_attachment: None,
+            });
     }
 }
@@ -0,0 +1,235 @@
+use boba_3d::{glam::Vec3, pearls::BobaTransform};
+use boba_core::{Pearl, PearlError};
+
+use crate::shading::buffers::{Light, ShadowCaster};
+
+/// How a light renders and filters its shadow maps
+#[derive(Debug, Clone, Copy)]
+pub struct TaroShadowSettings {
+    /// The depth offset subtracted from a surface before it is compared to the shadow map
+    pub depth_bias: f32,
+    /// The world space distance a surface is moved along its normal before sampling the shadow map
+    pub normal_bias: f32,
+    /// The radius in texels of the percentage closer filter.
+    ///
+    /// A radius of 0 uses a single hardware filtered sample.
+    pub pcf_radius: u32,
+}
+
+impl Default for TaroShadowSettings {
+    fn default() -> Self {
+        Self {
+            depth_bias: 0.0005,
+            normal_bias: 0.02,
+            pcf_radius: 1,
+        }
+    }
+}
+
+/// How the view of a camera is split into shadow maps for a directional light
+#[derive(Debug, Clone, Copy)]
+pub struct TaroCascades {
+    /// The amount of shadow maps, from 1 to 4
+    pub count: u32,
+    /// The distance from the camera that shadows reach
+    pub distance: f32,
+    /// Blends the cascade splits between uniform at 0 and logarithmic at 1
+    pub lambda: f32,
+}
+
+impl Default for TaroCascades {
+    fn default() -> Self {
+        Self {
+            count: 4,
+            distance: 50.,
+            lambda: 0.75,
+        }
+    }
+}
+
+/// A light infinitely far away that shines along the forward direction of its transform
+pub struct TaroDirectionalLight {
+    pub transform: Pearl<BobaTransform>,
+    pub color: [f32; 3],
+    pub intensity: f32,
+    pub shadows: Option<TaroShadowSettings>,
+    pub cascades: TaroCascades,
+}
+
+impl TaroDirectionalLight {
+    pub fn new(transform: Pearl<BobaTransform>) -> Self {
+        Self {
+            transform,
+            color: [1.; 3],
+            intensity: 1.,
+            shadows: None,
+            cascades: Default::default(),
+        }
+    }
+
+    pub fn new_simple(transform: BobaTransform) -> Self {
+        Self::new(Pearl::wrap(transform))
+    }
+
+    pub fn with_color(mut self, color: [f32; 3]) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_intensity(mut self, intensity: f32) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    pub fn with_shadows(mut self, shadows: TaroShadowSettings) -> Self {
+        self.shadows = Some(shadows);
+        self
+    }
+
+    pub fn with_cascades(mut self, cascades: TaroCascades) -> Self {
+        self.cascades = cascades;
+        self
+    }
+
+    /// Gets the direction the light travels in world space
+    pub fn direction(&self) -> Result<Vec3, PearlError> {
+        let transform = self.transform.borrow()?;
+        Ok(transform.world_rotation() * Vec3::Z)
+    }
+
+    pub(crate) fn light(&self) -> Result<(Light, Option<ShadowCaster>), PearlError> {
+        let light = Light::directional(self.direction()?, self.color, self.intensity);
+        Ok((light, shadow_caster(self.shadows, self.cascades)))
+    }
+}
+
+/// A light that shines in all directions from the position of its transform
+pub struct TaroPointLight {
+    pub transform: Pearl<BobaTransform>,
+    pub color: [f32; 3],
+    pub intensity: f32,
+    /// The distance at which the light has faded out completely
+    pub range: f32,
+    pub shadows: Option<TaroShadowSettings>,
+}
+
+impl TaroPointLight {
+    pub fn new(transform: Pearl<BobaTransform>) -> Self {
+        Self {
+            transform,
+            color: [1.; 3],
+            intensity: 10.,
+            range: 10.,
+            shadows: None,
+        }
+    }
+
+    pub fn new_simple(transform: BobaTransform) -> Self {
+        Self::new(Pearl::wrap(transform))
+    }
+
+    pub fn with_color(mut self, color: [f32; 3]) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_intensity(mut self, intensity: f32) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    pub fn with_range(mut self, range: f32) -> Self {
+        self.range = range;
+        self
+    }
+
+    pub fn with_shadows(mut self, shadows: TaroShadowSettings) -> Self {
+        self.shadows = Some(shadows);
+        self
+    }
+
+    pub(crate) fn light(&self) -> Result<(Light, Option<ShadowCaster>), PearlError> {
+        let transform = self.transform.borrow()?;
+        let position = transform.world_position();
+        let light = Light::point(position, self.range, self.color, self.intensity);
+        Ok((light, shadow_caster(self.shadows, Default::default())))
+    }
+}
+
+/// A light that shines in a cone along the forward direction of its transform
+pub struct TaroSpotLight {
+    pub transform: Pearl<BobaTransform>,
+    pub color: [f32; 3],
+    pub intensity: f32,
+    /// The distance at which the light has faded out completely
+    pub range: f32,
+    /// The angle in degrees from the center of the cone where the light starts to fade
+    pub inner_angle: f32,
+    /// The angle in degrees from the center of the cone where the light has faded out completely
+    pub outer_angle: f32,
+    pub shadows: Option<TaroShadowSettings>,
+}
+
+impl TaroSpotLight {
+    pub fn new(transform: Pearl<BobaTransform>) -> Self {
+        Self {
+            transform,
+            color: [1.; 3],
+            intensity: 10.,
+            range: 10.,
+            inner_angle: 20.,
+            outer_angle: 30.,
+            shadows: None,
+        }
+    }
+
+    pub fn new_simple(transform: BobaTransform) -> Self {
+        Self::new(Pearl::wrap(transform))
+    }
+
+    pub fn with_color(mut self, color: [f32; 3]) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_intensity(mut self, intensity: f32) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    pub fn with_range(mut self, range: f32) -> Self {
+        self.range = range;
+        self
+    }
+
+    pub fn with_shadows(mut self, shadows: TaroShadowSettings) -> Self {
+        self.shadows = Some(shadows);
+        self
+    }
+
+    pub fn with_angles(mut self, inner_angle: f32, outer_angle: f32) -> Self {
+        self.inner_angle = inner_angle;
+        self.outer_angle = outer_angle;
+        self
+    }
+
+    pub(crate) fn light(&self) -> Result<(Light, Option<ShadowCaster>), PearlError> {
+        let transform = self.transform.borrow()?;
+        let light = Light::spot(
+            transform.world_position(),
+            transform.world_rotation() * Vec3::Z,
+            self.range,
+            (self.inner_angle, self.outer_angle),
+            self.color,
+            self.intensity,
+        );
+        Ok((light, shadow_caster(self.shadows, Default::default())))
+    }
+}
+
+fn shadow_caster(
+    shadows: Option<TaroShadowSettings>,
+    cascades: TaroCascades,
+) -> Option<ShadowCaster> {
+    shadows.map(|settings| ShadowCaster { settings, cascades })
+}
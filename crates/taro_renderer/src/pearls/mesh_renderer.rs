@@ -1,14 +1,16 @@
-use boba_3d::pearls::BobaTransform;
-use boba_core::Pearl;
+use std::ops::Range;
+
+use boba_3d::{geometry::Aabb, pearls::BobaTransform};
+use boba_core::{Pearl, PearlError};
 use log::error;
 
 use crate::{
     shading::{
-        buffers::{CameraMatrix, TransformMatrix},
-        data_types::Mesh,
+        buffers::TransformMatrix,
+        data_types::{Mesh, MeshBuffer},
         Shader, Taro, TaroCoreShader, TaroMeshShader,
     },
-    TaroHardware,
+    TaroCameraView, TaroHardware,
 };
 
 pub struct TaroMeshRenderer<T>
@@ -20,6 +22,8 @@ where
     pub mesh: Taro<Mesh>,
     pub shader: Taro<Shader<T>>,
     pub transform: Pearl<BobaTransform>,
+    /// The index of the submesh to draw, or `None` to draw the whole mesh
+    pub submesh: Option<usize>,
 }
 
 impl<T> TaroMeshRenderer<T>
@@ -32,6 +36,7 @@ where
             mesh,
             shader,
             transform,
+            submesh: None,
         }
     }
 
@@ -39,10 +44,38 @@ where
         Self::new(Pearl::wrap(transform), mesh, shader)
     }
 
+    /// Only draws the submesh at `index` of the mesh
+    pub fn with_submesh(mut self, index: usize) -> Self {
+        self.submesh = Some(index);
+        self
+    }
+
+    /// Gets the range of the index buffer of `mesh` that this renderer draws.
+    ///
+    /// The range is clamped to the indices in the buffer,
+    /// and is empty if the submesh does not exist.
+    pub fn indices(&self, mesh: &MeshBuffer) -> Range<u32> {
+        let len = mesh.index_buffer().len();
+        let Some(index) = self.submesh else {
+            return 0..len;
+        };
+
+        match self.mesh.submeshes().get(index) {
+            Some(submesh) => submesh.indices.start.min(len)..submesh.indices.end.min(len),
+            None => 0..0,
+        }
+    }
+
+    /// Gets the bounds of the mesh in world space, using the current transform.
+    pub fn world_bounds(&self) -> Result<Aabb, PearlError> {
+        let transform = self.transform.borrow()?;
+        Ok(self.mesh.bounds().transformed(&transform.world_matrix()))
+    }
+
     pub fn render<'pass>(
         &'pass mut self,
         pass: &mut wgpu::RenderPass<'pass>,
-        camera_matrix: &CameraMatrix,
+        camera: &'pass TaroCameraView,
         hardware: &TaroHardware,
     ) {
         match self.transform.borrow() {
@@ -54,8 +87,8 @@ where
 
         let uploaded_mesh = self.mesh.get_or_compile(hardware);
         let shader = self.shader.get_or_compile(hardware);
-        shader.set_camera_matrix(camera_matrix, hardware);
         shader.set_model_matrix(&self.model_matrix, hardware);
-        shader.render(pass, uploaded_mesh, hardware);
+        let indices = self.indices(uploaded_mesh);
+        shader.render(pass, uploaded_mesh, indices, camera, hardware);
     }
 }
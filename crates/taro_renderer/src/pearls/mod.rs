@@ -1,3 +1,5 @@
+mod lights;
 mod mesh_renderer;
 
+pub use lights::*;
 pub use mesh_renderer::*;
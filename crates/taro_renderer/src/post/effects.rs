@@ -0,0 +1,174 @@
+use indexmap::IndexMap;
+use std::any::{Any, TypeId};
+
+use crate::{TaroAttachment, TaroCameraRegion, TaroHardware};
+
+use super::{TaroFullscreenPass, TaroFullscreenTarget};
+
+/// Everything an effect needs to render as part of [`TaroPostProcessing`](super::TaroPostProcessing)
+pub struct TaroPostContext<'a> {
+    /// The output of the previous effect, or the scene color if this is the first effect
+    pub source: &'a wgpu::TextureView,
+    /// The texture to write the result into.
+    ///
+    /// It has the same size as the source, and uses [`TaroAttachment::COLOR_FORMAT`].
+    pub destination: &'a wgpu::TextureView,
+    pub region: &'a TaroCameraRegion,
+    pub encoder: &'a mut wgpu::CommandEncoder,
+    pub hardware: &'a TaroHardware,
+}
+
+impl<'a> TaroPostContext<'a> {
+    /// Draws `pass` from the source into the destination, clipped to the camera region
+    pub fn draw(&mut self, pass: &mut TaroFullscreenPass, params: &[u8]) {
+        let target = TaroFullscreenTarget {
+            view: self.destination,
+            format: TaroAttachment::COLOR_FORMAT,
+            load: wgpu::LoadOp::Load,
+            scissor: Some(self.region.scissor),
+        };
+
+        pass.draw(self.encoder, self.hardware, &[self.source], target, params);
+    }
+}
+
+/// A full screen effect that is applied to the scene color
+pub trait TaroPostEffect: 'static {
+    fn render(&mut self, context: &mut TaroPostContext);
+}
+
+/// An ordered collection of post processing effects
+#[derive(Default)]
+pub struct TaroPostEffects {
+    effects: IndexMap<TypeId, Box<dyn DynamicPostEffect>>,
+}
+
+impl TaroPostEffects {
+    pub fn len(&self) -> usize {
+        self.effects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+
+    /// Adds or replaces an effect in the collection.
+    ///
+    /// If the effect exists, it will be replaced. If it does not it will be appended.
+    pub fn insert<Effect>(&mut self, effect: Effect)
+    where
+        Effect: TaroPostEffect,
+    {
+        let effectid = TypeId::of::<Effect>();
+        self.effects.insert(effectid, Box::new(effect));
+    }
+
+    /// Appends an effect to the collection
+    ///
+    /// If an instance of this effect already exists in this collection, it will be removed first.
+    pub fn append<Effect>(&mut self, effect: Effect)
+    where
+        Effect: TaroPostEffect,
+    {
+        let effectid = TypeId::of::<Effect>();
+        self.effects.shift_remove(&effectid);
+        self.effects.insert(effectid, Box::new(effect));
+    }
+
+    /// Prepends an effect to the collection
+    ///
+    /// If an instance of this effect already exists in this collection, it will be removed first.
+    pub fn prepend<Effect>(&mut self, effect: Effect)
+    where
+        Effect: TaroPostEffect,
+    {
+        let effectid = TypeId::of::<Effect>();
+        self.effects.shift_remove(&effectid);
+
+        let (index, _) = self.effects.insert_full(effectid, Box::new(effect));
+        if index > 0 {
+            self.effects.move_index(index, 0);
+        }
+    }
+
+    /// Removes an effect from the collection
+    pub fn remove<Effect>(&mut self)
+    where
+        Effect: TaroPostEffect,
+    {
+        let effectid = TypeId::of::<Effect>();
+        self.effects.shift_remove(&effectid);
+    }
+
+    pub fn get<Effect>(&self) -> Option<&Effect>
+    where
+        Effect: TaroPostEffect,
+    {
+        let effect = self.effects.get(&TypeId::of::<Effect>())?;
+        effect.as_any().downcast_ref::<Effect>()
+    }
+
+    pub fn get_mut<Effect>(&mut self) -> Option<&mut Effect>
+    where
+        Effect: TaroPostEffect,
+    {
+        let effect = self.effects.get_mut(&TypeId::of::<Effect>())?;
+        effect.as_any_mut().downcast_mut::<Effect>()
+    }
+
+    pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = &mut Box<dyn DynamicPostEffect>> {
+        self.effects.values_mut()
+    }
+}
+
+pub(crate) trait DynamicPostEffect: TaroPostEffect {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<Effect> DynamicPostEffect for Effect
+where
+    Effect: TaroPostEffect,
+{
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct First;
+    struct Second(u32);
+
+    impl TaroPostEffect for First {
+        fn render(&mut self, _: &mut TaroPostContext) {}
+    }
+
+    impl TaroPostEffect for Second {
+        fn render(&mut self, _: &mut TaroPostContext) {}
+    }
+
+    #[test]
+    fn effect_order() {
+        let mut effects = TaroPostEffects::default();
+        effects.append(First);
+        effects.append(Second(1));
+        effects.prepend(Second(2));
+        assert!(effects.len() == 2);
+        assert!(effects.effects.get_index(0).unwrap().0 == &TypeId::of::<Second>());
+
+        effects.insert(Second(3));
+        assert!(effects.get::<Second>().unwrap().0 == 3);
+        assert!(effects.effects.get_index(0).unwrap().0 == &TypeId::of::<Second>());
+
+        effects.remove::<Second>();
+        assert!(effects.get::<Second>().is_none());
+        assert!(effects.get_mut::<First>().is_some());
+    }
+}
@@ -0,0 +1,244 @@
+use wgpu::util::DeviceExt;
+
+use crate::{HardwareId, TaroHardware, TaroPixelRect};
+
+/// The shared prelude for every fullscreen shader.
+///
+/// It declares the vertex entry point `vs_main` that outputs a `FullscreenOutput`,
+/// the linear `source_sampler` at binding 0 and the first `source` texture at binding 2.
+/// Shaders declare their uniform parameters at binding 1 and any extra textures from binding 3.
+pub const FULLSCREEN_PRELUDE: &str = include_str!("fullscreen.wgsl");
+
+/// Where a [`TaroFullscreenPass`] draws into
+pub struct TaroFullscreenTarget<'a> {
+    pub view: &'a wgpu::TextureView,
+    pub format: wgpu::TextureFormat,
+    pub load: wgpu::LoadOp<wgpu::Color>,
+    pub scissor: Option<TaroPixelRect>,
+}
+
+/// A pipeline that draws a single triangle covering the whole target.
+///
+/// The fragment shader is appended to the [`FULLSCREEN_PRELUDE`] and must have the entry point `fs_main`.
+pub struct TaroFullscreenPass {
+    label: &'static str,
+    shader: &'static str,
+    textures: u32,
+    blend: Option<wgpu::BlendState>,
+    compiled: Option<CompiledFullscreenPass>,
+}
+
+struct CompiledFullscreenPass {
+    hardware: HardwareId,
+    format: wgpu::TextureFormat,
+    params_size: u64,
+    layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::RenderPipeline,
+    sampler: wgpu::Sampler,
+    params: wgpu::Buffer,
+}
+
+impl TaroFullscreenPass {
+    pub fn new(label: &'static str, shader: &'static str) -> Self {
+        Self {
+            label,
+            shader,
+            textures: 1,
+            blend: None,
+            compiled: None,
+        }
+    }
+
+    /// Sets the amount of source textures the shader samples
+    pub fn with_textures(mut self, textures: u32) -> Self {
+        self.textures = textures.max(1);
+        self
+    }
+
+    pub fn with_blend(mut self, blend: wgpu::BlendState) -> Self {
+        self.blend = Some(blend);
+        self
+    }
+
+    /// Draws the shader into `target`, sampling from `sources`.
+    ///
+    /// `params` are uploaded to the uniform buffer at binding 1.
+    pub fn draw(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        hardware: &TaroHardware,
+        sources: &[&wgpu::TextureView],
+        target: TaroFullscreenTarget,
+        params: &[u8],
+    ) {
+        // uniform buffers must be at least 16 bytes and a multiple of 16
+        let params_size = (params.len().max(1) as u64).div_ceil(16) * 16;
+        let compiled = match self.compiled.take() {
+            Some(compiled)
+                if compiled.hardware == *hardware.id()
+                    && compiled.format == target.format
+                    && compiled.params_size == params_size =>
+            {
+                compiled
+            }
+            _ => self.compile(hardware, target.format, params_size),
+        };
+
+        let mut bytes = params.to_vec();
+        bytes.resize(params_size as usize, 0);
+        hardware.queue().write_buffer(&compiled.params, 0, &bytes);
+
+        let mut entries = vec![
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::Sampler(&compiled.sampler),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: compiled.params.as_entire_binding(),
+            },
+        ];
+        for (index, source) in sources.iter().take(self.textures as usize).enumerate() {
+            entries.push(wgpu::BindGroupEntry {
+                binding: 2 + index as u32,
+                resource: wgpu::BindingResource::TextureView(source),
+            });
+        }
+
+        let bind_group = hardware
+            .device()
+            .create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some(self.label),
+                layout: &compiled.layout,
+                entries: &entries,
+            });
+
+        {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some(self.label),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: target.view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: target.load,
+                        store: true,
+                    },
+                })],
+                depth_stencil_attachment: None,
+            });
+
+            if let Some(rect) = target.scissor {
+                pass.set_scissor_rect(rect.x, rect.y, rect.width, rect.height);
+            }
+
+            pass.set_pipeline(&compiled.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.draw(0..3, 0..1);
+        }
+
+        self.compiled = Some(compiled);
+    }
+
+    fn compile(
+        &self,
+        hardware: &TaroHardware,
+        format: wgpu::TextureFormat,
+        params_size: u64,
+    ) -> CompiledFullscreenPass {
+        let device = hardware.device();
+
+        let mut entries = vec![
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+        ];
+        for index in 0..self.textures {
+            entries.push(wgpu::BindGroupLayoutEntry {
+                binding: 2 + index,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    multisampled: false,
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                },
+                count: None,
+            });
+        }
+
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some(self.label),
+            entries: &entries,
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some(self.label),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+
+        let source = format!("{FULLSCREEN_PRELUDE}\n{}", self.shader);
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some(self.label),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(self.label),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &module,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &module,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: self.blend,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some(self.label),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let params = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some(self.label),
+            contents: &vec![0; params_size as usize],
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        CompiledFullscreenPass {
+            hardware: *hardware.id(),
+            format,
+            params_size,
+            layout,
+            pipeline,
+            sampler,
+            params,
+        }
+    }
+}
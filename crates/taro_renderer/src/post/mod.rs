@@ -0,0 +1,7 @@
+mod effects;
+mod fullscreen;
+mod processing;
+
+pub use effects::*;
+pub use fullscreen::*;
+pub use processing::*;
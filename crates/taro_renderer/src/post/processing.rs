@@ -0,0 +1,82 @@
+use crate::{TaroAttachment, TaroPassBuilder, TaroPassContext, TaroRenderPass, TaroTextureDesc};
+
+use super::{TaroFullscreenPass, TaroFullscreenTarget, TaroPostContext, TaroPostEffects};
+
+const PING: TaroAttachment = TaroAttachment("post_ping");
+const PONG: TaroAttachment = TaroAttachment("post_pong");
+
+/// Runs the post processing effects on the scene color and resolves the result into the target.
+///
+/// Effects are applied in the order of the collection,
+/// each one reading the output of the effect before it.
+/// The resolved color is alpha blended over the target, so cameras drawn after
+/// the first one only cover the parts of the target that their scene touched.
+pub struct TaroPostProcessing {
+    pub effects: TaroPostEffects,
+    resolve: TaroFullscreenPass,
+}
+
+impl Default for TaroPostProcessing {
+    fn default() -> Self {
+        Self {
+            effects: Default::default(),
+            resolve: TaroFullscreenPass::new("Taro Post Resolve", include_str!("resolve.wgsl"))
+                .with_blend(wgpu::BlendState::ALPHA_BLENDING),
+        }
+    }
+}
+
+impl TaroRenderPass for TaroPostProcessing {
+    fn attachments(&self, builder: &mut TaroPassBuilder) {
+        builder
+            .read(TaroAttachment::COLOR)
+            .write(TaroAttachment::TARGET);
+
+        if !self.effects.is_empty() {
+            builder.create(PING, TaroTextureDesc::color());
+        }
+        if self.effects.len() > 1 {
+            builder.create(PONG, TaroTextureDesc::color());
+        }
+    }
+
+    fn render(&mut self, context: &mut TaroPassContext) {
+        let (Some(color), Some(target)) = (
+            context.attachment(TaroAttachment::COLOR),
+            context.attachment(TaroAttachment::TARGET),
+        ) else {
+            return;
+        };
+
+        // ping pong between the transient textures for each effect
+        let mut source = color;
+        let buffers = [context.attachment(PING), context.attachment(PONG)];
+        for (index, effect) in self.effects.iter_mut().enumerate() {
+            let Some(destination) = buffers[index % 2] else {
+                continue;
+            };
+
+            let mut post = TaroPostContext {
+                source,
+                destination,
+                region: context.region,
+                encoder: context.encoder,
+                hardware: context.hardware,
+            };
+
+            effect.render(&mut post);
+            source = destination;
+        }
+
+        let load = context.color_load(TaroAttachment::TARGET, wgpu::Color::BLACK);
+        let target = TaroFullscreenTarget {
+            view: target,
+            format: context.region.target_format,
+            load,
+            scissor: Some(context.region.scissor),
+        };
+
+        self.resolve
+            .draw(context.encoder, context.hardware, &[source], target, &[]);
+    }
+}
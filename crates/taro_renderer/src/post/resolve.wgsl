@@ -0,0 +1,4 @@
+@fragment
+fn fs_main(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    return textureSample(source, source_sampler, in.uv);
+}
@@ -54,6 +54,11 @@ impl<T: TaroBuilder> Taro<T> {
             .get_or_init(hardware.id().clone(), || self.inner.compile(hardware))
             .into_data()
     }
+
+    /// Returns true if both objects point to the same data
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
 }
 
 /// Base trait for items to be built into a binding.
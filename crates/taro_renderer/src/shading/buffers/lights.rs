@@ -0,0 +1,253 @@
+use boba_3d::glam::Vec3;
+use log::error;
+
+use crate::{
+    pearls::{TaroDirectionalLight, TaroPointLight, TaroSpotLight},
+    TaroCameraView, TaroRenderPearls,
+};
+
+use super::{ShadowArray, ShadowCaster, TaroBytesBuilder};
+
+/// The shader declarations for [`LightArray`].
+///
+/// It declares the `TaroLights` and `TaroShadows` structs that match [`LightArray`] and [`ShadowArray`],
+/// `taro_sample_light` which calculates the light arriving at a position,
+/// and `taro_sample_shadow` which filters a single shadow map.
+/// Lit shaders prepend this to their source and declare the bindings themselves.
+pub const LIGHTS_PRELUDE: &str = include_str!("lights.wgsl");
+
+/// A single light as it is laid out on the gpu
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct Light {
+    position: [f32; 3],
+    kind: u32,
+    direction: [f32; 3],
+    range: f32,
+    color: [f32; 3],
+    inner_cos: f32,
+    outer_cos: f32,
+    shadow_index: i32,
+    shadow_count: u32,
+    _padding: f32,
+}
+
+impl Default for Light {
+    fn default() -> Self {
+        Self {
+            shadow_index: -1,
+            ..bytemuck::Zeroable::zeroed()
+        }
+    }
+}
+
+impl Light {
+    pub(crate) const DIRECTIONAL: u32 = 0;
+    pub(crate) const POINT: u32 = 1;
+    pub(crate) const SPOT: u32 = 2;
+
+    pub fn directional(direction: Vec3, color: [f32; 3], intensity: f32) -> Self {
+        Self {
+            kind: Self::DIRECTIONAL,
+            direction: direction.normalize_or_zero().to_array(),
+            color: (Vec3::from(color) * intensity).to_array(),
+            ..Default::default()
+        }
+    }
+
+    pub fn point(position: Vec3, range: f32, color: [f32; 3], intensity: f32) -> Self {
+        Self {
+            kind: Self::POINT,
+            position: position.to_array(),
+            range,
+            color: (Vec3::from(color) * intensity).to_array(),
+            ..Default::default()
+        }
+    }
+
+    /// Creates a spot light, where `angles` are the inner and outer cone angles in degrees
+    pub fn spot(
+        position: Vec3,
+        direction: Vec3,
+        range: f32,
+        angles: (f32, f32),
+        color: [f32; 3],
+        intensity: f32,
+    ) -> Self {
+        let outer = angles.1.clamp(0., 90.);
+        let inner = angles.0.clamp(0., outer);
+        Self {
+            kind: Self::SPOT,
+            position: position.to_array(),
+            direction: direction.normalize_or_zero().to_array(),
+            range,
+            color: (Vec3::from(color) * intensity).to_array(),
+            inner_cos: inner.to_radians().cos(),
+            outer_cos: outer.to_radians().cos(),
+            ..Default::default()
+        }
+    }
+
+    pub fn is_directional(&self) -> bool {
+        self.kind == Self::DIRECTIONAL
+    }
+
+    pub fn position(&self) -> Vec3 {
+        Vec3::from(self.position)
+    }
+
+    pub fn direction(&self) -> Vec3 {
+        Vec3::from(self.direction)
+    }
+
+    pub fn range(&self) -> f32 {
+        self.range
+    }
+
+    /// The index of the first shadow map of this light, or -1 if it has no shadows
+    pub fn shadow_index(&self) -> i32 {
+        self.shadow_index
+    }
+
+    pub(crate) fn kind(&self) -> u32 {
+        self.kind
+    }
+
+    pub(crate) fn outer_cos(&self) -> f32 {
+        self.outer_cos
+    }
+
+    pub(crate) fn set_shadows(&mut self, index: i32, count: u32) {
+        self.shadow_index = index;
+        self.shadow_count = count;
+    }
+}
+
+/// The lights of a scene, gathered into a uniform buffer for lit shaders
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct LightArray {
+    count: u32,
+    _padding: [u32; 3],
+    lights: [Light; LightArray::MAX_LIGHTS],
+}
+
+impl Default for LightArray {
+    fn default() -> Self {
+        bytemuck::Zeroable::zeroed()
+    }
+}
+
+impl TaroBytesBuilder for LightArray {
+    fn build_bytes(&self) -> &[u8] {
+        bytemuck::bytes_of(self)
+    }
+}
+
+impl LightArray {
+    /// The most lights that fit into the buffer, matching the array size in [`LIGHTS_PRELUDE`]
+    pub const MAX_LIGHTS: usize = 64;
+
+    pub fn len(&self) -> usize {
+        self.count as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn lights(&self) -> &[Light] {
+        &self.lights[..self.len()]
+    }
+
+    /// Replaces the contents with the lights in `pearls`, and their shadow maps with `shadows`.
+    ///
+    /// When there are more than `limit` lights, directional lights are kept first,
+    /// followed by the lights closest to the camera.
+    /// The limit is clamped to [`LightArray::MAX_LIGHTS`].
+    /// Lights receive shadow maps in the same order until the shadow array is full.
+    pub fn gather(
+        &mut self,
+        pearls: &TaroRenderPearls,
+        camera: &TaroCameraView,
+        limit: usize,
+        shadows: &mut ShadowArray,
+    ) {
+        let mut lights = Vec::new();
+        for light in pearls.collect::<TaroDirectionalLight>().iter() {
+            lights.extend(log_error(light.light()));
+        }
+        for light in pearls.collect::<TaroPointLight>().iter() {
+            lights.extend(log_error(light.light()));
+        }
+        for light in pearls.collect::<TaroSpotLight>().iter() {
+            lights.extend(log_error(light.light()));
+        }
+
+        let priority = |light: &Light| match light.is_directional() {
+            true => -1.,
+            false => light.position().distance_squared(camera.position),
+        };
+        lights.sort_by(|(a, _), (b, _)| priority(a).total_cmp(&priority(b)));
+        lights.truncate(limit.min(Self::MAX_LIGHTS));
+
+        shadows.clear();
+        for (light, caster) in lights.iter_mut() {
+            if let Some(caster) = caster {
+                shadows.push(light, caster, camera);
+            }
+        }
+
+        self.count = lights.len() as u32;
+        for (index, (light, _)) in lights.into_iter().enumerate() {
+            self.lights[index] = light;
+        }
+    }
+}
+
+type GatheredLight = (Light, Option<ShadowCaster>);
+
+fn log_error(light: Result<GatheredLight, boba_core::PearlError>) -> Option<GatheredLight> {
+    match light {
+        Ok(light) => Some(light),
+        Err(e) => {
+            error!("Could not gather light. Error: {e}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use boba_3d::pearls::BobaTransform;
+    use boba_core::Pearl;
+
+    use super::*;
+
+    #[test]
+    fn gather_limit() {
+        let mut pearls = TaroRenderPearls::default();
+        for x in [8., 2., 5.] {
+            let transform = BobaTransform::from_position(Vec3::new(x, 0., 0.));
+            pearls.add(Pearl::wrap(TaroPointLight::new_simple(transform)));
+        }
+        let sun = TaroDirectionalLight::new_simple(BobaTransform::default());
+        pearls.add(Pearl::wrap(sun));
+
+        let mut shadows = ShadowArray::default();
+        let mut array = LightArray::default();
+        let camera = TaroCameraView::default();
+        array.gather(&pearls, &camera, 2, &mut shadows);
+        assert!(array.len() == 2);
+        assert!(array.lights()[0].is_directional());
+        assert!(array.lights()[1].position() == Vec3::new(2., 0., 0.));
+
+        let camera = TaroCameraView {
+            position: Vec3::new(10., 0., 0.),
+            ..camera
+        };
+        array.gather(&pearls, &camera, 100, &mut shadows);
+        assert!(array.len() == 4);
+        assert!(array.lights()[1].position() == Vec3::new(8., 0., 0.));
+    }
+}
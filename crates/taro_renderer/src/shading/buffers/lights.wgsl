@@ -0,0 +1,122 @@
+let TARO_DIRECTIONAL_LIGHT: u32 = 0u;
+let TARO_POINT_LIGHT: u32 = 1u;
+let TARO_SPOT_LIGHT: u32 = 2u;
+
+struct TaroLight {
+    position: vec3<f32>,
+    kind: u32,
+    direction: vec3<f32>,
+    range: f32,
+    color: vec3<f32>,
+    inner_cos: f32,
+    outer_cos: f32,
+    // the index of the first shadow map, or -1 without shadows
+    shadow_index: i32,
+    shadow_count: u32,
+    _padding: f32,
+}
+
+struct TaroLights {
+    count: u32,
+    _padding0: u32,
+    _padding1: u32,
+    _padding2: u32,
+    lights: array<TaroLight, 64>,
+}
+
+struct TaroLightSample {
+    // the direction from the surface towards the light
+    direction: vec3<f32>,
+    radiance: vec3<f32>,
+}
+
+// calculates the light arriving at a surface at `position`
+fn taro_sample_light(light: TaroLight, position: vec3<f32>) -> TaroLightSample {
+    var sample: TaroLightSample;
+    if light.kind == TARO_DIRECTIONAL_LIGHT {
+        sample.direction = -light.direction;
+        sample.radiance = light.color;
+        return sample;
+    }
+
+    let offset = light.position - position;
+    let distance_squared = max(dot(offset, offset), 0.0001);
+    sample.direction = offset * inverseSqrt(distance_squared);
+
+    // inverse square falloff, windowed to reach zero at the range of the light
+    let ratio = distance_squared / max(light.range * light.range, 0.0001);
+    let window = clamp(1.0 - ratio * ratio, 0.0, 1.0);
+    var attenuation = window * window / distance_squared;
+
+    if light.kind == TARO_SPOT_LIGHT {
+        let cos_angle = dot(-sample.direction, light.direction);
+        attenuation *= smoothstep(light.outer_cos, light.inner_cos, cos_angle);
+    }
+
+    sample.radiance = light.color * attenuation;
+    return sample;
+}
+
+struct TaroShadowMap {
+    view_projection: mat4x4<f32>,
+    depth_bias: f32,
+    normal_bias: f32,
+    pcf_radius: f32,
+    split: f32,
+}
+
+struct TaroShadows {
+    count: u32,
+    resolution: u32,
+    _padding0: u32,
+    _padding1: u32,
+    maps: array<TaroShadowMap, 32>,
+}
+
+// gets the point light shadow map for `direction`, in the order +x, -x, +y, -y, +z, -z
+fn taro_cube_face(direction: vec3<f32>) -> i32 {
+    let a = abs(direction);
+    if a.x >= a.y && a.x >= a.z {
+        return select(1, 0, direction.x > 0.0);
+    }
+    if a.y >= a.z {
+        return select(3, 2, direction.y > 0.0);
+    }
+    return select(5, 4, direction.z > 0.0);
+}
+
+// calculates how much of a surface is lit, filtering the shadow map with a square kernel
+fn taro_sample_shadow(
+    map: TaroShadowMap,
+    layer: i32,
+    resolution: u32,
+    shadow_maps: texture_depth_2d_array,
+    shadow_sampler: sampler_comparison,
+    position: vec3<f32>,
+    normal: vec3<f32>,
+) -> f32 {
+    let clip = map.view_projection * vec4<f32>(position + normal * map.normal_bias, 1.0);
+    if clip.w <= 0.0 {
+        return 1.0;
+    }
+
+    let ndc = clip.xyz / clip.w;
+    let uv = vec2<f32>(ndc.x * 0.5 + 0.5, 0.5 - ndc.y * 0.5);
+    if any(uv < vec2<f32>(0.0)) || any(uv > vec2<f32>(1.0)) || ndc.z > 1.0 {
+        return 1.0;
+    }
+
+    let depth = ndc.z - map.depth_bias;
+    let texel = 1.0 / f32(max(resolution, 1u));
+    let radius = i32(map.pcf_radius);
+    var lit = 0.0;
+    for (var y = -radius; y <= radius; y++) {
+        for (var x = -radius; x <= radius; x++) {
+            let offset = vec2<f32>(f32(x), f32(y)) * texel;
+            lit += textureSampleCompareLevel(shadow_maps, shadow_sampler, uv + offset, layer, depth);
+        }
+    }
+
+    let width = f32(2 * radius + 1);
+    return lit / (width * width);
+}
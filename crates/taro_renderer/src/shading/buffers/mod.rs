@@ -1,7 +1,11 @@
 mod buffer;
 mod color;
+mod lights;
 mod matrix;
+mod shadows;
 
 pub use buffer::*;
 pub use color::*;
+pub use lights::*;
 pub use matrix::*;
+pub use shadows::*;
@@ -0,0 +1,317 @@
+use std::f32::consts::FRAC_PI_2;
+
+use boba_3d::glam::{Mat4, Vec3, Vec4Swizzles};
+
+use crate::{
+    pearls::{TaroCascades, TaroShadowSettings},
+    TaroCameraView,
+};
+
+use super::{Light, TaroBytesBuilder};
+
+/// The near plane used for spot and point light shadow maps
+const SHADOW_NEAR: f32 = 0.05;
+
+/// The directions of each point light shadow map, in the order the shaders expect
+const CUBE_FACES: [Vec3; 6] = [
+    Vec3::X,
+    Vec3::NEG_X,
+    Vec3::Y,
+    Vec3::NEG_Y,
+    Vec3::Z,
+    Vec3::NEG_Z,
+];
+
+/// The shadow settings of a light while it is being gathered
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ShadowCaster {
+    pub settings: TaroShadowSettings,
+    pub cascades: TaroCascades,
+}
+
+/// A single shadow map as it is laid out on the gpu
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct ShadowMap {
+    view_projection: [[f32; 4]; 4],
+    depth_bias: f32,
+    normal_bias: f32,
+    pcf_radius: f32,
+    /// The view depth where a directional cascade ends
+    split: f32,
+}
+
+impl ShadowMap {
+    fn new(matrix: Mat4, settings: &TaroShadowSettings, split: f32) -> Self {
+        Self {
+            view_projection: matrix.to_cols_array_2d(),
+            depth_bias: settings.depth_bias,
+            normal_bias: settings.normal_bias,
+            pcf_radius: settings.pcf_radius as f32,
+            split,
+        }
+    }
+
+    /// The matrix that transforms world space into the clip space of the shadow map
+    pub fn matrix(&self) -> Mat4 {
+        Mat4::from_cols_array_2d(&self.view_projection)
+    }
+
+    pub fn split(&self) -> f32 {
+        self.split
+    }
+}
+
+/// The shadow maps of a scene, gathered into a uniform buffer for lit shaders.
+///
+/// Every map is a layer in a single depth texture array of `resolution` squared texels.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct ShadowArray {
+    count: u32,
+    resolution: u32,
+    _padding: [u32; 2],
+    maps: [ShadowMap; ShadowArray::MAX_SHADOW_MAPS],
+}
+
+impl Default for ShadowArray {
+    fn default() -> Self {
+        Self::new(1024)
+    }
+}
+
+impl TaroBytesBuilder for ShadowArray {
+    fn build_bytes(&self) -> &[u8] {
+        bytemuck::bytes_of(self)
+    }
+}
+
+impl ShadowArray {
+    /// The most shadow maps that fit into the buffer, matching the array size in the lights prelude
+    pub const MAX_SHADOW_MAPS: usize = 32;
+
+    pub fn new(resolution: u32) -> Self {
+        let mut array: Self = bytemuck::Zeroable::zeroed();
+        array.resolution = resolution.max(1);
+        array
+    }
+
+    pub fn resolution(&self) -> u32 {
+        self.resolution
+    }
+
+    pub fn set_resolution(&mut self, resolution: u32) {
+        self.resolution = resolution.max(1);
+    }
+
+    pub fn len(&self) -> usize {
+        self.count as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn maps(&self) -> &[ShadowMap] {
+        &self.maps[..self.len()]
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.count = 0;
+    }
+
+    /// Adds the shadow maps for `light` and links them to it.
+    ///
+    /// If there is not enough space left for every map the light needs, it is left without shadows.
+    pub(crate) fn push(
+        &mut self,
+        light: &mut Light,
+        caster: &ShadowCaster,
+        camera: &TaroCameraView,
+    ) {
+        let maps = match light.kind() {
+            Light::DIRECTIONAL => self.cascade_maps(light, caster, camera),
+            Light::SPOT => vec![spot_map(light, &caster.settings)],
+            _ => point_maps(light, &caster.settings),
+        };
+
+        let start = self.len();
+        if maps.is_empty() || start + maps.len() > Self::MAX_SHADOW_MAPS {
+            return;
+        }
+
+        self.maps[start..start + maps.len()].copy_from_slice(&maps);
+        self.count += maps.len() as u32;
+        light.set_shadows(start as i32, maps.len() as u32);
+    }
+
+    /// Splits the camera frustum into cascades, and fits an orthographic shadow map around each one
+    fn cascade_maps(
+        &self,
+        light: &Light,
+        caster: &ShadowCaster,
+        camera: &TaroCameraView,
+    ) -> Vec<ShadowMap> {
+        let direction = light.direction();
+        let inverse_view = camera.view_matrix.inverse();
+        let inverse_projection = camera.projection_matrix.inverse();
+
+        // the view space corners of the near and far plane, paired along each frustum edge
+        let corners = [(-1., -1.), (1., -1.), (-1., 1.), (1., 1.)].map(|(x, y)| {
+            let near = inverse_projection.project_point3(Vec3::new(x, y, 0.));
+            let far = inverse_projection.project_point3(Vec3::new(x, y, 1.));
+            (near, far)
+        });
+
+        let near = -corners[0].0.z;
+        let far = -corners[0].1.z;
+        if !far.is_finite() || far <= near {
+            return Vec::new();
+        }
+
+        let cascades = &caster.cascades;
+        let count = cascades.count.clamp(1, 4);
+        let distance = cascades.distance.clamp(near, far);
+        let log_near = near.max(0.001);
+
+        let mut maps = Vec::new();
+        let mut start = near;
+        for index in 1..=count {
+            let ratio = index as f32 / count as f32;
+            let uniform = near + (distance - near) * ratio;
+            let log = log_near * (distance / log_near).powf(ratio);
+            let end = uniform + (log - uniform) * cascades.lambda.clamp(0., 1.);
+
+            let points = corners.iter().flat_map(|(corner_near, corner_far)| {
+                [start, end].map(|depth| {
+                    let t = (depth - near) / (far - near);
+                    inverse_view.transform_point3(corner_near.lerp(*corner_far, t))
+                })
+            });
+            let points = points.collect::<Vec<_>>();
+
+            // a bounding sphere keeps the size of the map stable while the camera rotates
+            let center = points.iter().sum::<Vec3>() / points.len() as f32;
+            let radius = points.iter().map(|p| p.distance(center)).fold(0., f32::max);
+            let radius = (radius * 16.).ceil() / 16.;
+
+            // casters behind the cascade are included up to the shadow distance
+            let eye = center - direction * (radius + distance);
+            let view = Mat4::look_at_rh(eye, center, up_vector(direction));
+            let projection =
+                Mat4::orthographic_rh(-radius, radius, -radius, radius, 0., 2. * radius + distance);
+
+            // snap to whole texels so the edges do not shimmer when the camera moves
+            let matrix = projection * view;
+            let texels = self.resolution as f32 / 2.;
+            let origin = (matrix * Vec3::ZERO.extend(1.)).xy() * texels;
+            let offset = (origin.round() - origin) / texels;
+            let snap = Mat4::from_translation(offset.extend(0.));
+
+            maps.push(ShadowMap::new(snap * matrix, &caster.settings, end));
+            start = end;
+        }
+
+        maps
+    }
+}
+
+fn spot_map(light: &Light, settings: &TaroShadowSettings) -> ShadowMap {
+    let position = light.position();
+    let direction = light.direction();
+    let fov = (2. * light.outer_cos().clamp(-1., 1.).acos()).clamp(0.01, 3.);
+
+    let view = Mat4::look_at_rh(position, position + direction, up_vector(direction));
+    let projection =
+        Mat4::perspective_rh(fov, 1., SHADOW_NEAR, light.range().max(SHADOW_NEAR * 2.));
+    ShadowMap::new(projection * view, settings, 0.)
+}
+
+fn point_maps(light: &Light, settings: &TaroShadowSettings) -> Vec<ShadowMap> {
+    let position = light.position();
+    let far = light.range().max(SHADOW_NEAR * 2.);
+    let projection = Mat4::perspective_rh(FRAC_PI_2, 1., SHADOW_NEAR, far);
+
+    CUBE_FACES
+        .iter()
+        .map(|face| {
+            let view = Mat4::look_at_rh(position, position + *face, up_vector(*face));
+            ShadowMap::new(projection * view, settings, 0.)
+        })
+        .collect()
+}
+
+fn up_vector(direction: Vec3) -> Vec3 {
+    match direction.y.abs() > 0.99 {
+        true => Vec3::Z,
+        false => Vec3::Y,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TaroCameraSettings;
+
+    #[test]
+    fn cascades_cover_frustum() {
+        let position = Vec3::new(0., 2., 5.);
+        let view_matrix = Mat4::look_at_rh(position, Vec3::ZERO, Vec3::Y);
+        let projection_matrix =
+            TaroCameraSettings::perspective(60., 0.1, 100.).projection_matrix(1.);
+        let camera = TaroCameraView {
+            position,
+            view_matrix,
+            projection_matrix,
+            camera_matrix: (projection_matrix * view_matrix).into(),
+            ..Default::default()
+        };
+
+        let caster = ShadowCaster {
+            settings: Default::default(),
+            cascades: TaroCascades {
+                count: 3,
+                distance: 40.,
+                lambda: 0.5,
+            },
+        };
+
+        let mut light = Light::directional(Vec3::new(0.3, -1., 0.2), [1.; 3], 1.);
+        let mut array = ShadowArray::default();
+        array.push(&mut light, &caster, &camera);
+        assert!(array.len() == 3);
+        assert!(light.shadow_index() == 0);
+
+        let splits = array.maps().iter().map(|m| m.split()).collect::<Vec<_>>();
+        assert!(splits[0] < splits[1] && splits[1] < splits[2]);
+        assert!((splits[2] - 40.).abs() < 0.001);
+
+        // the point the camera looks at is inside the first cascade
+        let clip = array.maps()[0].matrix() * Vec3::ZERO.extend(1.);
+        let ndc = clip.xyz() / clip.w;
+        assert!(ndc.x.abs() <= 1. && ndc.y.abs() <= 1.);
+        assert!(ndc.z >= 0. && ndc.z <= 1.);
+    }
+
+    #[test]
+    fn map_capacity() {
+        let caster = ShadowCaster {
+            settings: Default::default(),
+            cascades: Default::default(),
+        };
+
+        let camera = TaroCameraView::default();
+        let mut array = ShadowArray::default();
+        let mut lights = Vec::new();
+        for _ in 0..6 {
+            let mut light = Light::point(Vec3::ZERO, 10., [1.; 3], 1.);
+            array.push(&mut light, &caster, &camera);
+            lights.push(light);
+        }
+
+        // 5 point lights with 6 faces each fit into 32 maps
+        assert!(array.len() == 30);
+        assert!(lights[4].shadow_index() == 24);
+        assert!(lights[5].shadow_index() == -1);
+    }
+}
@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+
+use boba_3d::glam::{Vec2, Vec3};
+
+use super::Vertex;
+
+/// How normals are calculated for meshes that do not provide their own
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum NormalMode {
+    /// Averages the normals of every triangle that shares a position, weighted by area
+    #[default]
+    Smooth,
+    /// Gives every triangle its own vertices that all use the face normal
+    Flat,
+}
+
+impl Vertex {
+    /// Calculates smooth normals for the triangles in `indices`.
+    ///
+    /// Vertices are welded by position, so seams in the uv layout stay smooth.
+    pub fn smooth_normals<I: Copy + Into<u32>>(vertices: &mut [Vertex], indices: &[I]) {
+        let mut normals = HashMap::<[u32; 3], Vec3>::new();
+        for triangle in triangles(vertices, indices) {
+            let [a, b, c] = triangle.map(|v| Vec3::from(v.position));
+            let face = (b - a).cross(c - a);
+            for vertex in triangle {
+                *normals.entry(bits(&vertex.position)).or_default() += face;
+            }
+        }
+
+        for vertex in vertices.iter_mut() {
+            let normal = normals.get(&bits(&vertex.position)).copied();
+            vertex.normal = normal.unwrap_or_default().normalize_or_zero().to_array();
+        }
+    }
+
+    /// Calculates flat normals for the triangles in `indices`.
+    ///
+    /// Every index gets its own vertex in the result,
+    /// so the new indices are the range `0..vertices.len()`.
+    pub fn flat_normals<I: Copy + Into<u32>>(vertices: &[Vertex], indices: &[I]) -> Vec<Vertex> {
+        let mut flat = Vec::with_capacity(indices.len());
+        for triangle in triangles(vertices, indices) {
+            let [a, b, c] = triangle.map(|v| Vec3::from(v.position));
+            let normal = (b - a).cross(c - a).normalize_or_zero().to_array();
+            flat.extend(triangle.map(|vertex| Vertex { normal, ..vertex }));
+        }
+
+        flat
+    }
+
+    /// Generates tangents for the triangles in `indices` from their normals and uvs.
+    ///
+    /// Like MikkTSpace, the tangent space is accumulated over vertices that share a position,
+    /// normal and uv, then made orthogonal to the normal.
+    /// The tangent points along increasing u, and `w` is the sign of the bitangent
+    /// `cross(normal, tangent.xyz) * w`, which points along decreasing v.
+    /// This is up in the image, matching the normal maps used by gltf.
+    pub fn generate_tangents<I: Copy + Into<u32>>(vertices: &mut [Vertex], indices: &[I]) {
+        let mut frames = HashMap::<[u32; 8], (Vec3, Vec3)>::new();
+        for triangle in triangles(vertices, indices) {
+            let [p0, p1, p2] = triangle.map(|v| Vec3::from(v.position));
+            let [uv0, uv1, uv2] = triangle.map(|v| Vec2::from(v.uv));
+
+            let (edge1, edge2) = (p1 - p0, p2 - p0);
+            let (duv1, duv2) = (uv1 - uv0, uv2 - uv0);
+            let det = duv1.perp_dot(duv2);
+            if det.abs() <= f32::EPSILON {
+                continue;
+            }
+
+            // weight by the triangle area so small triangles do not skew the result
+            let area = edge1.cross(edge2).length();
+            let tangent = ((edge1 * duv2.y - edge2 * duv1.y) / det).normalize_or_zero() * area;
+            let bitangent = ((edge2 * duv1.x - edge1 * duv2.x) / det).normalize_or_zero() * area;
+            for vertex in triangle {
+                let frame = frames.entry(frame_bits(&vertex)).or_default();
+                frame.0 += tangent;
+                frame.1 += bitangent;
+            }
+        }
+
+        for vertex in vertices.iter_mut() {
+            let normal = Vec3::from(vertex.normal).normalize_or_zero();
+            let (tangent, bitangent) = frames.get(&frame_bits(vertex)).copied().unwrap_or_default();
+
+            // gram schmidt, falling back to any perpendicular for degenerate uvs
+            let mut orthogonal = (tangent - normal * normal.dot(tangent)).normalize_or_zero();
+            if orthogonal == Vec3::ZERO {
+                orthogonal = normal.any_orthonormal_vector();
+            }
+
+            // the bitangent above points along increasing v, so it is flipped
+            let w = match normal.cross(orthogonal).dot(bitangent) > 0. {
+                true => -1.,
+                false => 1.,
+            };
+
+            vertex.tangent = orthogonal.extend(w).to_array();
+        }
+    }
+
+    /// Merges identical vertices, returning the unique vertices and the remapped indices.
+    ///
+    /// Vertices that are not used by any triangle are removed.
+    pub fn deduplicate<I: Copy + Into<u32>>(
+        vertices: &[Vertex],
+        indices: &[I],
+    ) -> (Vec<Vertex>, Vec<u32>) {
+        let mut unique = Vec::new();
+        let mut lookup = HashMap::<[u32; 12], u32>::new();
+        let indices = triangles(vertices, indices)
+            .flatten()
+            .map(|vertex| {
+                let key = bytemuck::cast::<Vertex, [u32; 12]>(vertex);
+                *lookup.entry(key).or_insert_with(|| {
+                    unique.push(vertex);
+                    unique.len() as u32 - 1
+                })
+            })
+            .collect();
+
+        (unique, indices)
+    }
+}
+
+fn triangles<'a, I: Copy + Into<u32>>(
+    vertices: &'a [Vertex],
+    indices: &'a [I],
+) -> impl Iterator<Item = [Vertex; 3]> + 'a {
+    indices.chunks_exact(3).filter_map(|triangle| {
+        let a = vertices.get(triangle[0].into() as usize)?;
+        let b = vertices.get(triangle[1].into() as usize)?;
+        let c = vertices.get(triangle[2].into() as usize)?;
+        Some([*a, *b, *c])
+    })
+}
+
+fn bits(values: &[f32; 3]) -> [u32; 3] {
+    values.map(f32::to_bits)
+}
+
+fn frame_bits(vertex: &Vertex) -> [u32; 8] {
+    let [px, py, pz] = bits(&vertex.position);
+    let [nx, ny, nz] = bits(&vertex.normal);
+    let [u, v] = vertex.uv.map(f32::to_bits);
+    [px, py, pz, nx, ny, nz, u, v]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quad() -> (Vec<Vertex>, Vec<u16>) {
+        let vertex = |position: [f32; 3], uv: [f32; 2]| Vertex {
+            position,
+            uv,
+            normal: [0.; 3],
+            tangent: [0.; 4],
+        };
+
+        // a quad in the xy plane facing +z, with the image top at +y
+        let vertices = vec![
+            vertex([0., 0., 0.], [0., 1.]),
+            vertex([1., 0., 0.], [1., 1.]),
+            vertex([1., 1., 0.], [1., 0.]),
+            vertex([0., 1., 0.], [0., 0.]),
+        ];
+
+        (vertices, vec![0, 1, 2, 0, 2, 3])
+    }
+
+    #[test]
+    fn normals() {
+        let (mut vertices, indices) = quad();
+        Vertex::smooth_normals(&mut vertices, &indices);
+        assert!(vertices.iter().all(|v| v.normal == [0., 0., 1.]));
+
+        let flat = Vertex::flat_normals(&vertices, &indices);
+        assert!(flat.len() == 6);
+        assert!(flat.iter().all(|v| v.normal == [0., 0., 1.]));
+
+        let (unique, indices) = Vertex::deduplicate(&flat, &[0u32, 1, 2, 3, 4, 5]);
+        assert!(unique.len() == 4);
+        assert!(indices == [0, 1, 2, 0, 2, 3]);
+    }
+
+    #[test]
+    fn tangents() {
+        let (mut vertices, indices) = quad();
+        Vertex::smooth_normals(&mut vertices, &indices);
+        Vertex::generate_tangents(&mut vertices, &indices);
+
+        for vertex in vertices.iter() {
+            let tangent = Vec3::from_slice(&vertex.tangent[..3]);
+            let bitangent = Vec3::from(vertex.normal).cross(tangent) * vertex.tangent[3];
+            assert!(tangent.abs_diff_eq(Vec3::X, 1e-5));
+            assert!(bitangent.abs_diff_eq(Vec3::Y, 1e-5));
+        }
+    }
+}
@@ -1,12 +1,12 @@
 use std::{
     fmt::Debug,
-    fs::File,
-    io::BufReader,
     marker::PhantomData,
+    ops::Range,
     sync::atomic::{AtomicU32, Ordering},
 };
 
-use tobj::LoadError;
+use boba_3d::{geometry::Aabb, glam::Vec3};
+use log::error;
 use wgpu::util::DeviceExt;
 
 use crate::{
@@ -20,13 +20,22 @@ pub struct Vertex {
     pub position: [f32; 3],
     pub uv: [f32; 2],
     pub normal: [f32; 3],
+    /// The direction of increasing u, with the bitangent sign in `w`.
+    ///
+    /// See [`Vertex::generate_tangents`] for the convention.
+    pub tangent: [f32; 4],
 }
 
 impl Vertex {
     pub const BUFFER_LAYOUT: wgpu::VertexBufferLayout<'_> = wgpu::VertexBufferLayout {
         array_stride: std::mem::size_of::<Vertex>() as wgpu::BufferAddress,
         step_mode: wgpu::VertexStepMode::Vertex,
-        attributes: &wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x2, 2 => Float32x3],
+        attributes: &wgpu::vertex_attr_array![
+            0 => Float32x3,
+            1 => Float32x2,
+            2 => Float32x3,
+            3 => Float32x4,
+        ],
     };
 }
 
@@ -69,32 +78,158 @@ impl MeshData<Vertex> {
     }
 }
 
-impl MeshData<u16> {
-    fn new(indices: &[u16], hardware: &TaroHardware) -> Self {
+/// The indices of a mesh, in either of the formats supported by the gpu
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MeshIndices {
+    U16(Box<[u16]>),
+    U32(Box<[u32]>),
+}
+
+impl Default for MeshIndices {
+    fn default() -> Self {
+        Self::U16(Box::new([]))
+    }
+}
+
+impl From<Vec<u16>> for MeshIndices {
+    fn from(indices: Vec<u16>) -> Self {
+        Self::U16(indices.into_boxed_slice())
+    }
+}
+
+impl From<Vec<u32>> for MeshIndices {
+    fn from(indices: Vec<u32>) -> Self {
+        Self::U32(indices.into_boxed_slice())
+    }
+}
+
+impl From<&[u16]> for MeshIndices {
+    fn from(indices: &[u16]) -> Self {
+        Self::U16(indices.into())
+    }
+}
+
+impl From<&[u32]> for MeshIndices {
+    fn from(indices: &[u32]) -> Self {
+        Self::U32(indices.into())
+    }
+}
+
+impl MeshIndices {
+    /// Chooses the format automatically, using u16 if every index fits and u32 otherwise
+    pub fn compact(indices: &[u32]) -> Self {
+        match indices.iter().all(|&i| i <= u16::MAX as u32) {
+            true => Self::U16(indices.iter().map(|&i| i as u16).collect()),
+            false => Self::U32(indices.into()),
+        }
+    }
+
+    pub fn format(&self) -> wgpu::IndexFormat {
+        match self {
+            Self::U16(_) => wgpu::IndexFormat::Uint16,
+            Self::U32(_) => wgpu::IndexFormat::Uint32,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Self::U16(indices) => indices.len(),
+            Self::U32(indices) => indices.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<u32> {
+        match self {
+            Self::U16(indices) => indices.get(index).map(|&i| i as u32),
+            Self::U32(indices) => indices.get(index).copied(),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        (0..self.len()).filter_map(|index| self.get(index))
+    }
+
+    /// Converts the indices into `format`.
+    ///
+    /// Returns `None` if an index does not fit in u16.
+    pub fn to_format(&self, format: wgpu::IndexFormat) -> Option<Self> {
+        match (self, format) {
+            (Self::U16(_), wgpu::IndexFormat::Uint16)
+            | (Self::U32(_), wgpu::IndexFormat::Uint32) => Some(self.clone()),
+            (_, wgpu::IndexFormat::Uint32) => Some(Self::U32(self.iter().collect())),
+            (_, wgpu::IndexFormat::Uint16) => {
+                let indices = self.iter().map(u16::try_from).collect::<Result<_, _>>();
+                Some(Self::U16(indices.ok()?))
+            }
+        }
+    }
+
+    fn bytes(&self) -> &[u8] {
+        match self {
+            Self::U16(indices) => bytemuck::cast_slice(indices),
+            Self::U32(indices) => bytemuck::cast_slice(indices),
+        }
+    }
+}
+
+pub struct IndexData {
+    raw_buffer: wgpu::Buffer,
+    length: AtomicU32,
+    format: wgpu::IndexFormat,
+}
+
+impl IndexData {
+    pub fn len(&self) -> u32 {
+        self.length.load(Ordering::Relaxed)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn raw_buffer(&self) -> &wgpu::Buffer {
+        &self.raw_buffer
+    }
+
+    /// Gets the format of the indices, which is passed to [`wgpu::RenderPass::set_index_buffer`]
+    pub fn format(&self) -> wgpu::IndexFormat {
+        self.format
+    }
+
+    fn new(indices: &MeshIndices, hardware: &TaroHardware) -> Self {
         Self {
-            _type: Default::default(),
             length: AtomicU32::new(indices.len() as u32),
+            format: indices.format(),
             raw_buffer: hardware
                 .device()
                 .create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                    label: Some("Vertex Buffer"),
-                    contents: bytemuck::cast_slice(&indices),
+                    label: Some("Index Buffer"),
+                    contents: indices.bytes(),
                     usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
                 }),
         }
     }
 
-    fn write(&self, indices: &[u16], hardware: &TaroHardware) {
+    fn write(&self, indices: &MeshIndices, hardware: &TaroHardware) {
+        let Some(indices) = indices.to_format(self.format) else {
+            error!("Could not write indices. Index buffer uses u16, but an index does not fit.");
+            return;
+        };
+
         hardware
             .queue()
-            .write_buffer(&self.raw_buffer, 0, bytemuck::cast_slice(indices));
+            .write_buffer(&self.raw_buffer, 0, indices.bytes());
         self.length.store(indices.len() as u32, Ordering::Relaxed);
     }
 }
 
 pub struct MeshBuffer {
     vertex_buffer: MeshData<Vertex>,
-    index_buffer: MeshData<u16>,
+    index_buffer: IndexData,
 }
 
 impl MeshBuffer {
@@ -102,66 +237,79 @@ impl MeshBuffer {
         &self.vertex_buffer
     }
 
-    pub fn index_buffer(&self) -> &MeshData<u16> {
+    pub fn index_buffer(&self) -> &IndexData {
         &self.index_buffer
     }
 }
 
+/// A range of a mesh's indices that is drawn with one material
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubMesh {
+    pub name: String,
+    pub indices: Range<u32>,
+    /// The index of the material in the file the mesh was loaded from
+    pub material: Option<usize>,
+}
+
 pub struct Mesh {
     vertices: Box<[Vertex]>,
-    indices: Box<[u16]>,
+    indices: MeshIndices,
+    submeshes: Box<[SubMesh]>,
+    bounds: Aabb,
 }
 
 impl Mesh {
-    pub fn new(obj_file: File) -> Result<Taro<Self>, LoadError> {
-        let mut reader = BufReader::new(obj_file);
-        let (models, _) = tobj::load_obj_buf(
-            &mut reader,
-            &tobj::LoadOptions {
-                single_index: true,
-                triangulate: true,
-                ignore_points: true,
-                ignore_lines: true,
-            },
-            |_| Ok(Default::default()),
-        )?;
-
-        let mut vertices = Vec::<Vertex>::new();
-        let mut indices = Vec::<u16>::new();
-
-        for model in models {
-            let mesh = model.mesh;
-            for index in &mesh.indices {
-                let pos_offset = (3 * index) as usize;
-                let texcoord_offset = (2 * index) as usize;
-
-                let vertex = Vertex {
-                    position: [
-                        mesh.positions[pos_offset + 0],
-                        mesh.positions[pos_offset + 1],
-                        mesh.positions[pos_offset + 2],
-                    ],
-                    uv: [
-                        mesh.texcoords[texcoord_offset + 0],
-                        1. - mesh.texcoords[texcoord_offset + 1],
-                    ],
-                    normal: [0., 0., 0.],
-                };
-
-                vertices.push(vertex);
-                indices.push(indices.len() as u16);
-            }
-        }
+    /// Creates a mesh from raw vertices and indices.
+    ///
+    /// The index format is chosen by the type of `indices`,
+    /// or automatically with [`MeshIndices::compact`].
+    pub fn from_vertices(vertices: &[Vertex], indices: impl Into<MeshIndices>) -> Taro<Self> {
+        let indices = indices.into();
+        let submesh = SubMesh {
+            name: String::new(),
+            indices: 0..indices.len() as u32,
+            material: None,
+        };
 
-        Ok(Self::from_vertices(&vertices, &indices))
+        Self::from_submeshes(vertices, indices, vec![submesh])
     }
 
-    pub fn from_vertices(vertices: &[Vertex], indices: &[u16]) -> Taro<Self> {
+    /// Creates a mesh that is split into `submeshes`
+    pub fn from_submeshes(
+        vertices: &[Vertex],
+        indices: impl Into<MeshIndices>,
+        submeshes: Vec<SubMesh>,
+    ) -> Taro<Self> {
+        let positions = vertices.iter().map(|v| Vec3::from(v.position));
+        let bounds = Aabb::from_points(positions).unwrap_or(Aabb::new(Vec3::ZERO, Vec3::ZERO));
+
         Taro::new(Self {
             vertices: Box::<[Vertex]>::from(vertices),
-            indices: Box::<[u16]>::from(indices),
+            indices: indices.into(),
+            submeshes: submeshes.into_boxed_slice(),
+            bounds,
         })
     }
+
+    pub fn vertices(&self) -> &[Vertex] {
+        &self.vertices
+    }
+
+    pub fn indices(&self) -> &MeshIndices {
+        &self.indices
+    }
+
+    pub fn submeshes(&self) -> &[SubMesh] {
+        &self.submeshes
+    }
+
+    /// Gets the local space bounds of the mesh, calculated when it was created.
+    ///
+    /// Vertices written with [`Taro::write_vertices`] only change the gpu buffer,
+    /// and are not included in the bounds.
+    pub fn bounds(&self) -> Aabb {
+        self.bounds
+    }
 }
 
 impl TaroBuilder for Mesh {
@@ -170,7 +318,7 @@ impl TaroBuilder for Mesh {
     fn compile(&self, hardware: &TaroHardware) -> Self::Compiled {
         MeshBuffer {
             vertex_buffer: MeshData::<Vertex>::new(&self.vertices, hardware),
-            index_buffer: MeshData::<u16>::new(&self.indices, hardware),
+            index_buffer: IndexData::new(&self.indices, hardware),
         }
     }
 }
@@ -181,8 +329,29 @@ impl Taro<Mesh> {
         buffer.vertex_buffer.write(vertices, hardware);
     }
 
-    pub fn write_indices(&self, indices: &[u16], hardware: &TaroHardware) {
+    /// Writes new indices into the gpu buffer, converting them into its existing format
+    pub fn write_indices(&self, indices: impl Into<MeshIndices>, hardware: &TaroHardware) {
         let buffer = self.get_or_compile(hardware);
-        buffer.index_buffer.write(indices, hardware);
+        buffer.index_buffer.write(&indices.into(), hardware);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_formats() {
+        let small = MeshIndices::compact(&[0, 1, u16::MAX as u32]);
+        assert!(small.format() == wgpu::IndexFormat::Uint16);
+        assert!(small.iter().eq([0, 1, u16::MAX as u32]));
+
+        let large = MeshIndices::compact(&[0, 1, 70_000]);
+        assert!(large.format() == wgpu::IndexFormat::Uint32);
+        assert!(large.get(2) == Some(70_000));
+        assert!(large.to_format(wgpu::IndexFormat::Uint16).is_none());
+
+        let widened = small.to_format(wgpu::IndexFormat::Uint32).unwrap();
+        assert!(widened == MeshIndices::from(vec![0u32, 1, u16::MAX as u32]));
     }
 }
@@ -1,5 +1,9 @@
+mod geometry;
 mod mesh;
+mod obj;
 mod texture;
 
+pub use geometry::*;
 pub use mesh::*;
+pub use obj::*;
 pub use texture::*;
@@ -0,0 +1,246 @@
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+
+use log::warn;
+use thiserror::Error;
+
+use crate::shading::Taro;
+
+use super::{Mesh, MeshIndices, NormalMode, SubMesh, Vertex};
+
+const LOAD_OPTIONS: tobj::LoadOptions = tobj::LoadOptions {
+    single_index: true,
+    triangulate: true,
+    ignore_points: true,
+    ignore_lines: true,
+};
+
+/// An error returned when loading a [`Mesh`] from an obj file
+#[derive(Debug, Error)]
+pub enum ObjError {
+    #[error("Could not load obj file. Error: {0}")]
+    Load(tobj::LoadError),
+    #[error("Model '{0}' has no texture coordinates")]
+    MissingTexcoords(String),
+}
+
+/// A material from the mtl library of an obj file
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjMaterial {
+    pub name: String,
+    pub ambient: [f32; 3],
+    pub diffuse: [f32; 3],
+    pub specular: [f32; 3],
+    pub shininess: f32,
+    /// The opacity of the material, where 1 is fully opaque
+    pub dissolve: f32,
+    pub diffuse_texture: Option<PathBuf>,
+    pub specular_texture: Option<PathBuf>,
+    pub normal_texture: Option<PathBuf>,
+    pub dissolve_texture: Option<PathBuf>,
+}
+
+impl ObjMaterial {
+    fn new(material: tobj::Material, directory: &Path) -> Self {
+        let texture = |path: String| match path.is_empty() {
+            true => None,
+            false => Some(directory.join(path)),
+        };
+
+        Self {
+            name: material.name,
+            ambient: material.ambient,
+            diffuse: material.diffuse,
+            specular: material.specular,
+            shininess: material.shininess,
+            dissolve: material.dissolve,
+            diffuse_texture: texture(material.diffuse_texture),
+            specular_texture: texture(material.specular_texture),
+            normal_texture: texture(material.normal_texture),
+            dissolve_texture: texture(material.dissolve_texture),
+        }
+    }
+}
+
+/// A mesh loaded from an obj file, with the materials that its submeshes refer to
+pub struct ObjModel {
+    pub mesh: Taro<Mesh>,
+    pub materials: Vec<ObjMaterial>,
+}
+
+impl Mesh {
+    /// Loads a mesh from an obj file, calculating smooth normals if the file has none
+    pub fn new(obj_file: File) -> Result<Taro<Self>, ObjError> {
+        Self::new_with_normals(obj_file, NormalMode::Smooth)
+    }
+
+    /// Loads a mesh from an obj file, calculating normals with `mode` if the file has none.
+    ///
+    /// Every object and material in the file becomes a [`SubMesh`].
+    /// The material library is not loaded, so submeshes have no material.
+    pub fn new_with_normals(obj_file: File, mode: NormalMode) -> Result<Taro<Self>, ObjError> {
+        let mut reader = BufReader::new(obj_file);
+        let (models, _) =
+            tobj::load_obj_buf(&mut reader, &LOAD_OPTIONS, |_| Ok(Default::default()))
+                .map_err(ObjError::Load)?;
+
+        build_mesh(models, mode, 0)
+    }
+
+    /// Loads a mesh and its material library from the obj file at `path`.
+    ///
+    /// Texture paths are resolved relative to the obj file.
+    /// If the material library can not be loaded, the mesh is still returned without materials.
+    pub fn load_obj(path: impl AsRef<Path>, mode: NormalMode) -> Result<ObjModel, ObjError> {
+        let path = path.as_ref();
+        let (models, materials) = tobj::load_obj(path, &LOAD_OPTIONS).map_err(ObjError::Load)?;
+        let materials = materials.unwrap_or_else(|e| {
+            warn!(
+                "Could not load materials for '{}'. Error: {e}",
+                path.display()
+            );
+            Vec::new()
+        });
+
+        let directory = path.parent().unwrap_or(Path::new(""));
+        let materials = materials
+            .into_iter()
+            .map(|material| ObjMaterial::new(material, directory))
+            .collect::<Vec<_>>();
+
+        let mesh = build_mesh(models, mode, materials.len())?;
+        Ok(ObjModel { mesh, materials })
+    }
+}
+
+fn build_mesh(
+    models: Vec<tobj::Model>,
+    mode: NormalMode,
+    material_count: usize,
+) -> Result<Taro<Mesh>, ObjError> {
+    let mut vertices = Vec::<Vertex>::new();
+    let mut indices = Vec::<u32>::new();
+    let mut submeshes = Vec::<SubMesh>::new();
+
+    for model in models {
+        let (model_vertices, model_indices) = model_vertices(&model, mode)?;
+        let base = vertices.len() as u32;
+        let start = indices.len() as u32;
+        vertices.extend(model_vertices);
+        indices.extend(model_indices.iter().map(|index| index + base));
+
+        let material = model.mesh.material_id;
+        submeshes.push(SubMesh {
+            name: model.name,
+            indices: start..indices.len() as u32,
+            material: material.filter(|&material| material < material_count),
+        });
+    }
+
+    let indices = MeshIndices::compact(&indices);
+    Ok(Mesh::from_submeshes(&vertices, indices, submeshes))
+}
+
+fn model_vertices(
+    model: &tobj::Model,
+    mode: NormalMode,
+) -> Result<(Vec<Vertex>, Vec<u32>), ObjError> {
+    let mesh = &model.mesh;
+    let count = mesh.positions.len() / 3;
+    if mesh.texcoords.len() < count * 2 {
+        return Err(ObjError::MissingTexcoords(model.name.clone()));
+    }
+
+    let has_normals = mesh.normals.len() >= count * 3;
+    let mut vertices = (0..count)
+        .map(|index| Vertex {
+            position: [
+                mesh.positions[index * 3],
+                mesh.positions[index * 3 + 1],
+                mesh.positions[index * 3 + 2],
+            ],
+            uv: [
+                mesh.texcoords[index * 2],
+                1. - mesh.texcoords[index * 2 + 1],
+            ],
+            normal: match has_normals {
+                true => [
+                    mesh.normals[index * 3],
+                    mesh.normals[index * 3 + 1],
+                    mesh.normals[index * 3 + 2],
+                ],
+                false => [0., 0., 0.],
+            },
+            tangent: [0., 0., 0., 1.],
+        })
+        .collect::<Vec<_>>();
+
+    let mut indices = mesh.indices.clone();
+    if !has_normals {
+        match mode {
+            NormalMode::Smooth => Vertex::smooth_normals(&mut vertices, &indices),
+            NormalMode::Flat => {
+                vertices = Vertex::flat_normals(&vertices, &indices);
+                indices = (0..vertices.len() as u32).collect();
+            }
+        }
+    }
+
+    Vertex::generate_tangents(&mut vertices, &indices);
+    Ok(Vertex::deduplicate(&vertices, &indices))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUADS: &str = "\
+o first\n\
+v 0 0 0\n\
+v 1 0 0\n\
+v 1 1 0\n\
+v 0 1 0\n\
+vt 0 0\n\
+vt 1 0\n\
+vt 1 1\n\
+vt 0 1\n\
+f 1/1 2/2 3/3 4/4\n\
+o second\n\
+v 0 0 1\n\
+v 1 0 1\n\
+v 1 1 1\n\
+f 5/1 6/2 7/3";
+
+    fn load(source: &str, mode: NormalMode) -> Result<Taro<Mesh>, ObjError> {
+        let mut reader = std::io::Cursor::new(source);
+        let (models, _) =
+            tobj::load_obj_buf(&mut reader, &LOAD_OPTIONS, |_| Ok(Default::default())).unwrap();
+
+        build_mesh(models, mode, 0)
+    }
+
+    #[test]
+    fn submeshes() {
+        for mode in [NormalMode::Smooth, NormalMode::Flat] {
+            let mesh = load(QUADS, mode).unwrap();
+            assert!(mesh.vertices().len() == 7);
+            assert!(mesh.indices().len() == 9);
+            assert!(mesh.vertices().iter().all(|v| v.normal == [0., 0., 1.]));
+
+            let submeshes = mesh.submeshes();
+            assert!(submeshes.len() == 2);
+            assert!(submeshes[0].name == "first" && submeshes[0].indices == (0..6));
+            assert!(submeshes[1].name == "second" && submeshes[1].indices == (6..9));
+        }
+    }
+
+    #[test]
+    fn missing_texcoords() {
+        let source = "v 0 0 0\nv 1 0 0\nv 1 1 0\nf 1 2 3";
+        let result = load(source, NormalMode::Smooth);
+        assert!(matches!(result, Err(ObjError::MissingTexcoords(_))));
+    }
+}
@@ -26,11 +26,16 @@ impl Into<wgpu::TextureFormat> for ImageFormat {
 
 pub struct Texture2D {
     size: (u32, u32),
-    image: RgbaImage,
-    format: ImageFormat,
+    image: Option<RgbaImage>,
+    format: wgpu::TextureFormat,
 }
 
 impl Texture2D {
+    /// The format used for render target textures.
+    ///
+    /// This matches the surface format that the standard shaders are built for.
+    pub const RENDER_TARGET_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Bgra8UnormSrgb;
+
     pub fn new(buffer: &[u8]) -> ImageResult<Taro<Self>> {
         Self::new_with_format(buffer, ImageFormat::Srgb)
     }
@@ -39,14 +44,45 @@ impl Texture2D {
         let image = image::load_from_memory(buffer)?;
         Ok(Taro::new(Self {
             size: (image.width(), image.height()),
-            image: image.into_rgba8(),
-            format,
+            image: Some(image.into_rgba8()),
+            format: format.into(),
         }))
     }
 
+    pub fn from_image(image: RgbaImage, format: ImageFormat) -> Taro<Self> {
+        Taro::new(Self {
+            size: (image.width(), image.height()),
+            image: Some(image),
+            format: format.into(),
+        })
+    }
+
+    /// Creates a single pixel texture filled with `color`
+    pub fn from_color(color: [u8; 4], format: ImageFormat) -> Taro<Self> {
+        Self::from_image(RgbaImage::from_pixel(1, 1, image::Rgba(color)), format)
+    }
+
+    /// Creates an empty texture that cameras can render into
+    pub fn new_render_target(size: (u32, u32)) -> Taro<Self> {
+        Taro::new(Self {
+            size: (size.0.max(1), size.1.max(1)),
+            image: None,
+            format: Self::RENDER_TARGET_FORMAT,
+        })
+    }
+
     pub fn size(&self) -> &(u32, u32) {
         &self.size
     }
+
+    pub fn format(&self) -> wgpu::TextureFormat {
+        self.format
+    }
+
+    /// Returns true if this texture was created as a render target
+    pub fn is_render_target(&self) -> bool {
+        self.image.is_none()
+    }
 }
 
 impl TaroBuilder for Texture2D {
@@ -65,13 +101,23 @@ impl TaroBuilder for Texture2D {
             mip_level_count: 1,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
-            format: self.format.into(),
+            format: self.format,
             usage: wgpu::TextureUsages::TEXTURE_BINDING,
         };
 
-        hardware
-            .device()
-            .create_texture_with_data(hardware.queue(), &descriptor, &self.image)
+        match &self.image {
+            Some(image) => {
+                hardware
+                    .device()
+                    .create_texture_with_data(hardware.queue(), &descriptor, image)
+            }
+            None => hardware.device().create_texture(&wgpu::TextureDescriptor {
+                label: Some("Taro Render Texture"),
+                usage: wgpu::TextureUsages::TEXTURE_BINDING
+                    | wgpu::TextureUsages::RENDER_ATTACHMENT,
+                ..descriptor
+            }),
+        }
     }
 }
 
@@ -89,6 +135,16 @@ impl Texture2DView {
         Ok(Self::from_texture(texture))
     }
 
+    /// Creates a view of a single pixel texture filled with `color`
+    pub fn from_color(color: [u8; 4], format: ImageFormat) -> Taro<Self> {
+        Self::from_texture(Texture2D::from_color(color, format))
+    }
+
+    /// Creates a view of an empty texture that cameras can render into
+    pub fn new_render_target(size: (u32, u32)) -> Taro<Self> {
+        Self::from_texture(Texture2D::new_render_target(size))
+    }
+
     pub fn from_texture(texture: Taro<Texture2D>) -> Taro<Self> {
         Taro::new(Self { texture })
     }
@@ -104,7 +160,7 @@ impl TaroBuilder for Texture2DView {
     fn compile(&self, hardware: &TaroHardware) -> Self::Compiled {
         let descriptor = wgpu::TextureViewDescriptor {
             label: Some("Taro Texture View"),
-            format: Some(self.texture.format.into()),
+            format: Some(self.texture.format),
             dimension: Some(wgpu::TextureViewDimension::D2),
             aspect: wgpu::TextureAspect::All,
             base_mip_level: 0,
@@ -285,3 +341,93 @@ impl TaroBuilder for DepthView {
         texture.create_view(&wgpu::TextureViewDescriptor::default())
     }
 }
+
+/// A layered depth texture, used to render shadow maps
+pub struct DepthArrayView {
+    size: (u32, u32),
+    layers: u32,
+}
+
+/// The compiled form of a [`DepthArrayView`]
+pub struct DepthArrayData {
+    pub texture: wgpu::Texture,
+    /// A view of every layer, for sampling in shaders
+    pub view: wgpu::TextureView,
+    /// A view of each single layer, for rendering into
+    pub layer_views: Vec<wgpu::TextureView>,
+}
+
+impl DepthArrayView {
+    pub fn new(size: (u32, u32), layers: u32) -> Taro<DepthArrayView> {
+        Taro::new(DepthArrayView {
+            size: (size.0.max(1), size.1.max(1)),
+            layers: layers.max(1),
+        })
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    pub fn layers(&self) -> u32 {
+        self.layers
+    }
+}
+
+impl TaroBuilder for DepthArrayView {
+    type Compiled = DepthArrayData;
+
+    fn compile(&self, hardware: &TaroHardware) -> Self::Compiled {
+        let size = wgpu::Extent3d {
+            width: self.size.0,
+            height: self.size.1,
+            depth_or_array_layers: self.layers,
+        };
+
+        let texture = hardware.device().create_texture(&wgpu::TextureDescriptor {
+            label: Some("Taro Depth Array Texture"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: DepthView::FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+        });
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            dimension: Some(wgpu::TextureViewDimension::D2Array),
+            ..Default::default()
+        });
+
+        let layer_views = (0..self.layers)
+            .map(|layer| {
+                texture.create_view(&wgpu::TextureViewDescriptor {
+                    dimension: Some(wgpu::TextureViewDimension::D2),
+                    base_array_layer: layer,
+                    array_layer_count: NonZeroU32::new(1),
+                    ..Default::default()
+                })
+            })
+            .collect();
+
+        DepthArrayData {
+            texture,
+            view,
+            layer_views,
+        }
+    }
+}
+
+impl TaroBindBuilder for Taro<DepthArrayView> {
+    fn build_bind_type(&self) -> wgpu::BindingType {
+        wgpu::BindingType::Texture {
+            multisampled: false,
+            view_dimension: wgpu::TextureViewDimension::D2Array,
+            sample_type: wgpu::TextureSampleType::Depth,
+        }
+    }
+
+    fn build_bind_resource(&self, hardware: &TaroHardware) -> wgpu::BindingResource {
+        wgpu::BindingResource::TextureView(&self.get_or_compile(hardware).view)
+    }
+}
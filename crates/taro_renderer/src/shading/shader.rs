@@ -1,9 +1,7 @@
-use super::{
-    buffers::{CameraMatrix, TransformMatrix},
-    data_types::MeshBuffer,
-    Taro, TaroBuilder,
-};
-use crate::TaroHardware;
+use std::ops::Range;
+
+use super::{buffers::TransformMatrix, data_types::MeshBuffer, Taro, TaroBuilder};
+use crate::{TaroCameraView, TaroHardware};
 
 /// The base trait for any shader type.
 ///
@@ -15,12 +13,18 @@ pub trait TaroCoreShader {
 
 /// The base trait for a shader that can render a mesh on screen.
 pub trait TaroMeshShader: TaroCoreShader {
-    fn set_camera_matrix(&self, data: &CameraMatrix, hardware: &TaroHardware);
     fn set_model_matrix(&self, data: &TransformMatrix, hardware: &TaroHardware);
+
+    /// Draws the `indices` range of the index buffer of `mesh` as seen by `camera`.
+    ///
+    /// The camera matrix should be bound from [`TaroCameraView::binding`],
+    /// as the same shader may be drawn by several cameras in one frame.
     fn render<'pass>(
         &'pass self,
         pass: &mut wgpu::RenderPass<'pass>,
         mesh: &'pass MeshBuffer,
+        indices: Range<u32>,
+        camera: &'pass TaroCameraView,
         hardware: &TaroHardware,
     );
 }
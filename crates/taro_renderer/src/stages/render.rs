@@ -4,12 +4,20 @@ use boba_core::{BobaResources, BobaResult, BobaStage, PearlRegistry, ResourceErr
 use log::warn;
 use thiserror::Error;
 
-use crate::{TaroCameras, TaroHardware, TaroRenderPearls};
+use crate::{
+    shading::{data_types::Texture2DView, Taro},
+    TaroCamera, TaroCameras, TaroHardware, TaroRenderPearls, TaroSurfaceId,
+};
 
 pub trait TaroSurfaceManager: 'static {
     fn get_hardware(&self) -> &TaroHardware;
-    fn get_current_texture(&self) -> Result<wgpu::SurfaceTexture, wgpu::SurfaceError>;
-    fn get_surface_size(&self) -> (u32, u32);
+    fn get_surface_ids(&self) -> Vec<TaroSurfaceId>;
+    fn get_current_texture(
+        &self,
+        id: TaroSurfaceId,
+    ) -> Option<Result<wgpu::SurfaceTexture, wgpu::SurfaceError>>;
+    fn get_surface_size(&self, id: TaroSurfaceId) -> Option<(u32, u32)>;
+    fn get_surface_format(&self, id: TaroSurfaceId) -> Option<wgpu::TextureFormat>;
 }
 
 #[derive(Debug, Error)]
@@ -54,22 +62,77 @@ where
         // get the graphics hardware
         let hardware = surface.get_hardware();
 
-        // get and create the view for rendering output
-        let output = surface.get_current_texture()?;
-        let view = output.texture.create_view(&Default::default());
-
         // create the command encoder for rendering
         const COMMAND_ENCODER: wgpu::CommandEncoderDescriptor = wgpu::CommandEncoderDescriptor {
             label: Some("OnTaroRender Command Encoder"),
         };
         let mut encoder = hardware.device().create_command_encoder(&COMMAND_ENCODER);
 
-        // create closure to render all cameras
+        // create closure to render all cameras into their target textures and surfaces
+        let mut outputs = Vec::new();
         let mut render_all_cameras = |pearls: &TaroRenderPearls| {
-            let size = surface.get_surface_size();
+            // group the cameras by target texture
+            let mut texture_targets = Vec::<(Taro<Texture2DView>, Vec<&mut TaroCamera>)>::new();
             for camera in cameras.cameras.iter_mut() {
-                camera.resize(size);
-                camera.render(&*pearls, &view, &mut encoder, hardware);
+                let Some(texture) = camera.target.texture().cloned() else {
+                    continue;
+                };
+
+                match texture_targets.iter_mut().find(|(t, _)| t.ptr_eq(&texture)) {
+                    Some((_, group)) => group.push(camera),
+                    None => texture_targets.push((texture, vec![camera])),
+                }
+            }
+
+            // render textures first so that surface cameras can sample them this frame.
+            // each camera binds its own matrix uniform, so they can all share one encoder
+            for (texture, group) in texture_targets {
+                if !texture.texture().is_render_target() {
+                    warn!("Skipping camera texture that was not created as a render target.");
+                    continue;
+                }
+
+                let size = *texture.texture().size();
+                let format = texture.texture().format();
+                let view = texture.get_or_compile(hardware);
+                render_cameras(group, size, format, view, pearls, &mut encoder, hardware);
+            }
+
+            for id in surface.get_surface_ids() {
+                let (Some(size), Some(format), Some(output)) = (
+                    surface.get_surface_size(id),
+                    surface.get_surface_format(id),
+                    surface.get_current_texture(id),
+                ) else {
+                    continue;
+                };
+
+                let output = match output {
+                    Ok(output) => output,
+                    Err(e) => {
+                        warn!("Could not get texture for surface {id:?}. Error: {e}");
+                        continue;
+                    }
+                };
+
+                let surface_cameras = cameras
+                    .cameras
+                    .iter_mut()
+                    .filter(|c| c.target.is_surface(id))
+                    .collect::<Vec<_>>();
+
+                let view = output.texture.create_view(&Default::default());
+                render_cameras(
+                    surface_cameras,
+                    size,
+                    format,
+                    &view,
+                    pearls,
+                    &mut encoder,
+                    hardware,
+                );
+
+                outputs.push(output);
             }
         };
 
@@ -85,8 +148,29 @@ where
             Err(e) => return Err(e.into()),
         };
 
-        // submit and present the rendered frame
+        // submit and present the rendered frames
         hardware.queue().submit(std::iter::once(encoder.finish()));
-        Ok(output.present())
+        for output in outputs {
+            output.present();
+        }
+
+        Ok(())
+    }
+}
+
+/// Renders `cameras` into `view` in priority order
+fn render_cameras(
+    mut cameras: Vec<&mut TaroCamera>,
+    size: (u32, u32),
+    format: wgpu::TextureFormat,
+    view: &wgpu::TextureView,
+    pearls: &TaroRenderPearls,
+    encoder: &mut wgpu::CommandEncoder,
+    hardware: &TaroHardware,
+) {
+    cameras.sort_by_key(|c| c.priority);
+    for (index, camera) in cameras.into_iter().enumerate() {
+        camera.resize(size, format);
+        camera.render(pearls, view, index == 0, encoder, hardware);
     }
 }
@@ -0,0 +1,172 @@
+/// A rectangle in normalized target coordinates.
+///
+/// The origin is the top left corner of the render target,
+/// and `(1, 1)` is the bottom right corner.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TaroViewport {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Default for TaroViewport {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
+impl TaroViewport {
+    /// A viewport that covers the whole render target
+    pub const FULL: Self = Self::new(0., 0., 1., 1.);
+
+    pub const fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Converts this viewport into a pixel rectangle for a target of `size`.
+    ///
+    /// The resulting rectangle is clamped to the bounds of the target.
+    pub fn pixel_rect(&self, size: (u32, u32)) -> TaroPixelRect {
+        let to_pixels =
+            |value: f32, max: u32| (value * max as f32).round().clamp(0., max as f32) as u32;
+        let x = to_pixels(self.x, size.0);
+        let y = to_pixels(self.y, size.1);
+        let right = to_pixels(self.x + self.width, size.0);
+        let bottom = to_pixels(self.y + self.height, size.1);
+
+        TaroPixelRect {
+            x,
+            y,
+            width: right.saturating_sub(x),
+            height: bottom.saturating_sub(y),
+        }
+    }
+}
+
+/// A rectangle in pixel coordinates of a render target
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TaroPixelRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl TaroPixelRect {
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    pub fn aspect(&self) -> f32 {
+        self.width.max(1) as f32 / self.height.max(1) as f32
+    }
+
+    /// Returns the overlapping area of both rectangles
+    pub fn intersection(&self, other: &TaroPixelRect) -> TaroPixelRect {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = (self.x + self.width).min(other.x + other.width);
+        let bottom = (self.y + self.height).min(other.y + other.height);
+
+        TaroPixelRect {
+            x,
+            y,
+            width: right.saturating_sub(x),
+            height: bottom.saturating_sub(y),
+        }
+    }
+}
+
+/// The area of a render target that a camera is drawing into.
+///
+/// This is passed to every [`TaroRenderPass`](crate::TaroRenderPass)
+/// so that passes can restrict their drawing to the camera's viewport.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TaroCameraRegion {
+    /// The full size of the render target
+    pub target_size: (u32, u32),
+    /// The texture format of the render target
+    pub target_format: wgpu::TextureFormat,
+    /// The pixel area that the camera projects onto
+    pub viewport: TaroPixelRect,
+    /// The pixel area that drawing is clipped to
+    pub scissor: TaroPixelRect,
+    /// True if this is the first camera drawn into the target this frame.
+    ///
+    /// Passes that clear the color target should only do so when this is set,
+    /// otherwise they would erase the output of earlier cameras.
+    pub clear_target: bool,
+}
+
+impl TaroCameraRegion {
+    /// Creates a region that covers the whole target
+    pub fn full(target_size: (u32, u32), target_format: wgpu::TextureFormat) -> Self {
+        let rect = TaroViewport::FULL.pixel_rect(target_size);
+        Self {
+            target_size,
+            target_format,
+            viewport: rect,
+            scissor: rect,
+            clear_target: true,
+        }
+    }
+
+    /// Returns true if the viewport covers the whole target
+    pub fn is_full(&self) -> bool {
+        self.viewport == TaroViewport::FULL.pixel_rect(self.target_size)
+    }
+
+    /// Applies the viewport and scissor rectangle to a render pass
+    pub fn apply<'a>(&self, pass: &mut wgpu::RenderPass<'a>) {
+        pass.set_viewport(
+            self.viewport.x as f32,
+            self.viewport.y as f32,
+            self.viewport.width as f32,
+            self.viewport.height as f32,
+            0.0,
+            1.0,
+        );
+        pass.set_scissor_rect(
+            self.scissor.x,
+            self.scissor.y,
+            self.scissor.width,
+            self.scissor.height,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_screen_rects() {
+        let left = TaroViewport::new(0., 0., 0.5, 1.).pixel_rect((801, 600));
+        let right = TaroViewport::new(0.5, 0., 0.5, 1.).pixel_rect((801, 600));
+        assert!(left.x == 0 && left.width == 401 && left.height == 600);
+        assert!(right.x == 401 && right.width == 400);
+        assert!(left.intersection(&right).is_empty());
+    }
+
+    #[test]
+    fn clamped_rects() {
+        let rect = TaroViewport::new(0.75, -0.5, 0.5, 1.).pixel_rect((100, 100));
+        assert!(
+            rect == TaroPixelRect {
+                x: 75,
+                y: 0,
+                width: 25,
+                height: 50
+            }
+        );
+
+        let outside = TaroViewport::new(2., 0., 1., 1.).pixel_rect((100, 100));
+        assert!(outside.is_empty());
+    }
+}
@@ -1,21 +1,34 @@
 use boba_core::{BobaResources, Pearl, PearlRegistry, StageCollection};
 
+use indexmap::IndexMap;
 use log::error;
-use milk_tea::{event_types::MilkTeaSize, winit::window::Window, MilkTeaAdapter, MilkTeaPlugin};
+use milk_tea::{event_types::MilkTeaSize, winit::window::Window, MilkTeaAdapter, MilkTeaWindowId};
 use taro_renderer::{
     stages::{OnTaroRender, TaroSurfaceManager},
-    TaroHardware, TaroSurface,
+    TaroHardware, TaroSurface, TaroSurfaceId,
 };
 
 use super::TaroMilkTeaResizeListener;
 
-pub struct TaroMilkTea {
+struct TaroWindow {
+    // the surface is declared first so that it is dropped before its window
+    surface: TaroSurface,
     _window: Window,
-    taro_surface: TaroSurface,
+}
+
+pub struct TaroMilkTea {
+    windows: IndexMap<MilkTeaWindowId, TaroWindow>,
     hardware: TaroHardware,
 }
 
 impl TaroMilkTea {
+    /// Gets the id of the surface that renders into `window`.
+    ///
+    /// Assign this to a cameras `target` to render that camera into the window.
+    pub fn surface_id(window: MilkTeaWindowId) -> TaroSurfaceId {
+        TaroSurfaceId(window.0)
+    }
+
     pub fn resize(&mut self, size: &MilkTeaSize) {
         let width = size.width;
         let height = size.height;
@@ -26,11 +39,24 @@ impl TaroMilkTea {
             return;
         }
 
-        self.taro_surface.config.width = width;
-        self.taro_surface.config.height = height;
-        self.taro_surface
+        let Some(window) = self.windows.get_mut(&size.window) else {
+            error!(
+                "Tried to resize window {:?} which does not exist.",
+                size.window
+            );
+            return;
+        };
+
+        window.surface.config.width = width;
+        window.surface.config.height = height;
+        window
+            .surface
             .surface
-            .configure(&self.hardware.device(), &self.taro_surface.config);
+            .configure(self.hardware.device(), &window.surface.config);
+    }
+
+    fn get_window(&self, id: TaroSurfaceId) -> Option<&TaroWindow> {
+        self.windows.get(&MilkTeaWindowId(id.0))
     }
 }
 
@@ -39,37 +65,32 @@ impl TaroSurfaceManager for TaroMilkTea {
         &self.hardware
     }
 
-    fn get_current_texture(&self) -> Result<wgpu::SurfaceTexture, wgpu::SurfaceError> {
-        self.taro_surface.surface.get_current_texture()
+    fn get_surface_ids(&self) -> Vec<TaroSurfaceId> {
+        self.windows
+            .keys()
+            .map(|id| Self::surface_id(*id))
+            .collect()
     }
 
-    fn get_surface_size(&self) -> (u32, u32) {
-        (
-            self.taro_surface.config.width,
-            self.taro_surface.config.height,
-        )
+    fn get_current_texture(
+        &self,
+        id: TaroSurfaceId,
+    ) -> Option<Result<wgpu::SurfaceTexture, wgpu::SurfaceError>> {
+        let window = self.get_window(id)?;
+        Some(window.surface.surface.get_current_texture())
     }
-}
-
-impl MilkTeaAdapter for TaroMilkTea {
-    fn build(window: Window) -> Self {
-        let size = window.inner_size();
 
-        // Safety: Surface must be alive for as long as the window.
-        // The surface and window are not exposed externally,
-        // and thus any problems between the two are handled internally.
-        let (hardware, taro_surface) =
-            unsafe { TaroHardware::build(&window, (size.width, size.height)) };
+    fn get_surface_size(&self, id: TaroSurfaceId) -> Option<(u32, u32)> {
+        let config = &self.get_window(id)?.surface.config;
+        Some((config.width, config.height))
+    }
 
-        Self {
-            _window: window,
-            taro_surface,
-            hardware,
-        }
+    fn get_surface_format(&self, id: TaroSurfaceId) -> Option<wgpu::TextureFormat> {
+        Some(self.get_window(id)?.surface.config.format)
     }
 }
 
-impl MilkTeaPlugin for TaroMilkTea {
+impl MilkTeaAdapter for TaroMilkTea {
     fn setup(
         registry: &mut PearlRegistry,
         _: &mut StageCollection,
@@ -79,4 +100,47 @@ impl MilkTeaPlugin for TaroMilkTea {
         main_stages.append(OnTaroRender::<TaroMilkTea>::default());
         registry.add(&Pearl::wrap(TaroMilkTeaResizeListener));
     }
+
+    fn build(id: MilkTeaWindowId, window: Window) -> Self {
+        let size = window.inner_size();
+
+        // Safety: Surface must be alive for as long as the window.
+        // The surface and window are not exposed externally,
+        // and thus any problems between the two are handled internally.
+        let (hardware, surface) =
+            unsafe { TaroHardware::build(&window, (size.width, size.height)) };
+
+        let mut windows = IndexMap::new();
+        windows.insert(
+            id,
+            TaroWindow {
+                surface,
+                _window: window,
+            },
+        );
+
+        Self { windows, hardware }
+    }
+
+    fn add_window(&mut self, id: MilkTeaWindowId, window: Window) {
+        let size = window.inner_size();
+
+        // Safety: see `build`, the same rules apply for every window.
+        let surface = unsafe {
+            self.hardware
+                .create_surface(&window, (size.width, size.height))
+        };
+
+        self.windows.insert(
+            id,
+            TaroWindow {
+                surface,
+                _window: window,
+            },
+        );
+    }
+
+    fn remove_window(&mut self, id: MilkTeaWindowId) {
+        self.windows.shift_remove(&id);
+    }
 }
@@ -0,0 +1,405 @@
+use std::collections::HashMap;
+
+use boba_3d::{
+    animation::gltf_node_name,
+    glam::{Mat4, Quat, Vec3},
+};
+use gltf::{
+    image::Format,
+    khr_lights_punctual::Kind,
+    mesh::Mode,
+    texture::{MagFilter, MinFilter, WrappingMode},
+};
+use log::warn;
+use taro_renderer::{
+    image::{Rgba, RgbaImage},
+    shading::{
+        data_types::{
+            ImageFormat, Mesh, MeshIndices, Sampler, SamplerSettings, SubMesh, Texture2D,
+            Texture2DView, Vertex,
+        },
+        Taro,
+    },
+    wgpu, TaroCameraSettings,
+};
+
+use crate::{PbrFactors, PbrMaterial};
+
+use super::{GltfCamera, GltfError, GltfLight, GltfLightKind, GltfMaterial, GltfNode, GltfSkin};
+
+/// Creates each texture once for every color space it is used in
+pub(super) struct TextureCache<'a> {
+    images: &'a [gltf::image::Data],
+    views: HashMap<(usize, ImageFormat), Taro<Texture2DView>>,
+    textures: Vec<Taro<Texture2D>>,
+}
+
+impl<'a> TextureCache<'a> {
+    pub fn new(images: &'a [gltf::image::Data]) -> Self {
+        Self {
+            images,
+            views: HashMap::new(),
+            textures: Vec::new(),
+        }
+    }
+
+    pub fn into_textures(self) -> Vec<Taro<Texture2D>> {
+        self.textures
+    }
+
+    fn get(
+        &mut self,
+        texture: &gltf::Texture,
+        format: ImageFormat,
+    ) -> Result<Taro<Texture2DView>, GltfError> {
+        let index = texture.source().index();
+        if let Some(view) = self.views.get(&(index, format)) {
+            return Ok(view.clone());
+        }
+
+        let image = self.images.get(index).and_then(rgba_image);
+        let image = image.ok_or(GltfError::InvalidImage(index))?;
+        let texture = Texture2D::from_image(image, format);
+        let view = Texture2DView::from_texture(texture.clone());
+        self.textures.push(texture);
+        self.views.insert((index, format), view.clone());
+        Ok(view)
+    }
+}
+
+/// Converts the pixels of a glTF image into 8 bit rgba.
+///
+/// Images with one or two channels are treated as luminance and alpha.
+fn rgba_image(data: &gltf::image::Data) -> Option<RgbaImage> {
+    let (channels, size) = match data.format {
+        Format::R8 => (1, 1),
+        Format::R8G8 => (2, 1),
+        Format::R8G8B8 => (3, 1),
+        Format::R8G8B8A8 => (4, 1),
+        Format::R16 => (1, 2),
+        Format::R16G16 => (2, 2),
+        Format::R16G16B16 => (3, 2),
+        Format::R16G16B16A16 => (4, 2),
+        Format::R32G32B32FLOAT => (3, 4),
+        Format::R32G32B32A32FLOAT => (4, 4),
+    };
+
+    let channel = |bytes: &[u8]| match bytes {
+        [value] => *value,
+        [a, b] => (u16::from_ne_bytes([*a, *b]) >> 8) as u8,
+        [a, b, c, d] => (f32::from_ne_bytes([*a, *b, *c, *d]).clamp(0., 1.) * 255.).round() as u8,
+        _ => 0,
+    };
+
+    let mut image = RgbaImage::new(data.width, data.height);
+    let pixels = data.pixels.chunks_exact(channels * size);
+    for (pixel, output) in pixels.zip(image.pixels_mut()) {
+        let values = pixel.chunks_exact(size).map(channel).collect::<Vec<_>>();
+        *output = match values[..] {
+            [l] => Rgba([l, l, l, 255]),
+            [l, a] => Rgba([l, l, l, a]),
+            [r, g, b] => Rgba([r, g, b, 255]),
+            [r, g, b, a] => Rgba([r, g, b, a]),
+            _ => Rgba([0, 0, 0, 255]),
+        };
+    }
+
+    match data.pixels.len() >= (data.width * data.height) as usize * channels * size {
+        true => Some(image),
+        false => None,
+    }
+}
+
+fn texture_sampler(sampler: &gltf::texture::Sampler) -> Taro<Sampler> {
+    let address_mode = |mode| match mode {
+        WrappingMode::ClampToEdge => wgpu::AddressMode::ClampToEdge,
+        WrappingMode::MirroredRepeat => wgpu::AddressMode::MirrorRepeat,
+        WrappingMode::Repeat => wgpu::AddressMode::Repeat,
+    };
+
+    let defaults = SamplerSettings::default();
+    let mag_filter = match sampler.mag_filter() {
+        Some(MagFilter::Nearest) => wgpu::FilterMode::Nearest,
+        Some(MagFilter::Linear) => wgpu::FilterMode::Linear,
+        None => defaults.mag_filter,
+    };
+
+    let (min_filter, mipmap_filter) = match sampler.min_filter() {
+        Some(MinFilter::Nearest | MinFilter::NearestMipmapNearest) => {
+            (wgpu::FilterMode::Nearest, wgpu::FilterMode::Nearest)
+        }
+        Some(MinFilter::Linear | MinFilter::LinearMipmapNearest) => {
+            (wgpu::FilterMode::Linear, wgpu::FilterMode::Nearest)
+        }
+        Some(MinFilter::NearestMipmapLinear) => {
+            (wgpu::FilterMode::Nearest, wgpu::FilterMode::Linear)
+        }
+        Some(MinFilter::LinearMipmapLinear) => (wgpu::FilterMode::Linear, wgpu::FilterMode::Linear),
+        None => (defaults.min_filter, defaults.mipmap_filter),
+    };
+
+    Sampler::from_settings(SamplerSettings {
+        address_mode_u: address_mode(sampler.wrap_s()),
+        address_mode_v: address_mode(sampler.wrap_t()),
+        mag_filter,
+        min_filter,
+        mipmap_filter,
+        ..defaults
+    })
+}
+
+pub(super) fn material(
+    material: &gltf::Material,
+    textures: &mut TextureCache,
+) -> Result<GltfMaterial, GltfError> {
+    let name = match (material.name(), material.index()) {
+        (Some(name), _) => name.to_string(),
+        (None, Some(index)) => format!("material_{index}"),
+        (None, None) => "default".to_string(),
+    };
+
+    let pbr = material.pbr_metallic_roughness();
+    let [r, g, b] = material.emissive_factor();
+    let normal = material.normal_texture();
+    let occlusion = material.occlusion_texture();
+
+    let factors = PbrFactors {
+        albedo: pbr.base_color_factor(),
+        emissive: [r, g, b, 1.],
+        metallic: pbr.metallic_factor(),
+        roughness: pbr.roughness_factor(),
+        normal_scale: normal.as_ref().map_or(1., |normal| normal.scale()),
+        occlusion_strength: occlusion.as_ref().map_or(1., |o| o.strength()),
+    };
+
+    // the material only has one sampler, so the base color sampler is used for every map
+    let base_color = pbr.base_color_texture().map(|info| info.texture());
+    let sampler = match &base_color {
+        Some(texture) => texture_sampler(&texture.sampler()),
+        None => Sampler::new(),
+    };
+
+    let mut load = |texture: Option<gltf::Texture>, format| match texture {
+        Some(texture) => textures.get(&texture, format).map(Some),
+        None => Ok(None),
+    };
+
+    let metallic_roughness = pbr.metallic_roughness_texture().map(|info| info.texture());
+    let emissive = material.emissive_texture().map(|info| info.texture());
+    let material = PbrMaterial {
+        factors,
+        albedo_map: load(base_color, ImageFormat::Srgb)?,
+        normal_map: load(normal.map(|n| n.texture()), ImageFormat::Linear)?,
+        metallic_roughness_map: load(metallic_roughness, ImageFormat::Linear)?,
+        occlusion_map: load(occlusion.map(|o| o.texture()), ImageFormat::Linear)?,
+        emissive_map: load(emissive, ImageFormat::Srgb)?,
+        sampler,
+    };
+
+    Ok(GltfMaterial { name, material })
+}
+
+pub(super) fn mesh(
+    mesh: &gltf::Mesh,
+    buffers: &[gltf::buffer::Data],
+) -> Result<Taro<Mesh>, GltfError> {
+    let name = match mesh.name() {
+        Some(name) => name.to_string(),
+        None => format!("mesh_{}", mesh.index()),
+    };
+
+    let mut vertices = Vec::<Vertex>::new();
+    let mut indices = Vec::<u32>::new();
+    let mut submeshes = Vec::<SubMesh>::new();
+    for primitive in mesh.primitives() {
+        if primitive.mode() != Mode::Triangles {
+            warn!(
+                "Skipping primitive {} of '{name}'. Only triangles are supported.",
+                primitive.index()
+            );
+            continue;
+        }
+
+        let (primitive_vertices, primitive_indices) =
+            primitive_vertices(&name, &primitive, buffers)?;
+        let base = vertices.len() as u32;
+        let start = indices.len() as u32;
+        vertices.extend(primitive_vertices);
+        indices.extend(primitive_indices.iter().map(|index| index + base));
+
+        submeshes.push(SubMesh {
+            name: format!("{name}_{}", primitive.index()),
+            indices: start..indices.len() as u32,
+            material: primitive.material().index(),
+        });
+    }
+
+    let indices = MeshIndices::compact(&indices);
+    Ok(Mesh::from_submeshes(&vertices, indices, submeshes))
+}
+
+/// Reads the vertices of a primitive.
+///
+/// Missing normals are calculated flat and missing tangents are generated, as the glTF spec asks.
+fn primitive_vertices(
+    name: &str,
+    primitive: &gltf::Primitive,
+    buffers: &[gltf::buffer::Data],
+) -> Result<(Vec<Vertex>, Vec<u32>), GltfError> {
+    let reader = primitive.reader(|buffer| Some(&buffers.get(buffer.index())?.0));
+    let Some(positions) = reader.read_positions() else {
+        return Err(GltfError::MissingPositions {
+            mesh: name.into(),
+            primitive: primitive.index(),
+        });
+    };
+
+    let mut vertices = positions
+        .map(|position| Vertex {
+            position,
+            uv: [0., 0.],
+            normal: [0., 0., 0.],
+            tangent: [0., 0., 0., 1.],
+        })
+        .collect::<Vec<_>>();
+
+    if let Some(uvs) = reader.read_tex_coords(0) {
+        for (vertex, uv) in vertices.iter_mut().zip(uvs.into_f32()) {
+            vertex.uv = uv;
+        }
+    }
+
+    let mut indices = match reader.read_indices() {
+        Some(indices) => indices.into_u32().collect(),
+        None => (0..vertices.len() as u32).collect::<Vec<_>>(),
+    };
+
+    match reader.read_normals() {
+        Some(normals) => {
+            for (vertex, normal) in vertices.iter_mut().zip(normals) {
+                vertex.normal = normal;
+            }
+        }
+        None => {
+            let flat = Vertex::flat_normals(&vertices, &indices);
+            (vertices, indices) =
+                Vertex::deduplicate(&flat, &(0..flat.len() as u32).collect::<Vec<_>>());
+        }
+    }
+
+    match reader.read_tangents() {
+        Some(tangents) => {
+            for (vertex, tangent) in vertices.iter_mut().zip(tangents) {
+                vertex.tangent = tangent;
+            }
+        }
+        None => Vertex::generate_tangents(&mut vertices, &indices),
+    }
+
+    Ok((vertices, indices))
+}
+
+pub(super) fn node(node: &gltf::Node) -> GltfNode {
+    let (position, rotation, scale) = node.transform().decomposed();
+    GltfNode {
+        name: gltf_node_name(node),
+        position: Vec3::from(position),
+        rotation: Quat::from_array(rotation),
+        scale: Vec3::from(scale),
+        children: node.children().map(|child| child.index()).collect(),
+        mesh: node.mesh().map(|mesh| mesh.index()),
+        camera: node.camera().map(|camera| camera.index()),
+        light: node.light().map(|light| light.index()),
+        skin: node.skin().map(|skin| skin.index()),
+    }
+}
+
+/// Finds the nodes that are not the child of any other node
+pub(super) fn parentless_nodes(document: &gltf::Document) -> Vec<usize> {
+    let mut parentless = vec![true; document.nodes().len()];
+    for child in document.nodes().flat_map(|node| node.children()) {
+        parentless[child.index()] = false;
+    }
+
+    (0..parentless.len()).filter(|&i| parentless[i]).collect()
+}
+
+pub(super) fn camera(camera: &gltf::Camera) -> GltfCamera {
+    let defaults = TaroCameraSettings::default();
+    let settings = match camera.projection() {
+        gltf::camera::Projection::Perspective(perspective) => TaroCameraSettings::perspective(
+            perspective.yfov().to_degrees(),
+            perspective.znear(),
+            perspective.zfar().unwrap_or(defaults.zfar),
+        ),
+        gltf::camera::Projection::Orthographic(orthographic) => TaroCameraSettings::orthographic(
+            orthographic.ymag() * 2.,
+            orthographic.znear(),
+            orthographic.zfar(),
+        ),
+    };
+
+    let name = match camera.name() {
+        Some(name) => name.to_string(),
+        None => format!("camera_{}", camera.index()),
+    };
+
+    GltfCamera { name, settings }
+}
+
+pub(super) fn lights(document: &gltf::Document) -> Vec<GltfLight> {
+    let Some(lights) = document.lights() else {
+        return Vec::new();
+    };
+
+    lights
+        .map(|light| {
+            let kind = match light.kind() {
+                Kind::Directional => GltfLightKind::Directional,
+                Kind::Point => GltfLightKind::Point,
+                Kind::Spot {
+                    inner_cone_angle,
+                    outer_cone_angle,
+                } => GltfLightKind::Spot {
+                    inner_angle: inner_cone_angle.to_degrees(),
+                    outer_angle: outer_cone_angle.to_degrees(),
+                },
+            };
+
+            let name = match light.name() {
+                Some(name) => name.to_string(),
+                None => format!("light_{}", light.index()),
+            };
+
+            GltfLight {
+                name,
+                kind,
+                color: light.color(),
+                intensity: light.intensity(),
+                range: light.range(),
+            }
+        })
+        .collect()
+}
+
+pub(super) fn skin(skin: &gltf::Skin, buffers: &[gltf::buffer::Data]) -> GltfSkin {
+    let joints = skin.joints().map(|joint| joint.index()).collect::<Vec<_>>();
+    let reader = skin.reader(|buffer| Some(&buffers.get(buffer.index())?.0));
+
+    // the inverse bind matrices are the identity when they are left out
+    let inverse_bind_matrices = match reader.read_inverse_bind_matrices() {
+        Some(matrices) => matrices.map(|m| Mat4::from_cols_array_2d(&m)).collect(),
+        None => vec![Mat4::IDENTITY; joints.len()],
+    };
+
+    let name = match skin.name() {
+        Some(name) => name.to_string(),
+        None => format!("skin_{}", skin.index()),
+    };
+
+    GltfSkin {
+        name,
+        joints,
+        inverse_bind_matrices,
+    }
+}
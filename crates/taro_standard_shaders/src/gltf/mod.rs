@@ -0,0 +1,258 @@
+mod import;
+mod spawn;
+
+pub use spawn::*;
+
+use std::{path::Path, rc::Rc};
+
+use boba_3d::{
+    animation::{AnimationClip, GltfAnimationError},
+    glam::{Mat4, Quat, Vec3},
+};
+use taro_renderer::{
+    shading::{
+        data_types::{Mesh, Texture2D},
+        Taro,
+    },
+    TaroCameraSettings,
+};
+use thiserror::Error;
+
+use crate::PbrMaterial;
+
+/// An error returned when loading a [`GltfScene`]
+#[derive(Debug, Error)]
+pub enum GltfError {
+    #[error("Could not import glTF file. Error: {0}")]
+    Import(gltf::Error),
+    #[error("Primitive {primitive} of mesh '{mesh}' has no positions")]
+    MissingPositions { mesh: String, primitive: usize },
+    #[error("Image {0} could not be converted into a texture")]
+    InvalidImage(usize),
+    #[error("Could not load animations. Error: {0}")]
+    Animation(GltfAnimationError),
+}
+
+/// A material of a glTF file, converted into a [`PbrMaterial`]
+#[derive(Clone)]
+pub struct GltfMaterial {
+    pub name: String,
+    pub material: PbrMaterial,
+}
+
+/// A node in the hierarchy of a glTF file
+#[derive(Debug, Clone)]
+pub struct GltfNode {
+    /// The name of the node, which animation clips use to target it.
+    ///
+    /// Unnamed nodes are called `node_<index>`.
+    pub name: String,
+    pub position: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+    pub children: Vec<usize>,
+    pub mesh: Option<usize>,
+    pub camera: Option<usize>,
+    pub light: Option<usize>,
+    pub skin: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GltfCamera {
+    pub name: String,
+    pub settings: TaroCameraSettings,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GltfLightKind {
+    Directional,
+    Point,
+    /// A spot light, with the angles in degrees from the center of the cone
+    Spot {
+        inner_angle: f32,
+        outer_angle: f32,
+    },
+}
+
+/// A light from the `KHR_lights_punctual` extension.
+///
+/// The intensity is used as is, without converting from the physical units of glTF.
+#[derive(Debug, Clone)]
+pub struct GltfLight {
+    pub name: String,
+    pub kind: GltfLightKind,
+    pub color: [f32; 3],
+    pub intensity: f32,
+    /// The range of the light, or `None` if it should use the default range
+    pub range: Option<f32>,
+}
+
+/// The joints of a skin and the matrices that move the mesh into their local space.
+///
+/// Vertex skinning is not rendered, but the joints are spawned as part of the hierarchy
+/// so they can be animated.
+#[derive(Debug, Clone)]
+pub struct GltfSkin {
+    pub name: String,
+    pub joints: Vec<usize>,
+    pub inverse_bind_matrices: Vec<Mat4>,
+}
+
+/// The contents of a glTF or GLB file, ready to be spawned with [`GltfScene::spawn`].
+///
+/// Each glTF mesh becomes one [`Mesh`] with a [`SubMesh`](taro_renderer::shading::data_types::SubMesh)
+/// for every primitive. The indices of meshes, materials, nodes, cameras,
+/// lights and skins match the indices in the file.
+pub struct GltfScene {
+    pub meshes: Vec<Taro<Mesh>>,
+    /// Every texture used by the materials, with one texture for each image and color space
+    pub textures: Vec<Taro<Texture2D>>,
+    pub materials: Vec<GltfMaterial>,
+    pub nodes: Vec<GltfNode>,
+    /// The root nodes of the default scene, or of the first scene if there is no default
+    pub roots: Vec<usize>,
+    pub cameras: Vec<GltfCamera>,
+    pub lights: Vec<GltfLight>,
+    pub skins: Vec<GltfSkin>,
+    pub animations: Vec<Rc<AnimationClip>>,
+}
+
+impl GltfScene {
+    /// Loads the glTF or GLB file at `path`
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, GltfError> {
+        let (document, buffers, images) = gltf::import(path).map_err(GltfError::Import)?;
+        Self::from_gltf(&document, &buffers, &images)
+    }
+
+    /// Loads a glTF or GLB file from memory.
+    ///
+    /// Any external buffers or images have to be embedded as data uris.
+    pub fn from_slice(data: &[u8]) -> Result<Self, GltfError> {
+        let (document, buffers, images) = gltf::import_slice(data).map_err(GltfError::Import)?;
+        Self::from_gltf(&document, &buffers, &images)
+    }
+
+    /// Converts an already imported glTF document
+    pub fn from_gltf(
+        document: &gltf::Document,
+        buffers: &[gltf::buffer::Data],
+        images: &[gltf::image::Data],
+    ) -> Result<Self, GltfError> {
+        let mut textures = import::TextureCache::new(images);
+        let materials = document
+            .materials()
+            .map(|material| import::material(&material, &mut textures))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let meshes = document
+            .meshes()
+            .map(|mesh| import::mesh(&mesh, buffers))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let scene = document
+            .default_scene()
+            .or_else(|| document.scenes().next());
+        let roots = match scene {
+            Some(scene) => scene.nodes().map(|node| node.index()).collect(),
+            None => import::parentless_nodes(document),
+        };
+
+        let animations = AnimationClip::from_gltf(document, buffers)
+            .map_err(GltfError::Animation)?
+            .into_iter()
+            .map(Rc::new)
+            .collect();
+
+        Ok(Self {
+            meshes,
+            textures: textures.into_textures(),
+            materials,
+            nodes: document.nodes().map(|node| import::node(&node)).collect(),
+            roots,
+            cameras: document.cameras().map(|c| import::camera(&c)).collect(),
+            lights: import::lights(document),
+            skins: document
+                .skins()
+                .map(|s| import::skin(&s, buffers))
+                .collect(),
+            animations,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use boba_core::PearlRegistry;
+    use taro_renderer::{pearls::TaroMeshRenderer, TaroRenderPearls};
+
+    use super::*;
+    use crate::PbrShader;
+
+    // a root with a camera child and a mesh child, where the mesh has two triangle primitives
+    // and the second primitive uses the only material
+    const SCENE: &str = r#"{
+        "asset": { "version": "2.0" },
+        "scene": 0,
+        "scenes": [{ "nodes": [0] }],
+        "nodes": [
+            { "name": "root", "translation": [0.0, 1.0, 0.0], "children": [1, 2] },
+            { "name": "eye", "camera": 0 },
+            { "mesh": 0, "scale": [2.0, 2.0, 2.0] }
+        ],
+        "cameras": [{ "type": "perspective", "perspective": { "yfov": 1.0, "znear": 0.1, "zfar": 50.0 } }],
+        "materials": [{ "name": "red", "pbrMetallicRoughness": { "baseColorFactor": [1.0, 0.0, 0.0, 1.0] } }],
+        "meshes": [{
+            "name": "triangles",
+            "primitives": [
+                { "attributes": { "POSITION": 0 }, "indices": 1 },
+                { "attributes": { "POSITION": 0 }, "indices": 1, "material": 0 }
+            ]
+        }],
+        "buffers": [{
+            "byteLength": 44,
+            "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAAABAAIAAAA="
+        }],
+        "bufferViews": [
+            { "buffer": 0, "byteOffset": 0, "byteLength": 36 },
+            { "buffer": 0, "byteOffset": 36, "byteLength": 6 }
+        ],
+        "accessors": [
+            { "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3", "min": [0.0, 0.0, 0.0], "max": [1.0, 1.0, 0.0] },
+            { "bufferView": 1, "componentType": 5123, "count": 3, "type": "SCALAR" }
+        ]
+    }"#;
+
+    #[test]
+    fn load_scene() {
+        let scene = GltfScene::from_slice(SCENE.as_bytes()).unwrap();
+        assert!(scene.roots == [0]);
+        assert!(scene.nodes[0].children == [1, 2]);
+        assert!(scene.nodes[2].name == "node_2");
+        assert!(scene.cameras.len() == 1);
+        assert!(scene.materials[0].material.factors.albedo == [1., 0., 0., 1.]);
+
+        let mesh = &scene.meshes[0];
+        let submeshes = mesh.submeshes();
+        assert!(submeshes.len() == 2);
+        assert!(submeshes[0].material.is_none() && submeshes[1].material == Some(0));
+        assert!(submeshes[1].indices == (3..6));
+        assert!(mesh.vertices().iter().all(|v| v.normal == [0., 0., 1.]));
+    }
+
+    #[test]
+    fn spawn_scene() {
+        let scene = GltfScene::from_slice(SCENE.as_bytes()).unwrap();
+        let mut pearls = TaroRenderPearls::default();
+        let mut registry = PearlRegistry::default();
+        let instance = scene.spawn(&mut pearls, &mut registry);
+
+        assert!(instance.roots.len() == 1);
+        assert!(instance.cameras.len() == 1);
+        assert!(instance.renderers.len() == 2);
+        assert!(pearls.collect::<TaroMeshRenderer<PbrShader>>().len() == 2);
+
+        let mesh_node = instance.nodes[2].as_ref().unwrap().borrow().unwrap();
+        assert!(mesh_node.world_position() == Vec3::Y);
+        assert!(mesh_node.lossy_scale() == Vec3::splat(2.));
+    }
+}
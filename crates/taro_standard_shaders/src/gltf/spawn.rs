@@ -0,0 +1,202 @@
+use std::f32::consts::PI;
+
+use boba_3d::{
+    glam::{Quat, Vec3},
+    pearls::{AnimationPlayer, BobaTransform, SetTransformParent},
+};
+use boba_core::{Pearl, PearlRegistry};
+use log::error;
+use taro_renderer::{
+    pearls::{TaroDirectionalLight, TaroMeshRenderer, TaroPointLight, TaroSpotLight},
+    shading::Shader,
+    TaroCamera, TaroRenderPearls,
+};
+
+use crate::{passes::PbrRenderPass, PbrMaterial, PbrShader};
+
+use super::{GltfLightKind, GltfScene};
+
+/// The pearls created by [`GltfScene::spawn`]
+pub struct GltfInstance {
+    pub roots: Vec<Pearl<BobaTransform>>,
+    /// The transform of every node in the file, or `None` if it is not part of the spawned scene
+    pub nodes: Vec<Option<Pearl<BobaTransform>>>,
+    /// The renderers of every node with a mesh, with one renderer for each submesh
+    pub renderers: Vec<Pearl<TaroMeshRenderer<PbrShader>>>,
+    pub directional_lights: Vec<Pearl<TaroDirectionalLight>>,
+    pub point_lights: Vec<Pearl<TaroPointLight>>,
+    pub spot_lights: Vec<Pearl<TaroSpotLight>>,
+    /// The cameras of the scene, which render with a [`PbrRenderPass`].
+    ///
+    /// They are not rendered until they are added to the [`TaroCameras`](taro_renderer::TaroCameras).
+    pub cameras: Vec<TaroCamera>,
+    /// The joint transforms of every skin, in the same order as [`GltfSkin::joints`](super::GltfSkin)
+    pub skins: Vec<Vec<Pearl<BobaTransform>>>,
+    /// A player bound to every node, if the file has animations
+    pub animation_player: Option<Pearl<AnimationPlayer>>,
+}
+
+impl GltfScene {
+    /// Creates the node hierarchy of the scene, adding the renderers and lights to `pearls`
+    /// and the animation player to `registry`.
+    ///
+    /// The scene can be spawned many times, and every instance shares the same meshes and textures.
+    pub fn spawn(
+        &self,
+        pearls: &mut TaroRenderPearls,
+        registry: &mut PearlRegistry,
+    ) -> GltfInstance {
+        let mut instance = GltfInstance {
+            roots: Vec::new(),
+            nodes: vec![None; self.nodes.len()],
+            renderers: Vec::new(),
+            directional_lights: Vec::new(),
+            point_lights: Vec::new(),
+            spot_lights: Vec::new(),
+            cameras: Vec::new(),
+            skins: Vec::new(),
+            animation_player: None,
+        };
+
+        for &root in self.roots.iter() {
+            if let Some(transform) = self.spawn_node(root, None, pearls, &mut instance) {
+                instance.roots.push(transform);
+            }
+        }
+
+        instance.skins = self
+            .skins
+            .iter()
+            .map(|skin| {
+                let joints = skin.joints.iter();
+                let joints = joints.filter_map(|&joint| instance.nodes.get(joint)?.clone());
+                joints.collect()
+            })
+            .collect();
+
+        if !self.animations.is_empty() {
+            let mut player = AnimationPlayer::new();
+            for (node, transform) in self.nodes.iter().zip(instance.nodes.iter()) {
+                if let Some(transform) = transform {
+                    player.bind(&node.name, transform.clone());
+                }
+            }
+
+            let player = Pearl::wrap(player);
+            registry.add(&player);
+            instance.animation_player = Some(player);
+        }
+
+        instance
+    }
+
+    fn spawn_node(
+        &self,
+        index: usize,
+        parent: Option<&Pearl<BobaTransform>>,
+        pearls: &mut TaroRenderPearls,
+        instance: &mut GltfInstance,
+    ) -> Option<Pearl<BobaTransform>> {
+        let node = self.nodes.get(index)?;
+        // a node that was already spawned would make a cycle
+        if instance.nodes[index].is_some() {
+            error!(
+                "Skipping glTF node '{}'. It is part of the scene twice.",
+                node.name
+            );
+            return None;
+        }
+
+        let transform = BobaTransform::new(node.position, node.rotation, node.scale);
+        let transform = Pearl::wrap(transform);
+        if let Some(parent) = parent {
+            attach(&transform, parent);
+        }
+        instance.nodes[index] = Some(transform.clone());
+
+        if let Some(mesh) = node.mesh.and_then(|mesh| self.meshes.get(mesh)) {
+            for (submesh_index, submesh) in mesh.submeshes().iter().enumerate() {
+                let material = submesh.material.and_then(|m| self.materials.get(m));
+                let material = material.map_or_else(PbrMaterial::default, |m| m.material.clone());
+                let shader = Shader::<PbrShader>::new(material);
+                let renderer = TaroMeshRenderer::new(transform.clone(), mesh.clone(), shader)
+                    .with_submesh(submesh_index);
+
+                let renderer = Pearl::wrap(renderer);
+                pearls.add(renderer.clone());
+                instance.renderers.push(renderer);
+            }
+        }
+
+        if let Some(camera) = node.camera.and_then(|camera| self.cameras.get(camera)) {
+            let mut camera = TaroCamera::new(facing_child(&transform), camera.settings.clone());
+            camera.passes.append(PbrRenderPass::default());
+            instance.cameras.push(camera);
+        }
+
+        if let Some(light) = node.light.and_then(|light| self.lights.get(light)) {
+            let facing = facing_child(&transform);
+            match light.kind {
+                GltfLightKind::Directional => {
+                    let light = TaroDirectionalLight::new(facing)
+                        .with_color(light.color)
+                        .with_intensity(light.intensity);
+
+                    let light = Pearl::wrap(light);
+                    pearls.add(light.clone());
+                    instance.directional_lights.push(light);
+                }
+                GltfLightKind::Point => {
+                    let mut point = TaroPointLight::new(facing)
+                        .with_color(light.color)
+                        .with_intensity(light.intensity);
+                    if let Some(range) = light.range {
+                        point = point.with_range(range);
+                    }
+
+                    let light = Pearl::wrap(point);
+                    pearls.add(light.clone());
+                    instance.point_lights.push(light);
+                }
+                GltfLightKind::Spot {
+                    inner_angle,
+                    outer_angle,
+                } => {
+                    let mut spot = TaroSpotLight::new(facing)
+                        .with_color(light.color)
+                        .with_intensity(light.intensity)
+                        .with_angles(inner_angle, outer_angle);
+                    if let Some(range) = light.range {
+                        spot = spot.with_range(range);
+                    }
+
+                    let light = Pearl::wrap(spot);
+                    pearls.add(light.clone());
+                    instance.spot_lights.push(light);
+                }
+            }
+        }
+
+        for &child in node.children.iter() {
+            self.spawn_node(child, Some(&transform), pearls, instance);
+        }
+
+        Some(transform)
+    }
+}
+
+fn attach(child: &Pearl<BobaTransform>, parent: &Pearl<BobaTransform>) {
+    if let Err(e) = child.set_parent(parent.clone(), false) {
+        error!("Could not attach glTF node to its parent. Error: {e}");
+    }
+}
+
+/// Creates a child transform that faces along the node's `-Z` axis.
+///
+/// Cameras and lights face `-Z` in glTF, while taro uses `+Z` as forward.
+fn facing_child(parent: &Pearl<BobaTransform>) -> Pearl<BobaTransform> {
+    let rotation = Quat::from_rotation_y(PI);
+    let child = Pearl::wrap(BobaTransform::new(Vec3::ZERO, rotation, Vec3::ONE));
+    attach(&child, parent);
+    child
+}
@@ -1,5 +1,9 @@
+mod pbr;
 mod unlit;
 
+pub use pbr::*;
 pub use unlit::*;
 
+pub mod gltf;
 pub mod passes;
+pub mod post;
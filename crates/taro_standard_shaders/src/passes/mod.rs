@@ -1,3 +1,5 @@
+mod pbr;
 mod unlit;
 
+pub use pbr::*;
 pub use unlit::*;
@@ -0,0 +1,251 @@
+use boba_3d::glam::Vec3;
+use taro_renderer::{
+    pearls::TaroMeshRenderer,
+    shading::{
+        buffers::{
+            CameraMatrix, LightArray, ShadowArray, TaroBytesBuilder, TransformMatrix, UniformBuffer,
+        },
+        data_types::{DepthArrayView, Sampler, SamplerSettings},
+        Bind, BindGroup, BindGroupBuilder, BindSingle, Taro,
+    },
+    wgpu, TaroAttachment, TaroHardware, TaroPassBuilder, TaroPassContext, TaroRenderPass,
+};
+
+use crate::PbrShader;
+
+#[repr(C)]
+#[derive(Default, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct PbrSceneData {
+    camera_position: [f32; 4],
+    camera_forward: [f32; 4],
+    ambient: [f32; 4],
+}
+
+impl TaroBytesBuilder for PbrSceneData {
+    fn build_bytes(&self) -> &[u8] {
+        bytemuck::bytes_of(self)
+    }
+}
+
+/// Renders every [`TaroMeshRenderer<PbrShader>`] into the scene color,
+/// lit by the lights in the [`TaroRenderPearls`](taro_renderer::TaroRenderPearls).
+///
+/// The shadow maps of the lights are rendered in a depth only pass first.
+pub struct PbrRenderPass {
+    /// The light that reaches every surface from all directions
+    pub ambient: [f32; 3],
+    /// The most lights that affect the scene, up to [`LightArray::MAX_LIGHTS`]
+    pub light_limit: usize,
+    /// The width and height in texels of every shadow map
+    pub shadow_resolution: u32,
+    lights: Box<LightArray>,
+    shadows: Box<ShadowArray>,
+    scene_data: Taro<UniformBuffer<PbrSceneData>>,
+    light_data: Taro<UniformBuffer<LightArray>>,
+    shadow_data: Taro<UniformBuffer<ShadowArray>>,
+    shadow_maps: Taro<DepthArrayView>,
+    shadow_sampler: Taro<Sampler>,
+    light_matrices: Vec<Taro<BindSingle<UniformBuffer<CameraMatrix>>>>,
+    scene: Taro<BindGroup>,
+}
+
+impl Default for PbrRenderPass {
+    fn default() -> Self {
+        let shadows = Box::<ShadowArray>::default();
+        let shadow_resolution = shadows.resolution();
+        let shadow_sampler = Sampler::from_settings(SamplerSettings {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            compare: Some(wgpu::CompareFunction::LessEqual),
+            ..Default::default()
+        });
+
+        let scene_data = UniformBuffer::new();
+        let light_data = UniformBuffer::new();
+        let shadow_data = UniformBuffer::new();
+        let shadow_maps = DepthArrayView::new((shadow_resolution, shadow_resolution), 1);
+        let scene = scene_bind_group(
+            &scene_data,
+            &light_data,
+            &shadow_data,
+            &shadow_maps,
+            &shadow_sampler,
+        );
+
+        Self {
+            ambient: [0.03; 3],
+            light_limit: LightArray::MAX_LIGHTS,
+            shadow_resolution,
+            lights: Default::default(),
+            shadows,
+            scene_data,
+            light_data,
+            shadow_data,
+            shadow_maps,
+            shadow_sampler,
+            light_matrices: Vec::new(),
+            scene,
+        }
+    }
+}
+
+impl PbrRenderPass {
+    /// Grows the shadow texture when there are more maps than layers, or the resolution changed
+    fn prepare_shadow_maps(&mut self) {
+        let resolution = self.shadow_resolution.max(1);
+        let layers = self.shadows.len().max(1) as u32;
+        let size = self.shadow_maps.size();
+        if size == (resolution, resolution) && self.shadow_maps.layers() >= layers {
+            return;
+        }
+
+        self.shadow_maps = DepthArrayView::new((resolution, resolution), layers);
+        self.scene = scene_bind_group(
+            &self.scene_data,
+            &self.light_data,
+            &self.shadow_data,
+            &self.shadow_maps,
+            &self.shadow_sampler,
+        );
+    }
+
+    fn render_shadows(
+        &mut self,
+        renderers: &[std::cell::Ref<TaroMeshRenderer<PbrShader>>],
+        encoder: &mut wgpu::CommandEncoder,
+        hardware: &TaroHardware,
+    ) {
+        // the model matrices are written before any pass uses them this frame
+        for renderer in renderers.iter() {
+            let Ok(transform) = renderer.transform.borrow() else {
+                continue;
+            };
+
+            let matrix = TransformMatrix::from(transform.world_matrix());
+            let shader = renderer.shader.get_or_compile(hardware);
+            shader.model_matrix.data().write_buffer(&matrix, hardware);
+        }
+
+        let layer_views = &self.shadow_maps.get_or_compile(hardware).layer_views;
+        for (index, map) in self.shadows.maps().iter().enumerate() {
+            if self.light_matrices.len() <= index {
+                let matrix = Bind::new(UniformBuffer::new(), wgpu::ShaderStages::VERTEX);
+                self.light_matrices.push(matrix);
+            }
+
+            let light_matrix = &self.light_matrices[index];
+            let data = CameraMatrix::from(map.matrix());
+            light_matrix.data().write_buffer(&data, hardware);
+            let light_bind = light_matrix.get_or_compile(hardware);
+
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Pbr Shadow Pass"),
+                color_attachments: &[],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &layer_views[index],
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: true,
+                    }),
+                    stencil_ops: None,
+                }),
+            });
+
+            pass.set_bind_group(0, &light_bind.bind_group, &[]);
+            for renderer in renderers.iter() {
+                let mesh = renderer.mesh.get_or_compile(hardware);
+                let shader = renderer.shader.get_or_compile(hardware);
+                shader.render_shadow(&mut pass, mesh, renderer.indices(mesh), hardware);
+            }
+        }
+    }
+}
+
+fn scene_bind_group(
+    scene_data: &Taro<UniformBuffer<PbrSceneData>>,
+    light_data: &Taro<UniformBuffer<LightArray>>,
+    shadow_data: &Taro<UniformBuffer<ShadowArray>>,
+    shadow_maps: &Taro<DepthArrayView>,
+    shadow_sampler: &Taro<Sampler>,
+) -> Taro<BindGroup> {
+    let fragment = wgpu::ShaderStages::FRAGMENT;
+    BindGroupBuilder::new()
+        .insert(0, Bind::new(scene_data.clone(), fragment))
+        .insert(1, Bind::new(light_data.clone(), fragment))
+        .insert(2, Bind::new(shadow_data.clone(), fragment))
+        .insert(3, Bind::new(shadow_maps.clone(), fragment))
+        .insert(4, Bind::new(shadow_sampler.clone(), fragment))
+        .build()
+}
+
+impl TaroRenderPass for PbrRenderPass {
+    fn attachments(&self, builder: &mut TaroPassBuilder) {
+        builder
+            .write(TaroAttachment::COLOR)
+            .write(TaroAttachment::DEPTH);
+    }
+
+    fn render(&mut self, context: &mut TaroPassContext) {
+        let (Some(view), Some(depth)) = (
+            context.attachment(TaroAttachment::COLOR),
+            context.attachment(TaroAttachment::DEPTH),
+        ) else {
+            return;
+        };
+
+        let camera = context.camera;
+        let forward = camera.view_matrix.inverse().transform_vector3(Vec3::NEG_Z);
+        let scene = PbrSceneData {
+            camera_position: camera.position.extend(1.).to_array(),
+            camera_forward: forward.normalize_or_zero().extend(0.).to_array(),
+            ambient: [self.ambient[0], self.ambient[1], self.ambient[2], 1.],
+        };
+        self.scene_data.write_buffer(&scene, context.hardware);
+
+        self.shadows.set_resolution(self.shadow_resolution);
+        let limit = self.light_limit;
+        self.lights
+            .gather(context.pearls, camera, limit, &mut self.shadows);
+        self.light_data.write_buffer(&self.lights, context.hardware);
+        self.shadow_data
+            .write_buffer(&self.shadows, context.hardware);
+        self.prepare_shadow_maps();
+
+        {
+            let renderers = context.pearls.collect::<TaroMeshRenderer<PbrShader>>();
+            self.render_shadows(&renderers, context.encoder, context.hardware);
+        }
+
+        let scene_bind = self.scene.get_or_compile(context.hardware);
+        let load = context.color_load(TaroAttachment::COLOR, wgpu::Color::TRANSPARENT);
+        let depth_stencil_attachment = Some(wgpu::RenderPassDepthStencilAttachment {
+            view: depth,
+            depth_ops: Some(wgpu::Operations {
+                load: context.depth_load(TaroAttachment::DEPTH),
+                store: true,
+            }),
+            stencil_ops: None,
+        });
+
+        let mut pbr_renderers = context.pearls.collect_mut::<TaroMeshRenderer<PbrShader>>();
+        let mut pass = context
+            .encoder
+            .begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Pbr Render Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations { load, store: true },
+                })],
+                depth_stencil_attachment,
+            });
+
+        context.region.apply(&mut pass);
+        pass.set_bind_group(3, &scene_bind.bind_group, &[]);
+        for renderer in pbr_renderers.iter_mut() {
+            renderer.render(&mut pass, camera, context.hardware);
+        }
+    }
+}
@@ -1,7 +1,6 @@
 use taro_renderer::{
-    pearls::TaroMeshRenderer,
-    shading::{buffers::CameraMatrix, data_types::DepthView, Taro},
-    wgpu, TaroRenderPass,
+    pearls::TaroMeshRenderer, wgpu, TaroAttachment, TaroPassBuilder, TaroPassContext,
+    TaroRenderPass,
 };
 
 use crate::UnlitShader;
@@ -9,40 +8,48 @@ use crate::UnlitShader;
 pub struct UnlitRenderPass;
 
 impl TaroRenderPass for UnlitRenderPass {
-    fn render(
-        &mut self,
-        pearls: &taro_renderer::TaroRenderPearls,
-        camera_matrix: &CameraMatrix,
-        view: &taro_renderer::wgpu::TextureView,
-        depth: &Taro<DepthView>,
-        encoder: &mut taro_renderer::wgpu::CommandEncoder,
-        hardware: &taro_renderer::TaroHardware,
-    ) {
+    fn attachments(&self, builder: &mut TaroPassBuilder) {
+        builder
+            .write(TaroAttachment::COLOR)
+            .write(TaroAttachment::DEPTH);
+    }
+
+    fn render(&mut self, context: &mut TaroPassContext) {
+        let (Some(view), Some(depth)) = (
+            context.attachment(TaroAttachment::COLOR),
+            context.attachment(TaroAttachment::DEPTH),
+        ) else {
+            return;
+        };
+
+        let load = context.color_load(TaroAttachment::COLOR, wgpu::Color::TRANSPARENT);
         let depth_stencil_attachment = Some(wgpu::RenderPassDepthStencilAttachment {
-            view: depth.get_or_compile(hardware),
+            view: depth,
             depth_ops: Some(wgpu::Operations {
-                load: wgpu::LoadOp::Clear(1.0),
+                load: context.depth_load(TaroAttachment::DEPTH),
                 store: true,
             }),
             stencil_ops: None,
         });
 
-        let mut unlit_renderers = pearls.collect_mut::<TaroMeshRenderer<UnlitShader>>();
-        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-            label: Some("Unlit Render Pass"),
-            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                view,
-                resolve_target: None,
-                ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Load,
-                    store: true,
-                },
-            })],
-            depth_stencil_attachment,
-        });
+        let mut unlit_renderers = context
+            .pearls
+            .collect_mut::<TaroMeshRenderer<UnlitShader>>();
+        let mut pass = context
+            .encoder
+            .begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Unlit Render Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations { load, store: true },
+                })],
+                depth_stencil_attachment,
+            });
 
+        context.region.apply(&mut pass);
         for renderer in unlit_renderers.iter_mut() {
-            renderer.render(&mut pass, camera_matrix, hardware);
+            renderer.render(&mut pass, context.camera, context.hardware);
         }
     }
 }
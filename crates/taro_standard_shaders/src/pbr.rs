@@ -0,0 +1,362 @@
+use std::ops::Range;
+
+use once_cell::sync::OnceCell;
+use taro_renderer::{
+    shading::{
+        buffers::{TaroBytesBuilder, TransformMatrix, UniformBuffer, LIGHTS_PRELUDE},
+        data_types::{DepthView, ImageFormat, MeshBuffer, Sampler, Texture2DView, Vertex},
+        Bind, BindGroup, BindGroupBuilder, BindSingle, Taro, TaroCoreShader, TaroMeshShader,
+    },
+    wgpu, TaroAttachment, TaroCameraView, TaroHardware,
+};
+
+static PIPELINE: OnceCell<wgpu::RenderPipeline> = OnceCell::new();
+static SHADOW_PIPELINE: OnceCell<wgpu::RenderPipeline> = OnceCell::new();
+static MATRIX_LAYOUT: OnceCell<wgpu::BindGroupLayout> = OnceCell::new();
+static SCENE_LAYOUT: OnceCell<wgpu::BindGroupLayout> = OnceCell::new();
+
+/// The uniform values of a [`PbrMaterial`].
+///
+/// Each value is multiplied with its map, so they are used directly when a map is missing.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct PbrFactors {
+    pub albedo: [f32; 4],
+    /// The emissive color, where the last component is unused
+    pub emissive: [f32; 4],
+    pub metallic: f32,
+    pub roughness: f32,
+    pub normal_scale: f32,
+    pub occlusion_strength: f32,
+}
+
+impl Default for PbrFactors {
+    fn default() -> Self {
+        Self {
+            albedo: [1.; 4],
+            emissive: [0.; 4],
+            metallic: 0.,
+            roughness: 0.5,
+            normal_scale: 1.,
+            occlusion_strength: 1.,
+        }
+    }
+}
+
+impl TaroBytesBuilder for PbrFactors {
+    fn build_bytes(&self) -> &[u8] {
+        bytemuck::bytes_of(self)
+    }
+}
+
+/// A metallic roughness material.
+///
+/// The maps follow the gltf conventions. The metallic roughness map stores roughness in
+/// the green channel and metalness in the blue channel, and the occlusion map uses the red channel.
+/// The normal, metallic roughness and occlusion maps should be loaded with [`ImageFormat::Linear`].
+#[derive(Clone)]
+pub struct PbrMaterial {
+    pub factors: PbrFactors,
+    pub albedo_map: Option<Taro<Texture2DView>>,
+    pub normal_map: Option<Taro<Texture2DView>>,
+    pub metallic_roughness_map: Option<Taro<Texture2DView>>,
+    pub occlusion_map: Option<Taro<Texture2DView>>,
+    /// The emissive map, which is multiplied with the emissive factor that defaults to black
+    pub emissive_map: Option<Taro<Texture2DView>>,
+    pub sampler: Taro<Sampler>,
+}
+
+impl Default for PbrMaterial {
+    fn default() -> Self {
+        Self {
+            factors: Default::default(),
+            albedo_map: None,
+            normal_map: None,
+            metallic_roughness_map: None,
+            occlusion_map: None,
+            emissive_map: None,
+            sampler: Sampler::new(),
+        }
+    }
+}
+
+impl PbrMaterial {
+    pub fn new(factors: PbrFactors) -> Self {
+        Self {
+            factors,
+            ..Default::default()
+        }
+    }
+}
+
+pub struct PbrShader {
+    pub material: Taro<BindGroup>,
+    pub model_matrix: Taro<BindSingle<UniformBuffer<TransformMatrix>>>,
+    factors: Taro<UniformBuffer<PbrFactors>>,
+}
+
+impl PbrShader {
+    /// Renders the depth of `mesh` into a shadow map.
+    ///
+    /// The light matrix has to be bound to group 0 before this is called.
+    pub fn render_shadow<'pass>(
+        &'pass self,
+        pass: &mut wgpu::RenderPass<'pass>,
+        mesh: &'pass MeshBuffer,
+        indices: Range<u32>,
+        hardware: &TaroHardware,
+    ) {
+        let model_bind = self.model_matrix.get_or_compile(hardware);
+
+        pass.set_pipeline(SHADOW_PIPELINE.get().unwrap());
+        pass.set_bind_group(1, &model_bind.bind_group, &[]);
+        pass.set_vertex_buffer(0, mesh.vertex_buffer().raw_buffer().slice(..));
+        let index_buffer = mesh.index_buffer();
+        pass.set_index_buffer(index_buffer.raw_buffer().slice(..), index_buffer.format());
+        pass.draw_indexed(indices, 0, 0..1);
+    }
+
+    /// Updates the uniform values of the material
+    pub fn set_factors(&self, factors: &PbrFactors, hardware: &TaroHardware) {
+        self.factors.write_buffer(factors, hardware);
+    }
+}
+
+impl TaroCoreShader for PbrShader {
+    type InitParameters = PbrMaterial;
+
+    fn build_instance(init: &PbrMaterial, hardware: &TaroHardware) -> Self {
+        let matrix_layout = MATRIX_LAYOUT.get_or_init(|| {
+            hardware
+                .device()
+                .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    entries: &[wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::VERTEX,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    }],
+                    label: Some("Pbr Shader Mat4 Bind Group"),
+                })
+        });
+
+        // the scene bind group is set once per pass by the PbrRenderPass
+        let scene_layout = SCENE_LAYOUT.get_or_init(|| {
+            let entry = |binding, ty| wgpu::BindGroupLayoutEntry {
+                binding,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty,
+                count: None,
+            };
+            let uniform = wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            };
+
+            hardware
+                .device()
+                .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    entries: &[
+                        entry(0, uniform),
+                        entry(1, uniform),
+                        entry(2, uniform),
+                        entry(
+                            3,
+                            wgpu::BindingType::Texture {
+                                multisampled: false,
+                                view_dimension: wgpu::TextureViewDimension::D2Array,
+                                sample_type: wgpu::TextureSampleType::Depth,
+                            },
+                        ),
+                        entry(
+                            4,
+                            wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Comparison),
+                        ),
+                    ],
+                    label: Some("Pbr Shader Scene Bind Group"),
+                })
+        });
+
+        // missing maps are replaced with a single pixel that leaves the factors unchanged
+        let map = |map: &Option<Taro<Texture2DView>>, color: [u8; 4], format: ImageFormat| {
+            let view = match map {
+                Some(view) => view.clone(),
+                None => Texture2DView::from_color(color, format),
+            };
+            Bind::new(view, wgpu::ShaderStages::FRAGMENT)
+        };
+
+        let factors = UniformBuffer::<PbrFactors>::new();
+        factors.write_buffer(&init.factors, hardware);
+
+        let material = BindGroupBuilder::new()
+            .insert(0, Bind::new(factors.clone(), wgpu::ShaderStages::FRAGMENT))
+            .insert(
+                1,
+                Bind::new(init.sampler.clone(), wgpu::ShaderStages::FRAGMENT),
+            )
+            .insert(2, map(&init.albedo_map, [255; 4], ImageFormat::Srgb))
+            .insert(
+                3,
+                map(&init.normal_map, [128, 128, 255, 255], ImageFormat::Linear),
+            )
+            .insert(
+                4,
+                map(&init.metallic_roughness_map, [255; 4], ImageFormat::Linear),
+            )
+            .insert(5, map(&init.occlusion_map, [255; 4], ImageFormat::Linear))
+            .insert(6, map(&init.emissive_map, [255; 4], ImageFormat::Srgb))
+            .build();
+
+        PIPELINE.get_or_init(|| {
+            let pipeline_layout =
+                hardware
+                    .device()
+                    .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                        label: Some("Pbr Pipeline Layout"),
+                        bind_group_layouts: &[
+                            matrix_layout,
+                            matrix_layout,
+                            &material.get_or_compile(hardware).layout,
+                            scene_layout,
+                        ],
+                        push_constant_ranges: &[],
+                    });
+
+            let source = format!("{LIGHTS_PRELUDE}\n{}", include_str!("pbr.wgsl"));
+            let module = &hardware
+                .device()
+                .create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: Some("Pbr Shader"),
+                    source: wgpu::ShaderSource::Wgsl(source.into()),
+                });
+
+            hardware
+                .device()
+                .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                    label: Some("Pbr Render Pipeline"),
+                    layout: Some(&pipeline_layout),
+                    vertex: wgpu::VertexState {
+                        module,
+                        entry_point: "vs_main",
+                        buffers: &[Vertex::BUFFER_LAYOUT],
+                    },
+                    fragment: Some(wgpu::FragmentState {
+                        module,
+                        entry_point: "fs_main",
+                        targets: &[Some(wgpu::ColorTargetState {
+                            format: TaroAttachment::COLOR_FORMAT,
+                            blend: Some(wgpu::BlendState::REPLACE),
+                            write_mask: wgpu::ColorWrites::ALL,
+                        })],
+                    }),
+                    primitive: wgpu::PrimitiveState {
+                        topology: wgpu::PrimitiveTopology::TriangleList,
+                        strip_index_format: None,
+                        front_face: wgpu::FrontFace::Ccw,
+                        cull_mode: None,
+                        polygon_mode: wgpu::PolygonMode::Fill,
+                        unclipped_depth: false,
+                        conservative: false,
+                    },
+                    depth_stencil: Some(wgpu::DepthStencilState {
+                        format: DepthView::FORMAT,
+                        depth_write_enabled: true,
+                        depth_compare: wgpu::CompareFunction::Less,
+                        stencil: wgpu::StencilState::default(),
+                        bias: wgpu::DepthBiasState::default(),
+                    }),
+                    multisample: wgpu::MultisampleState {
+                        count: 1,
+                        mask: !0,
+                        alpha_to_coverage_enabled: false,
+                    },
+                    multiview: None,
+                })
+        });
+
+        SHADOW_PIPELINE.get_or_init(|| {
+            let pipeline_layout =
+                hardware
+                    .device()
+                    .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                        label: Some("Pbr Shadow Pipeline Layout"),
+                        bind_group_layouts: &[matrix_layout, matrix_layout],
+                        push_constant_ranges: &[],
+                    });
+
+            let module = &hardware
+                .device()
+                .create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: Some("Pbr Shadow Shader"),
+                    source: wgpu::ShaderSource::Wgsl(include_str!("pbr_shadow.wgsl").into()),
+                });
+
+            hardware
+                .device()
+                .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                    label: Some("Pbr Shadow Pipeline"),
+                    layout: Some(&pipeline_layout),
+                    vertex: wgpu::VertexState {
+                        module,
+                        entry_point: "vs_main",
+                        buffers: &[Vertex::BUFFER_LAYOUT],
+                    },
+                    fragment: None,
+                    primitive: wgpu::PrimitiveState::default(),
+                    depth_stencil: Some(wgpu::DepthStencilState {
+                        format: DepthView::FORMAT,
+                        depth_write_enabled: true,
+                        depth_compare: wgpu::CompareFunction::Less,
+                        stencil: wgpu::StencilState::default(),
+                        bias: wgpu::DepthBiasState {
+                            constant: 2,
+                            slope_scale: 2.0,
+                            clamp: 0.0,
+                        },
+                    }),
+                    multisample: wgpu::MultisampleState::default(),
+                    multiview: None,
+                })
+        });
+
+        Self {
+            material,
+            model_matrix: Bind::new(UniformBuffer::new(), wgpu::ShaderStages::VERTEX),
+            factors,
+        }
+    }
+}
+
+impl TaroMeshShader for PbrShader {
+    fn set_model_matrix(&self, data: &TransformMatrix, hardware: &TaroHardware) {
+        self.model_matrix.data().write_buffer(data, hardware);
+    }
+
+    fn render<'pass>(
+        &'pass self,
+        pass: &mut wgpu::RenderPass<'pass>,
+        mesh: &'pass MeshBuffer,
+        indices: Range<u32>,
+        camera: &'pass TaroCameraView,
+        hardware: &TaroHardware,
+    ) {
+        let camera_bind = camera.binding.get_or_compile(hardware);
+        let model_bind = self.model_matrix.get_or_compile(hardware);
+        let material_bind = self.material.get_or_compile(hardware);
+
+        pass.set_pipeline(PIPELINE.get().unwrap());
+        pass.set_bind_group(0, &camera_bind.bind_group, &[]);
+        pass.set_bind_group(1, &model_bind.bind_group, &[]);
+        pass.set_bind_group(2, &material_bind.bind_group, &[]);
+        pass.set_vertex_buffer(0, mesh.vertex_buffer().raw_buffer().slice(..));
+        let index_buffer = mesh.index_buffer();
+        pass.set_index_buffer(index_buffer.raw_buffer().slice(..), index_buffer.format());
+        pass.draw_indexed(indices, 0, 0..1);
+    }
+}
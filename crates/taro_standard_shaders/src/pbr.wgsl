@@ -0,0 +1,202 @@
+@group(0) @binding(0)
+var<uniform> camera_matrix: mat4x4<f32>;
+@group(1) @binding(0)
+var<uniform> model_matrix: mat4x4<f32>;
+
+struct PbrFactors {
+    albedo: vec4<f32>,
+    emissive: vec4<f32>,
+    metallic: f32,
+    roughness: f32,
+    normal_scale: f32,
+    occlusion_strength: f32,
+}
+
+@group(2) @binding(0)
+var<uniform> factors: PbrFactors;
+@group(2) @binding(1)
+var material_sampler: sampler;
+@group(2) @binding(2)
+var albedo_map: texture_2d<f32>;
+@group(2) @binding(3)
+var normal_map: texture_2d<f32>;
+@group(2) @binding(4)
+var metallic_roughness_map: texture_2d<f32>;
+@group(2) @binding(5)
+var occlusion_map: texture_2d<f32>;
+@group(2) @binding(6)
+var emissive_map: texture_2d<f32>;
+
+struct SceneData {
+    camera_position: vec4<f32>,
+    camera_forward: vec4<f32>,
+    ambient: vec4<f32>,
+}
+
+@group(3) @binding(0)
+var<uniform> scene: SceneData;
+@group(3) @binding(1)
+var<uniform> lights: TaroLights;
+@group(3) @binding(2)
+var<uniform> shadows: TaroShadows;
+@group(3) @binding(3)
+var shadow_maps: texture_depth_2d_array;
+@group(3) @binding(4)
+var shadow_sampler: sampler_comparison;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) tex_coords: vec2<f32>,
+    @location(2) normal: vec3<f32>,
+    @location(3) tangent: vec4<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) world_position: vec3<f32>,
+    @location(1) tex_coords: vec2<f32>,
+    @location(2) world_normal: vec3<f32>,
+    @location(3) world_tangent: vec4<f32>,
+}
+
+@vertex
+fn vs_main(
+    model: VertexInput,
+) -> VertexOutput {
+    let world_position = model_matrix * vec4<f32>(model.position, 1.0);
+
+    var out: VertexOutput;
+    out.world_position = world_position.xyz;
+    out.tex_coords = model.tex_coords;
+    // this assumes a uniform scale, which keeps normals perpendicular to the surface
+    out.world_normal = (model_matrix * vec4<f32>(model.normal, 0.0)).xyz;
+    out.world_tangent = vec4<f32>((model_matrix * vec4<f32>(model.tangent.xyz, 0.0)).xyz, model.tangent.w);
+    out.clip_position = camera_matrix * world_position;
+    return out;
+}
+
+let PI: f32 = 3.14159265359;
+
+// applies the tangent space normal using the vertex tangent frame
+fn perturb_normal(normal: vec3<f32>, tangent: vec4<f32>, sample: vec3<f32>) -> vec3<f32> {
+    // re-orthogonalize, as the interpolated tangent may have drifted from the normal
+    let t = normalize(tangent.xyz - normal * dot(normal, tangent.xyz));
+    let bitangent = cross(normal, t) * tangent.w;
+    let tbn = mat3x3<f32>(t, bitangent, normal);
+    return normalize(tbn * sample);
+}
+
+fn distribution_ggx(n_dot_h: f32, roughness: f32) -> f32 {
+    let a = roughness * roughness;
+    let a2 = a * a;
+    let denom = n_dot_h * n_dot_h * (a2 - 1.0) + 1.0;
+    return a2 / (PI * denom * denom);
+}
+
+fn geometry_smith(n_dot_v: f32, n_dot_l: f32, roughness: f32) -> f32 {
+    let r = roughness + 1.0;
+    let k = r * r / 8.0;
+    let ggx_v = n_dot_v / (n_dot_v * (1.0 - k) + k);
+    let ggx_l = n_dot_l / (n_dot_l * (1.0 - k) + k);
+    return ggx_v * ggx_l;
+}
+
+fn fresnel_schlick(cos_theta: f32, f0: vec3<f32>) -> vec3<f32> {
+    return f0 + (1.0 - f0) * pow(clamp(1.0 - cos_theta, 0.0, 1.0), 5.0);
+}
+
+fn fresnel_schlick_roughness(cos_theta: f32, f0: vec3<f32>, roughness: f32) -> vec3<f32> {
+    let smooth_f0 = max(vec3<f32>(1.0 - roughness), f0);
+    return f0 + (smooth_f0 - f0) * pow(clamp(1.0 - cos_theta, 0.0, 1.0), 5.0);
+}
+
+struct Surface {
+    albedo: vec3<f32>,
+    normal: vec3<f32>,
+    view: vec3<f32>,
+    metallic: f32,
+    roughness: f32,
+    f0: vec3<f32>,
+}
+
+// the cook torrance brdf for light arriving from `direction`, scaled by the incoming `radiance`
+fn direct_light(surface: Surface, direction: vec3<f32>, radiance: vec3<f32>) -> vec3<f32> {
+    let half_dir = normalize(surface.view + direction);
+    let n_dot_l = max(dot(surface.normal, direction), 0.0);
+    let n_dot_v = max(dot(surface.normal, surface.view), 0.0001);
+    let n_dot_h = max(dot(surface.normal, half_dir), 0.0);
+
+    let d = distribution_ggx(n_dot_h, surface.roughness);
+    let g = geometry_smith(n_dot_v, n_dot_l, surface.roughness);
+    let f = fresnel_schlick(max(dot(half_dir, surface.view), 0.0), surface.f0);
+
+    let specular = d * g * f / (4.0 * n_dot_v * n_dot_l + 0.0001);
+    let diffuse = (1.0 - f) * (1.0 - surface.metallic) * surface.albedo / PI;
+    return (diffuse + specular) * radiance * n_dot_l;
+}
+
+// finds the shadow map that covers `position` and samples it
+fn shadow_factor(light: TaroLight, position: vec3<f32>, normal: vec3<f32>, view_depth: f32) -> f32 {
+    if light.shadow_index < 0 {
+        return 1.0;
+    }
+
+    var layer = light.shadow_index;
+    if light.kind == TARO_DIRECTIONAL_LIGHT {
+        let last = light.shadow_index + i32(light.shadow_count) - 1;
+        loop {
+            if layer >= last || view_depth <= shadows.maps[layer].split {
+                break;
+            }
+            layer += 1;
+        }
+
+        if view_depth > shadows.maps[layer].split {
+            return 1.0;
+        }
+    } else if light.kind == TARO_POINT_LIGHT {
+        layer += taro_cube_face(position - light.position);
+    }
+
+    let map = shadows.maps[layer];
+    return taro_sample_shadow(map, layer, shadows.resolution, shadow_maps, shadow_sampler, position, normal);
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let albedo = textureSample(albedo_map, material_sampler, in.tex_coords) * factors.albedo;
+    let normal_sample = textureSample(normal_map, material_sampler, in.tex_coords).xyz;
+    let metallic_roughness = textureSample(metallic_roughness_map, material_sampler, in.tex_coords);
+    let occlusion_sample = textureSample(occlusion_map, material_sampler, in.tex_coords).r;
+    let emissive = textureSample(emissive_map, material_sampler, in.tex_coords).rgb * factors.emissive.rgb;
+
+    // metallic and roughness are stored in the blue and green channels, matching gltf
+    var surface: Surface;
+    surface.albedo = albedo.rgb;
+    surface.metallic = clamp(metallic_roughness.b * factors.metallic, 0.0, 1.0);
+    surface.roughness = clamp(metallic_roughness.g * factors.roughness, 0.04, 1.0);
+    surface.f0 = mix(vec3<f32>(0.04), surface.albedo, surface.metallic);
+    surface.view = normalize(scene.camera_position.xyz - in.world_position);
+
+    let tangent_normal = (normal_sample * 2.0 - 1.0) * vec3<f32>(factors.normal_scale, factors.normal_scale, 1.0);
+    let geometry_normal = normalize(in.world_normal);
+    surface.normal = perturb_normal(geometry_normal, in.world_tangent, tangent_normal);
+
+    let occlusion = mix(1.0, occlusion_sample, factors.occlusion_strength);
+    let n_dot_v = max(dot(surface.normal, surface.view), 0.0);
+    let ambient_specular = fresnel_schlick_roughness(n_dot_v, surface.f0, surface.roughness);
+    let ambient_diffuse = (1.0 - ambient_specular) * (1.0 - surface.metallic) * surface.albedo;
+    let ambient = (ambient_diffuse + ambient_specular) * scene.ambient.rgb * occlusion;
+
+    let view_depth = dot(in.world_position - scene.camera_position.xyz, scene.camera_forward.xyz);
+    var direct = vec3<f32>(0.0);
+    let count = min(lights.count, 64u);
+    for (var i = 0u; i < count; i++) {
+        let light = lights.lights[i];
+        let sample = taro_sample_light(light, in.world_position);
+        let shadow = shadow_factor(light, in.world_position, geometry_normal, view_depth);
+        direct += direct_light(surface, sample.direction, sample.radiance) * shadow;
+    }
+
+    return vec4<f32>(direct + ambient + emissive, albedo.a);
+}
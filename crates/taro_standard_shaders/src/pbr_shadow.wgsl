@@ -0,0 +1,9 @@
+@group(0) @binding(0)
+var<uniform> light_matrix: mat4x4<f32>;
+@group(1) @binding(0)
+var<uniform> model_matrix: mat4x4<f32>;
+
+@vertex
+fn vs_main(@location(0) position: vec3<f32>) -> @builtin(position) vec4<f32> {
+    return light_matrix * model_matrix * vec4<f32>(position, 1.0);
+}
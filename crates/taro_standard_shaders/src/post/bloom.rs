@@ -0,0 +1,189 @@
+use bytemuck::{Pod, Zeroable};
+use taro_renderer::{
+    post::{TaroFullscreenPass, TaroFullscreenTarget, TaroPostContext, TaroPostEffect},
+    wgpu, HardwareId, TaroAttachment, TaroHardware,
+};
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct BloomParams {
+    threshold: f32,
+    knee: f32,
+    _padding: [f32; 2],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct BlurParams {
+    direction: [f32; 2],
+    _padding: [f32; 2],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct CompositeParams {
+    intensity: f32,
+    _padding: [f32; 3],
+}
+
+/// Makes bright parts of the scene glow into their surroundings.
+///
+/// The bright parts are extracted and blurred at half resolution, then added back onto the scene.
+/// This works on high dynamic range colors, so it should run before tonemapping.
+pub struct Bloom {
+    /// The brightness above which pixels start to glow
+    pub threshold: f32,
+    /// The range below the threshold over which the glow fades in
+    pub knee: f32,
+    /// How strongly the glow is added back onto the scene
+    pub intensity: f32,
+    /// The distance in half resolution pixels between blur samples
+    pub spread: f32,
+    prefilter: TaroFullscreenPass,
+    blur_horizontal: TaroFullscreenPass,
+    blur_vertical: TaroFullscreenPass,
+    composite: TaroFullscreenPass,
+    textures: Option<BloomTextures>,
+}
+
+struct BloomTextures {
+    hardware: HardwareId,
+    size: (u32, u32),
+    first: wgpu::TextureView,
+    second: wgpu::TextureView,
+}
+
+impl Default for Bloom {
+    fn default() -> Self {
+        let blur = include_str!("bloom_blur.wgsl");
+        Self {
+            threshold: 1.,
+            knee: 0.5,
+            intensity: 0.8,
+            spread: 1.,
+            prefilter: TaroFullscreenPass::new(
+                "Bloom Prefilter",
+                include_str!("bloom_prefilter.wgsl"),
+            ),
+            blur_horizontal: TaroFullscreenPass::new("Bloom Horizontal Blur", blur),
+            blur_vertical: TaroFullscreenPass::new("Bloom Vertical Blur", blur),
+            composite: TaroFullscreenPass::new(
+                "Bloom Composite",
+                include_str!("bloom_composite.wgsl"),
+            )
+            .with_textures(2),
+            textures: None,
+        }
+    }
+}
+
+impl Bloom {
+    /// Reallocates the half resolution textures when the target size or hardware changes
+    fn allocate(&mut self, size: (u32, u32), hardware: &TaroHardware) {
+        let size = ((size.0 / 2).max(1), (size.1 / 2).max(1));
+        if !matches!(&self.textures, Some(t) if t.hardware == *hardware.id() && t.size == size) {
+            let create = || {
+                hardware
+                    .device()
+                    .create_texture(&wgpu::TextureDescriptor {
+                        label: Some("Bloom Texture"),
+                        size: wgpu::Extent3d {
+                            width: size.0,
+                            height: size.1,
+                            depth_or_array_layers: 1,
+                        },
+                        mip_level_count: 1,
+                        sample_count: 1,
+                        dimension: wgpu::TextureDimension::D2,
+                        format: TaroAttachment::COLOR_FORMAT,
+                        usage: wgpu::TextureUsages::TEXTURE_BINDING
+                            | wgpu::TextureUsages::RENDER_ATTACHMENT,
+                    })
+                    .create_view(&wgpu::TextureViewDescriptor::default())
+            };
+
+            self.textures = Some(BloomTextures {
+                hardware: *hardware.id(),
+                size,
+                first: create(),
+                second: create(),
+            });
+        }
+    }
+}
+
+impl TaroPostEffect for Bloom {
+    fn render(&mut self, context: &mut TaroPostContext) {
+        let params = BloomParams {
+            threshold: self.threshold,
+            knee: self.knee.max(0.0001),
+            _padding: Default::default(),
+        };
+        let horizontal = BlurParams {
+            direction: [
+                self.spread / (context.region.target_size.0 / 2).max(1) as f32,
+                0.,
+            ],
+            _padding: Default::default(),
+        };
+        let vertical = BlurParams {
+            direction: [
+                0.,
+                self.spread / (context.region.target_size.1 / 2).max(1) as f32,
+            ],
+            _padding: Default::default(),
+        };
+        let composite = CompositeParams {
+            intensity: self.intensity,
+            _padding: Default::default(),
+        };
+
+        self.allocate(context.region.target_size, context.hardware);
+        let Some(textures) = &self.textures else {
+            return;
+        };
+        let half_target = |view| TaroFullscreenTarget {
+            view,
+            format: TaroAttachment::COLOR_FORMAT,
+            load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+            scissor: None,
+        };
+
+        let (encoder, hardware) = (&mut *context.encoder, context.hardware);
+        self.prefilter.draw(
+            encoder,
+            hardware,
+            &[context.source],
+            half_target(&textures.first),
+            bytemuck::bytes_of(&params),
+        );
+        self.blur_horizontal.draw(
+            encoder,
+            hardware,
+            &[&textures.first],
+            half_target(&textures.second),
+            bytemuck::bytes_of(&horizontal),
+        );
+        self.blur_vertical.draw(
+            encoder,
+            hardware,
+            &[&textures.second],
+            half_target(&textures.first),
+            bytemuck::bytes_of(&vertical),
+        );
+
+        let target = TaroFullscreenTarget {
+            view: context.destination,
+            format: TaroAttachment::COLOR_FORMAT,
+            load: wgpu::LoadOp::Load,
+            scissor: Some(context.region.scissor),
+        };
+        self.composite.draw(
+            encoder,
+            hardware,
+            &[context.source, &textures.first],
+            target,
+            bytemuck::bytes_of(&composite),
+        );
+    }
+}
@@ -0,0 +1,22 @@
+struct BlurParams {
+    direction: vec2<f32>,
+    _padding0: f32,
+    _padding1: f32,
+}
+
+@group(0) @binding(1)
+var<uniform> params: BlurParams;
+
+@fragment
+fn fs_main(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    var weights = array<f32, 5>(0.227027, 0.1945946, 0.1216216, 0.054054, 0.016216);
+
+    var color = textureSampleLevel(source, source_sampler, in.uv, 0.0).rgb * weights[0];
+    for (var i = 1; i < 5; i++) {
+        let offset = params.direction * f32(i);
+        color += textureSampleLevel(source, source_sampler, in.uv + offset, 0.0).rgb * weights[i];
+        color += textureSampleLevel(source, source_sampler, in.uv - offset, 0.0).rgb * weights[i];
+    }
+
+    return vec4<f32>(color, 1.0);
+}
@@ -0,0 +1,18 @@
+struct CompositeParams {
+    intensity: f32,
+    _padding0: f32,
+    _padding1: f32,
+    _padding2: f32,
+}
+
+@group(0) @binding(1)
+var<uniform> params: CompositeParams;
+@group(0) @binding(3)
+var bloom: texture_2d<f32>;
+
+@fragment
+fn fs_main(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    let color = textureSampleLevel(source, source_sampler, in.uv, 0.0);
+    let glow = textureSampleLevel(bloom, source_sampler, in.uv, 0.0).rgb;
+    return vec4<f32>(color.rgb + glow * params.intensity, color.a);
+}
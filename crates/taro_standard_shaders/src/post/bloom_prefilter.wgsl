@@ -0,0 +1,23 @@
+struct BloomParams {
+    threshold: f32,
+    knee: f32,
+    _padding0: f32,
+    _padding1: f32,
+}
+
+@group(0) @binding(1)
+var<uniform> params: BloomParams;
+
+@fragment
+fn fs_main(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    // sampling between texels averages the four covered pixels of the full size source
+    let color = textureSampleLevel(source, source_sampler, in.uv, 0.0).rgb;
+
+    // soft threshold with a quadratic knee
+    let brightness = max(color.r, max(color.g, color.b));
+    var soft = clamp(brightness - params.threshold + params.knee, 0.0, 2.0 * params.knee);
+    soft = soft * soft / (4.0 * params.knee + 0.0001);
+    let contribution = max(soft, brightness - params.threshold) / max(brightness, 0.0001);
+
+    return vec4<f32>(color * contribution, 1.0);
+}
@@ -0,0 +1,49 @@
+use bytemuck::{Pod, Zeroable};
+use taro_renderer::post::{TaroFullscreenPass, TaroPostContext, TaroPostEffect};
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct ColorGradingParams {
+    tint: [f32; 4],
+    exposure: f32,
+    contrast: f32,
+    saturation: f32,
+    _padding: f32,
+}
+
+/// Adjusts the exposure, contrast, saturation and tint of the scene.
+///
+/// This works on linear colors, so it should run before tonemapping.
+pub struct ColorGrading {
+    /// The exposure adjustment in stops
+    pub exposure: f32,
+    pub contrast: f32,
+    pub saturation: f32,
+    pub tint: [f32; 3],
+    pass: TaroFullscreenPass,
+}
+
+impl Default for ColorGrading {
+    fn default() -> Self {
+        Self {
+            exposure: 0.,
+            contrast: 1.,
+            saturation: 1.,
+            tint: [1., 1., 1.],
+            pass: TaroFullscreenPass::new("Color Grading", include_str!("color_grading.wgsl")),
+        }
+    }
+}
+
+impl TaroPostEffect for ColorGrading {
+    fn render(&mut self, context: &mut TaroPostContext) {
+        let params = ColorGradingParams {
+            tint: [self.tint[0], self.tint[1], self.tint[2], 1.],
+            exposure: self.exposure,
+            contrast: self.contrast,
+            saturation: self.saturation,
+            _padding: 0.,
+        };
+        context.draw(&mut self.pass, bytemuck::bytes_of(&params));
+    }
+}
@@ -0,0 +1,25 @@
+struct ColorGradingParams {
+    tint: vec4<f32>,
+    exposure: f32,
+    contrast: f32,
+    saturation: f32,
+    _padding: f32,
+}
+
+@group(0) @binding(1)
+var<uniform> params: ColorGradingParams;
+
+@fragment
+fn fs_main(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    let color = textureSampleLevel(source, source_sampler, in.uv, 0.0);
+
+    var graded = color.rgb * exp2(params.exposure) * params.tint.rgb;
+
+    // contrast pivots around middle grey in linear space
+    graded = (graded - vec3<f32>(0.18)) * params.contrast + vec3<f32>(0.18);
+
+    let luminance = dot(graded, vec3<f32>(0.2126, 0.7152, 0.0722));
+    graded = mix(vec3<f32>(luminance), graded, params.saturation);
+
+    return vec4<f32>(max(graded, vec3<f32>(0.0)), color.a);
+}
@@ -0,0 +1,50 @@
+use bytemuck::{Pod, Zeroable};
+use taro_renderer::post::{TaroFullscreenPass, TaroPostContext, TaroPostEffect};
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct FxaaParams {
+    edge_threshold: f32,
+    edge_threshold_min: f32,
+    reduce_multiplier: f32,
+    span_max: f32,
+}
+
+/// Fast approximate anti-aliasing.
+///
+/// This should run after tonemapping, as it works on the perceived brightness of the image.
+pub struct Fxaa {
+    /// The contrast relative to the brightest neighbour needed to detect an edge
+    pub edge_threshold: f32,
+    /// The minimum contrast needed to detect an edge, which skips dark areas
+    pub edge_threshold_min: f32,
+    /// How much the blur is reduced along short edges
+    pub reduce_multiplier: f32,
+    /// The maximum distance in pixels to blur along an edge
+    pub span_max: f32,
+    pass: TaroFullscreenPass,
+}
+
+impl Default for Fxaa {
+    fn default() -> Self {
+        Self {
+            edge_threshold: 0.125,
+            edge_threshold_min: 0.0312,
+            reduce_multiplier: 0.125,
+            span_max: 8.,
+            pass: TaroFullscreenPass::new("FXAA", include_str!("fxaa.wgsl")),
+        }
+    }
+}
+
+impl TaroPostEffect for Fxaa {
+    fn render(&mut self, context: &mut TaroPostContext) {
+        let params = FxaaParams {
+            edge_threshold: self.edge_threshold,
+            edge_threshold_min: self.edge_threshold_min,
+            reduce_multiplier: self.reduce_multiplier,
+            span_max: self.span_max,
+        };
+        context.draw(&mut self.pass, bytemuck::bytes_of(&params));
+    }
+}
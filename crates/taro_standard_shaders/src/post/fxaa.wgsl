@@ -0,0 +1,65 @@
+struct FxaaParams {
+    edge_threshold: f32,
+    edge_threshold_min: f32,
+    reduce_multiplier: f32,
+    span_max: f32,
+}
+
+@group(0) @binding(1)
+var<uniform> params: FxaaParams;
+
+fn luma(color: vec3<f32>) -> f32 {
+    return dot(color, vec3<f32>(0.299, 0.587, 0.114));
+}
+
+fn sample_rgb(uv: vec2<f32>) -> vec3<f32> {
+    return textureSampleLevel(source, source_sampler, uv, 0.0).rgb;
+}
+
+@fragment
+fn fs_main(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    let texel = 1.0 / vec2<f32>(textureDimensions(source));
+    let center = textureSampleLevel(source, source_sampler, in.uv, 0.0);
+
+    let luma_m = luma(center.rgb);
+    let luma_nw = luma(sample_rgb(in.uv + vec2<f32>(-1.0, -1.0) * texel));
+    let luma_ne = luma(sample_rgb(in.uv + vec2<f32>(1.0, -1.0) * texel));
+    let luma_sw = luma(sample_rgb(in.uv + vec2<f32>(-1.0, 1.0) * texel));
+    let luma_se = luma(sample_rgb(in.uv + vec2<f32>(1.0, 1.0) * texel));
+
+    let luma_min = min(luma_m, min(min(luma_nw, luma_ne), min(luma_sw, luma_se)));
+    let luma_max = max(luma_m, max(max(luma_nw, luma_ne), max(luma_sw, luma_se)));
+
+    // skip pixels that are not on an edge
+    let threshold = max(params.edge_threshold_min, luma_max * params.edge_threshold);
+    if luma_max - luma_min < threshold {
+        return center;
+    }
+
+    // find the direction of the edge
+    var dir = vec2<f32>(
+        -((luma_nw + luma_ne) - (luma_sw + luma_se)),
+        (luma_nw + luma_sw) - (luma_ne + luma_se),
+    );
+    let luma_sum = luma_nw + luma_ne + luma_sw + luma_se;
+    let dir_reduce = max(luma_sum * 0.25 * params.reduce_multiplier, 1.0 / 128.0);
+    let dir_scale = 1.0 / (min(abs(dir.x), abs(dir.y)) + dir_reduce);
+    dir = clamp(dir * dir_scale, vec2<f32>(-params.span_max), vec2<f32>(params.span_max)) * texel;
+
+    // blend along the edge
+    let near = 0.5 * (
+        sample_rgb(in.uv + dir * (1.0 / 3.0 - 0.5)) +
+        sample_rgb(in.uv + dir * (2.0 / 3.0 - 0.5))
+    );
+    let far = near * 0.5 + 0.25 * (
+        sample_rgb(in.uv - dir * 0.5) +
+        sample_rgb(in.uv + dir * 0.5)
+    );
+
+    let luma_far = luma(far);
+    if luma_far < luma_min || luma_far > luma_max {
+        return vec4<f32>(near, center.a);
+    }
+
+    return vec4<f32>(far, center.a);
+}
@@ -0,0 +1,43 @@
+use bytemuck::{Pod, Zeroable};
+use taro_renderer::post::{TaroFullscreenPass, TaroPostContext, TaroPostEffect};
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct GammaParams {
+    gamma: f32,
+    _padding: [f32; 3],
+}
+
+/// Raises the color to the power of `1 / gamma`.
+///
+/// Surfaces with an srgb format already encode their output,
+/// so this is only needed for linear targets or as an artistic adjustment.
+pub struct GammaCorrection {
+    pub gamma: f32,
+    pass: TaroFullscreenPass,
+}
+
+impl Default for GammaCorrection {
+    fn default() -> Self {
+        Self::new(2.2)
+    }
+}
+
+impl GammaCorrection {
+    pub fn new(gamma: f32) -> Self {
+        Self {
+            gamma,
+            pass: TaroFullscreenPass::new("Gamma Correction", include_str!("gamma.wgsl")),
+        }
+    }
+}
+
+impl TaroPostEffect for GammaCorrection {
+    fn render(&mut self, context: &mut TaroPostContext) {
+        let params = GammaParams {
+            gamma: self.gamma.max(0.0001),
+            _padding: Default::default(),
+        };
+        context.draw(&mut self.pass, bytemuck::bytes_of(&params));
+    }
+}
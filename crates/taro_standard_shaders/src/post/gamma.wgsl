@@ -0,0 +1,16 @@
+struct GammaParams {
+    gamma: f32,
+    _padding0: f32,
+    _padding1: f32,
+    _padding2: f32,
+}
+
+@group(0) @binding(1)
+var<uniform> params: GammaParams;
+
+@fragment
+fn fs_main(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    let color = textureSampleLevel(source, source_sampler, in.uv, 0.0);
+    let corrected = pow(max(color.rgb, vec3<f32>(0.0)), vec3<f32>(1.0 / params.gamma));
+    return vec4<f32>(corrected, color.a);
+}
@@ -0,0 +1,13 @@
+mod bloom;
+mod color_grading;
+mod fxaa;
+mod gamma;
+mod tonemapping;
+mod vignette;
+
+pub use bloom::*;
+pub use color_grading::*;
+pub use fxaa::*;
+pub use gamma::*;
+pub use tonemapping::*;
+pub use vignette::*;
@@ -0,0 +1,59 @@
+use bytemuck::{Pod, Zeroable};
+use taro_renderer::post::{TaroFullscreenPass, TaroPostContext, TaroPostEffect};
+
+/// The curve used to map high dynamic range colors into the displayable range
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TonemapOperator {
+    Reinhard,
+    /// Reinhard with a luminance that is mapped to pure white
+    ReinhardExtended {
+        white: f32,
+    },
+    /// An approximation of the ACES filmic curve
+    Aces,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct TonemapParams {
+    mode: u32,
+    white: f32,
+    _padding: [f32; 2],
+}
+
+pub struct Tonemapping {
+    pub operator: TonemapOperator,
+    pass: TaroFullscreenPass,
+}
+
+impl Default for Tonemapping {
+    fn default() -> Self {
+        Self::new(TonemapOperator::Aces)
+    }
+}
+
+impl Tonemapping {
+    pub fn new(operator: TonemapOperator) -> Self {
+        Self {
+            operator,
+            pass: TaroFullscreenPass::new("Tonemapping", include_str!("tonemapping.wgsl")),
+        }
+    }
+}
+
+impl TaroPostEffect for Tonemapping {
+    fn render(&mut self, context: &mut TaroPostContext) {
+        let (mode, white) = match self.operator {
+            TonemapOperator::Reinhard => (0, 1.),
+            TonemapOperator::ReinhardExtended { white } => (1, white),
+            TonemapOperator::Aces => (2, 1.),
+        };
+
+        let params = TonemapParams {
+            mode,
+            white,
+            _padding: Default::default(),
+        };
+        context.draw(&mut self.pass, bytemuck::bytes_of(&params));
+    }
+}
@@ -0,0 +1,50 @@
+struct TonemapParams {
+    mode: u32,
+    white: f32,
+    _padding0: f32,
+    _padding1: f32,
+}
+
+@group(0) @binding(1)
+var<uniform> params: TonemapParams;
+
+fn luminance(color: vec3<f32>) -> f32 {
+    return dot(color, vec3<f32>(0.2126, 0.7152, 0.0722));
+}
+
+fn reinhard(color: vec3<f32>) -> vec3<f32> {
+    return color / (vec3<f32>(1.0) + color);
+}
+
+fn reinhard_extended(color: vec3<f32>, white: f32) -> vec3<f32> {
+    let l = luminance(color);
+    let mapped = l * (1.0 + l / (white * white)) / (1.0 + l);
+    return color * (mapped / max(l, 0.0001));
+}
+
+// Krzysztof Narkowicz's fit of the ACES filmic curve
+fn aces(color: vec3<f32>) -> vec3<f32> {
+    let a = color * (2.51 * color + vec3<f32>(0.03));
+    let b = color * (2.43 * color + vec3<f32>(0.59)) + vec3<f32>(0.14);
+    return clamp(a / b, vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+@fragment
+fn fs_main(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    let color = textureSampleLevel(source, source_sampler, in.uv, 0.0);
+
+    var mapped = color.rgb;
+    switch params.mode {
+        case 0u: {
+            mapped = reinhard(color.rgb);
+        }
+        case 1u: {
+            mapped = reinhard_extended(color.rgb, params.white);
+        }
+        default: {
+            mapped = aces(color.rgb);
+        }
+    }
+
+    return vec4<f32>(mapped, color.a);
+}
@@ -0,0 +1,58 @@
+use bytemuck::{Pod, Zeroable};
+use taro_renderer::post::{TaroFullscreenPass, TaroPostContext, TaroPostEffect};
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct VignetteParams {
+    color: [f32; 4],
+    viewport: [f32; 4],
+    intensity: f32,
+    radius: f32,
+    smoothness: f32,
+    _padding: f32,
+}
+
+/// Darkens the edges of the camera viewport
+pub struct Vignette {
+    pub color: [f32; 3],
+    /// How strongly the edges are tinted, from 0 to 1
+    pub intensity: f32,
+    /// The distance from the center where the vignette starts, where 1 is the edge of the viewport
+    pub radius: f32,
+    /// The distance over which the vignette fades in
+    pub smoothness: f32,
+    pass: TaroFullscreenPass,
+}
+
+impl Default for Vignette {
+    fn default() -> Self {
+        Self {
+            color: [0., 0., 0.],
+            intensity: 0.5,
+            radius: 0.75,
+            smoothness: 0.6,
+            pass: TaroFullscreenPass::new("Vignette", include_str!("vignette.wgsl")),
+        }
+    }
+}
+
+impl TaroPostEffect for Vignette {
+    fn render(&mut self, context: &mut TaroPostContext) {
+        let size = context.region.target_size;
+        let viewport = context.region.viewport;
+        let params = VignetteParams {
+            color: [self.color[0], self.color[1], self.color[2], 1.],
+            viewport: [
+                viewport.x as f32 / size.0 as f32,
+                viewport.y as f32 / size.1 as f32,
+                viewport.width.max(1) as f32 / size.0 as f32,
+                viewport.height.max(1) as f32 / size.1 as f32,
+            ],
+            intensity: self.intensity,
+            radius: self.radius,
+            smoothness: self.smoothness,
+            _padding: 0.,
+        };
+        context.draw(&mut self.pass, bytemuck::bytes_of(&params));
+    }
+}
@@ -0,0 +1,24 @@
+struct VignetteParams {
+    color: vec4<f32>,
+    viewport: vec4<f32>,
+    intensity: f32,
+    radius: f32,
+    smoothness: f32,
+    _padding: f32,
+}
+
+@group(0) @binding(1)
+var<uniform> params: VignetteParams;
+
+@fragment
+fn fs_main(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    let color = textureSampleLevel(source, source_sampler, in.uv, 0.0);
+
+    // measure the distance from the center of the camera viewport
+    let local = (in.uv - params.viewport.xy) / params.viewport.zw;
+    let distance = length((local - vec2<f32>(0.5)) * 2.0);
+    let edge = params.radius + max(params.smoothness, 0.0001);
+    let factor = smoothstep(params.radius, edge, distance) * params.intensity;
+
+    return vec4<f32>(mix(color.rgb, params.color.rgb, factor), color.a);
+}
@@ -1,11 +1,13 @@
+use std::ops::Range;
+
 use once_cell::sync::OnceCell;
 use taro_renderer::{
     shading::{
-        buffers::{CameraMatrix, TransformMatrix, UniformBuffer},
+        buffers::{TransformMatrix, UniformBuffer},
         data_types::{DepthView, MeshBuffer, Sampler, Texture2DView, Vertex},
         Bind, BindGroup, BindGroupBuilder, BindSingle, Taro, TaroCoreShader, TaroMeshShader,
     },
-    wgpu, TaroHardware,
+    wgpu, TaroAttachment, TaroCameraView, TaroHardware,
 };
 
 static PIPELINE: OnceCell<wgpu::RenderPipeline> = OnceCell::new();
@@ -24,7 +26,6 @@ impl UnlitShaderInit {
 
 pub struct UnlitShader {
     pub texture: Taro<BindGroup>,
-    pub camera_matrix: Taro<BindSingle<UniformBuffer<CameraMatrix>>>,
     pub model_matrix: Taro<BindSingle<UniformBuffer<TransformMatrix>>>,
     _private: (),
 }
@@ -95,7 +96,7 @@ impl TaroCoreShader for UnlitShader {
                         module,
                         entry_point: "fs_main",
                         targets: &[Some(wgpu::ColorTargetState {
-                            format: wgpu::TextureFormat::Bgra8UnormSrgb,
+                            format: TaroAttachment::COLOR_FORMAT,
                             blend: Some(wgpu::BlendState::REPLACE),
                             write_mask: wgpu::ColorWrites::ALL,
                         })],
@@ -127,7 +128,6 @@ impl TaroCoreShader for UnlitShader {
 
         Self {
             texture,
-            camera_matrix: Bind::new(UniformBuffer::new(), wgpu::ShaderStages::VERTEX),
             model_matrix: Bind::new(UniformBuffer::new(), wgpu::ShaderStages::VERTEX),
             _private: (),
         }
@@ -135,10 +135,6 @@ impl TaroCoreShader for UnlitShader {
 }
 
 impl TaroMeshShader for UnlitShader {
-    fn set_camera_matrix(&self, data: &CameraMatrix, hardware: &TaroHardware) {
-        self.camera_matrix.data().write_buffer(data, hardware);
-    }
-
     fn set_model_matrix(&self, data: &TransformMatrix, hardware: &TaroHardware) {
         self.model_matrix.data().write_buffer(data, hardware);
     }
@@ -147,9 +143,11 @@ impl TaroMeshShader for UnlitShader {
         &'pass self,
         pass: &mut wgpu::RenderPass<'pass>,
         mesh: &'pass MeshBuffer,
+        indices: Range<u32>,
+        camera: &'pass TaroCameraView,
         hardware: &TaroHardware,
     ) {
-        let camera_bind = self.camera_matrix.get_or_compile(hardware);
+        let camera_bind = camera.binding.get_or_compile(hardware);
         let model_bind = self.model_matrix.get_or_compile(hardware);
         let texture_bind = self.texture.get_or_compile(hardware);
 
@@ -158,10 +156,8 @@ impl TaroMeshShader for UnlitShader {
         pass.set_bind_group(1, &model_bind.bind_group, &[]);
         pass.set_bind_group(2, &texture_bind.bind_group, &[]);
         pass.set_vertex_buffer(0, mesh.vertex_buffer().raw_buffer().slice(..));
-        pass.set_index_buffer(
-            mesh.index_buffer().raw_buffer().slice(..),
-            wgpu::IndexFormat::Uint16,
-        );
-        pass.draw_indexed(0..mesh.index_buffer().len(), 0, 0..1);
+        let index_buffer = mesh.index_buffer();
+        pass.set_index_buffer(index_buffer.raw_buffer().slice(..), index_buffer.format());
+        pass.draw_indexed(indices, 0, 0..1);
     }
 }
@@ -0,0 +1,23 @@
+use boba::prelude::*;
+use milk_tea::MilkTeaWindowSettings;
+
+fn main() {
+    // create app with an extra window
+    let mut app = Bobarista::<TaroMilkTea>::default();
+    let inspector = app.add_window(MilkTeaWindowSettings::new("Inspector").with_size(400, 300));
+
+    // create one camera for each window
+    let settings = TaroCameraSettings::perspective(60.0, 0.1, 100.0);
+    let main_camera = TaroCamera::new_simple(BobaTransform::default(), settings.clone());
+    let mut inspector_camera = TaroCamera::new_simple(BobaTransform::default(), settings);
+    inspector_camera.target = TaroMilkTea::surface_id(inspector).into();
+
+    // create TaroCameras resource and add it
+    let mut cameras = TaroCameras::default();
+    cameras.cameras.push(main_camera);
+    cameras.cameras.push(inspector_camera);
+    app.resources.add(cameras);
+
+    // run the app
+    app.run().unwrap();
+}
@@ -1,14 +1,17 @@
 use boba::prelude::*;
 use milk_tea::{
-    winit::event::{ElementState, KeyboardInput, VirtualKeyCode},
-    MilkTeaEvent,
+    input::{AxisBinding, MilkTeaInput},
+    winit::event::VirtualKeyCode,
 };
 use std::{f32::consts::PI, fs::File};
-use taro_standard_shaders::{passes::UnlitRenderPass, UnlitShader, UnlitShaderInit};
+use taro_standard_shaders::{
+    passes::UnlitRenderPass,
+    post::{Fxaa, Tonemapping},
+    UnlitShader, UnlitShaderInit,
+};
 
 pub struct Rotator {
     current_rot: f32,
-    rotate_direction: f32,
 
     pub transform: Pearl<BobaTransform>,
     pub speed: f32,
@@ -18,37 +21,20 @@ impl Rotator {
     pub fn new(transform: Pearl<BobaTransform>, speed: f32) -> Self {
         Self {
             current_rot: 0.,
-            rotate_direction: 0.,
             transform,
             speed,
         }
     }
 }
 
-register_pearl_stages!(Rotator: BobaUpdate, MilkTeaEvent<KeyboardInput>);
-
-impl PearlStage<MilkTeaEvent<KeyboardInput>> for Rotator {
-    fn update(&mut self, data: &KeyboardInput, _: &mut BobaResources) -> BobaResult {
-        let rotate_direction = match &data.virtual_keycode {
-            Some(VirtualKeyCode::Right) => 1.,
-            Some(VirtualKeyCode::Left) => -1.,
-            _ => 0.,
-        };
-
-        match data.state {
-            ElementState::Pressed => self.rotate_direction = rotate_direction,
-            ElementState::Released => self.rotate_direction = 0.,
-        }
-
-        Ok(())
-    }
-}
+register_pearl_stages!(Rotator: BobaUpdate);
 
 impl PearlStage<BobaUpdate> for Rotator {
-    fn update(&mut self, delta: &f32, _resources: &mut BobaResources) -> BobaResult {
+    fn update(&mut self, delta: &f32, resources: &mut BobaResources) -> BobaResult {
+        let rotate_direction = resources.get::<MilkTeaInput>()?.axis("rotate");
         let mut transform = self.transform.borrow_mut()?;
 
-        self.current_rot += self.speed * self.rotate_direction * delta;
+        self.current_rot += self.speed * rotate_direction * delta;
         self.current_rot %= 2. * PI;
 
         transform.set_local_rotation(Quat::from_axis_angle(Vec3::Y, self.current_rot));
@@ -79,14 +65,14 @@ fn main() {
     );
 
     // create another mesh to be rendered
-    let mut renderer2 = TaroMeshRenderer::new_simple(
+    let renderer2 = TaroMeshRenderer::new_simple(
         BobaTransform::from_position_scale(Vec3::X * 1.5, Vec3::ONE * 0.5),
         mesh.clone(),
         shader.clone(),
     );
 
     // create another mesh to be rendered
-    let mut renderer3 = TaroMeshRenderer::new_simple(
+    let renderer3 = TaroMeshRenderer::new_simple(
         BobaTransform::from_position_scale(-Vec3::X * 1.5, Vec3::ONE * 0.5),
         mesh.clone(),
         shader.clone(),
@@ -95,13 +81,23 @@ fn main() {
     // set parents
     renderer2
         .transform
-        .set_parent(renderer.transform.clone())
+        .set_parent(renderer.transform.clone(), false)
         .unwrap();
     renderer3
         .transform
-        .set_parent(renderer.transform.clone())
+        .set_parent(renderer.transform.clone(), false)
         .unwrap();
 
+    // bind the rotate axis to the arrow keys
+    app.resources
+        .get_mut::<MilkTeaInput>()
+        .unwrap()
+        .actions
+        .bind_axis(
+            "rotate",
+            AxisBinding::buttons(VirtualKeyCode::Right, VirtualKeyCode::Left),
+        );
+
     // create a rotator object that links to the renderers transform
     let rotator = Pearl::wrap(Rotator::new(renderer.transform.clone(), 3.));
     app.registry.add(&rotator);
@@ -116,16 +112,17 @@ fn main() {
     // create camera with transform
     let mut camera = TaroCamera::new_simple(
         BobaTransform::from_position_look_at(Vec3::new(0., 1., 2.), Vec3::ZERO),
-        TaroCameraSettings {
-            fovy: 60.0,
-            znear: 0.1,
-            zfar: 100.0,
-        },
+        TaroCameraSettings::perspective(60.0, 0.1, 100.0),
     );
 
     // add unlit render pass for testing
     camera.passes.append(UnlitRenderPass);
 
+    // map the hdr scene color into the surface range and smooth the edges
+    let post_effects = camera.passes.post_effects();
+    post_effects.append(Tonemapping::default());
+    post_effects.append(Fxaa::default());
+
     // create TaroCameras resource and add it
     let mut cameras = TaroCameras::default();
     cameras.cameras.push(camera);
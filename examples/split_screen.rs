@@ -0,0 +1,66 @@
+use boba::prelude::*;
+use std::fs::File;
+use taro_standard_shaders::{passes::UnlitRenderPass, UnlitShader, UnlitShaderInit};
+
+fn main() {
+    // create app
+    let mut app = Bobarista::<TaroMilkTea>::default();
+
+    // create a textured cube to look at
+    let mesh = Mesh::new(File::open("cube.obj").unwrap()).unwrap();
+    let tex_view = Texture2DView::new(include_bytes!("../readme_assets/boba-logo.png")).unwrap();
+    let shader = Shader::<UnlitShader>::new(UnlitShaderInit::new(tex_view, Sampler::new()));
+    let renderer = TaroMeshRenderer::new_simple(BobaTransform::default(), mesh.clone(), shader);
+
+    // create a monitor that shows what a security camera sees
+    let monitor_view = Texture2DView::new_render_target((256, 256));
+    let monitor_init = UnlitShaderInit::new(monitor_view.clone(), Sampler::new());
+    let monitor_shader = Shader::<UnlitShader>::new(monitor_init);
+    let monitor = TaroMeshRenderer::new_simple(
+        BobaTransform::from_position_scale(Vec3::new(2.5, 0., -4.), Vec3::splat(0.5)),
+        mesh,
+        monitor_shader,
+    );
+
+    // create TaroRenderPearls resource and add it
+    let mut render_pearls = TaroRenderPearls::default();
+    render_pearls.add(Pearl::wrap(renderer));
+    render_pearls.add(Pearl::wrap(monitor));
+    app.resources.add(render_pearls);
+
+    // the security camera looks at the cube from behind, and renders into the monitor texture.
+    // the monitor is behind it, as a camera should never see its own target texture.
+    // it is rendered in the same frame as the other cameras, but uses its own matrix
+    let mut security_camera = TaroCamera::new_simple(
+        BobaTransform::from_position_look_at(Vec3::new(0., 0., -3.), Vec3::ZERO),
+        TaroCameraSettings::perspective(60.0, 0.1, 100.0),
+    )
+    .with_target(monitor_view);
+    security_camera.passes.append(UnlitRenderPass);
+
+    // the left half of the window looks at the cube from the front
+    let mut front_camera = TaroCamera::new_simple(
+        BobaTransform::from_position_look_at(Vec3::new(0., 1., 3.), Vec3::ZERO),
+        TaroCameraSettings::perspective(60.0, 0.1, 100.0),
+    )
+    .with_viewport(TaroViewport::new(0., 0., 0.5, 1.));
+    front_camera.passes.append(UnlitRenderPass);
+
+    // the right half looks straight down from above, like a minimap
+    let mut top_camera = TaroCamera::new_simple(
+        BobaTransform::from_position_look_at(Vec3::new(0., 5., 0.), Vec3::ZERO),
+        TaroCameraSettings::orthographic(4.0, 0.1, 100.0),
+    )
+    .with_viewport(TaroViewport::new(0.5, 0., 0.5, 1.));
+    top_camera.passes.append(UnlitRenderPass);
+
+    // create TaroCameras resource and add it
+    let mut cameras = TaroCameras::default();
+    cameras.cameras.push(security_camera);
+    cameras.cameras.push(front_camera);
+    cameras.cameras.push(top_camera);
+    app.resources.add(cameras);
+
+    // run the app
+    app.run().unwrap();
+}
@@ -1,20 +1,25 @@
 pub use boba_core as core;
+#[cfg(feature = "physics")]
+pub use boba_physics as physics;
 pub use milk_tea;
 pub use taro_renderer;
 pub use taro_standard_adapters;
 
 pub mod prelude {
+    pub use boba_3d::animation::AnimationClip;
     pub use boba_3d::glam::*;
     pub use boba_3d::pearls::*;
+    pub use boba_3d::tween::*;
     pub use boba_core::stages::*;
     pub use boba_core::*;
-    pub use milk_tea::Bobarista;
+    pub use milk_tea::{Bobarista, MilkTeaPlugin};
     pub use taro_standard_adapters::milk_tea::TaroMilkTea;
 
     pub use taro_renderer::{
-        pearls::TaroMeshRenderer,
+        pearls::{TaroDirectionalLight, TaroMeshRenderer, TaroPointLight, TaroSpotLight},
         shading::data_types::{Mesh, Sampler, Texture2D, Texture2DView},
         shading::Shader,
-        TaroCamera, TaroCameraSettings, TaroCameras, TaroRenderPearls,
+        TaroCamera, TaroCameraSettings, TaroCameraTarget, TaroCameras, TaroProjection,
+        TaroRenderPearls, TaroViewport,
     };
 }